@@ -1,37 +1,23 @@
-use chrono::{DateTime, Utc};
-use std::env::{current_dir, var};
-use std::path::PathBuf;
-use tauri::command;
+use crate::store::{load_date_settings, DateStyle};
+use chrono::Utc;
+use std::env::var;
+use tauri::{command, AppHandle};
 
+// Both follow the timezone and locale from the date settings
 #[command]
-pub fn cli_help_command(explanation: Option<String>) -> String {
-    if let Some(cmd) = explanation {
-        cmd
-    } else {
-        "help,date,version,pwd,goto,theme dark|light,lang en|fr|de,hide|show navbar,show json-example,add folder,delete folder,update folder,exit".to_string()
-    }
-}
-
-#[command]
-pub fn cli_date_now() -> String {
-    let now: DateTime<Utc> = Utc::now();
-    now.format("%d-%m-%Y %H:%M:%S").to_string()
+pub fn cli_date_now(app: AppHandle) -> String {
+    let settings = load_date_settings(&app).unwrap_or_default();
+    settings.format(Utc::now(), DateStyle::DateTime)
 }
 
 #[command]
-pub fn cli_date_without_hours() -> String {
-    let now: DateTime<Utc> = Utc::now();
-    now.format("%d-%m-%Y").to_string()
+pub fn cli_date_without_hours(app: AppHandle) -> String {
+    let settings = load_date_settings(&app).unwrap_or_default();
+    settings.format(Utc::now(), DateStyle::Date)
 }
 
 #[command]
-pub fn cli_show_dir() -> PathBuf {
-    let path = current_dir().unwrap();
-    path
-}
-
-#[command]
-pub fn cli_design() -> String {
+pub fn cli_design(cwd: Option<String>) -> String {
     let user = if cfg!(windows) {
         var("USERNAME")
     } else {
@@ -39,6 +25,9 @@ pub fn cli_design() -> String {
     }
     .unwrap_or_else(|_| String::from("Unknown"));
 
-    let prompt = format!("{user}@fenris$ ");
-    prompt
+    // The shell's working folder, e.g. user@fenris:/Work$
+    match cwd {
+        Some(cwd) => format!("{user}@fenris:{cwd}$ "),
+        None => format!("{user}@fenris$ "),
+    }
 }
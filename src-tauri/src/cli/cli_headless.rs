@@ -0,0 +1,690 @@
+use crate::cli::shell_notes::{
+    add_folder, all_folders, all_notes, append_to_note, create_note, delete_folder, delete_note,
+    folder_tree, list_folder, listing_table, move_folder, move_note, note_content, notes_table,
+    notes_with_tag, rename_folder, rename_note, resolve_folder, resolve_note, resolve_path,
+    search_notes, FolderRow, NoteRow,
+};
+use crate::cli::shell_registry::{help_for, parse_command, ParsedCommand, CLI_COMMANDS};
+use crate::cli::shell_table::format_table;
+use crate::cli::shell_tags::{add_tag, delete_tag, list_tags, tag_notes, untag_notes};
+use crate::error::FenrisError;
+use crate::i18n::{set_active_locale, t};
+use crate::json::{format_json, minify_json};
+use crate::sqlite::sqlite_migrations;
+use crate::store::{format_date_with, load_date_settings_file, DateSettings, DATE_SETTINGS_STORE};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Pool, Sqlite};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Must match the identifier in tauri.conf.json, the SQL plugin keeps the database in its config dir
+const APP_IDENTIFIER: &str = "com.fenris.app";
+const DATABASE_FILE: &str = "fenris_app_notes.db";
+const DATABASE_ENV: &str = "FENRIS_DB";
+const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5000;
+// Checked in order for the message language, unsupported values fall back to English
+const LOCALE_ENV: [&str; 4] = ["FENRIS_LANG", "LC_ALL", "LC_MESSAGES", "LANG"];
+const USAGE: &str =
+    "Usage: fenris-cli [--json] [--db <path>] [--busy-timeout <ms>] <command> [arguments...]";
+
+struct CliOptions {
+    json: bool,
+    database: Option<PathBuf>,
+    /// How long to wait for the app to release a write lock
+    busy_timeout: Duration,
+    /// Timezone and locale the app saved, used for dates in text output
+    dates: DateSettings,
+}
+
+// What a command prints, as text for people or as JSON for scripts
+struct CliOutput {
+    text: String,
+    data: Value,
+}
+
+impl CliOutput {
+    fn new(text: impl Into<String>, data: impl Serialize) -> Result<Self, FenrisError> {
+        Ok(Self {
+            text: text.into(),
+            data: serde_json::to_value(data)
+                .map_err(|e| FenrisError::internal("cli.serialize_failed", &[("error", &e)]))?,
+        })
+    }
+
+    fn message(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            data: json!({ "message": text }),
+            text,
+        }
+    }
+}
+
+// Entry point of the fenris-cli binary, returns the process exit code
+pub fn run_headless(args: Vec<String>) -> i32 {
+    if let Some(code) = LOCALE_ENV
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+    {
+        let _ = set_active_locale(&code);
+    }
+
+    let (options, words) = match parse_options(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("fenris-cli: {}\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    if words.is_empty() {
+        eprintln!("{}\n{}", USAGE, t("cli.help_hint", &[]));
+        return 2;
+    }
+
+    let command = match parse_command(CLI_COMMANDS, &words) {
+        Ok(command) => command,
+        Err(e) => return fail(&options, e, 2),
+    };
+    match tauri::async_runtime::block_on(execute(&options, &command)) {
+        Ok(output) => {
+            if options.json {
+                match serde_json::to_string_pretty(&output.data) {
+                    Ok(text) => println!("{}", text),
+                    Err(e) => {
+                        let error = FenrisError::internal("cli.serialize_failed", &[("error", &e)]);
+                        return fail(&options, error, 1);
+                    }
+                }
+            } else if !output.text.is_empty() {
+                println!("{}", output.text);
+            }
+            0
+        }
+        Err(e) if e.code == "database.locked" => {
+            let message = format!("{}\n{}", e.message, t("cli.database_locked_hint", &[]));
+            fail(&options, FenrisError { message, ..e }, 1)
+        }
+        Err(e) => fail(&options, e, 1),
+    }
+}
+
+fn fail(options: &CliOptions, error: FenrisError, code: i32) -> i32 {
+    if options.json {
+        println!(
+            "{}",
+            json!({
+                "error": error.message,
+                "code": error.code,
+                "kind": error.kind,
+                "context": error.context,
+            })
+        );
+    } else {
+        eprintln!("fenris-cli: {}", error);
+    }
+    code
+}
+
+// Global options may appear anywhere before `--`, everything else is the command line
+fn parse_options(args: Vec<String>) -> Result<(CliOptions, Vec<String>), FenrisError> {
+    let mut options = CliOptions {
+        json: false,
+        database: None,
+        busy_timeout: Duration::from_millis(DEFAULT_BUSY_TIMEOUT_MS),
+        dates: dirs::config_dir()
+            .map(|dir| load_date_settings_file(&dir.join(APP_IDENTIFIER).join(DATE_SETTINGS_STORE)))
+            .unwrap_or_default(),
+    };
+    let mut words = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => {
+                words.extend(args);
+                break;
+            }
+            "--json" => options.json = true,
+            "--db" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| FenrisError::validation("cli.missing_db_path", &[]))?;
+                options.database = Some(PathBuf::from(path));
+            }
+            "--busy-timeout" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| FenrisError::validation("cli.missing_busy_timeout", &[]))?;
+                let millis = value.parse::<u64>().map_err(|_| {
+                    FenrisError::validation("cli.invalid_busy_timeout", &[("value", &value)])
+                })?;
+                options.busy_timeout = Duration::from_millis(millis);
+            }
+            "-h" | "--help" => words.insert(0, String::from("help")),
+            "-V" | "--version" => words.insert(0, String::from("version")),
+            _ => words.push(arg),
+        }
+    }
+    Ok((options, words))
+}
+
+async fn execute(options: &CliOptions, command: &ParsedCommand) -> Result<CliOutput, FenrisError> {
+    let arg = |name: &str| command.text(name).unwrap_or_default().to_string();
+
+    match command.path.as_slice() {
+        ["help"] => {
+            let topic: Vec<String> = arg("command")
+                .split_whitespace()
+                .map(String::from)
+                .collect();
+            return Ok(CliOutput::message(help_for(CLI_COMMANDS, &topic)?));
+        }
+        ["version"] => {
+            return Ok(CliOutput::message(t(
+                "cli.version",
+                &[("version", &env!("CARGO_PKG_VERSION"))],
+            )))
+        }
+        ["date"] => {
+            let now = options.dates.now();
+            return CliOutput::new(
+                format_date_with(&options.dates, &arg("format"))?,
+                json!({ "utc": now.naive_utc().and_utc().to_rfc3339(), "timestamp": now.timestamp() }),
+            );
+        }
+        ["json", action] => {
+            let input = read_input(&arg("file"))?;
+            let text = if *action == "format" {
+                format_json(input, None)?
+            } else {
+                minify_json(input)?
+            };
+            let data: Value = serde_json::from_str(&text)
+                .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+            return Ok(CliOutput { text, data });
+        }
+        _ => {}
+    }
+
+    let pool = open_database(options, writes(command)).await?;
+    let output = execute_with_database(options, &pool, command).await;
+    pool.close().await;
+    output
+}
+
+fn writes(command: &ParsedCommand) -> bool {
+    matches!(
+        command.path.as_slice(),
+        ["note", "new" | "append" | "rm" | "mv" | "rename"]
+            | ["folder", "new" | "rm" | "mv" | "rename"]
+            | ["tag", "new" | "rm" | "add" | "remove"]
+    )
+}
+
+async fn execute_with_database(
+    options: &CliOptions,
+    pool: &Pool<Sqlite>,
+    command: &ParsedCommand,
+) -> Result<CliOutput, FenrisError> {
+    let arg = |name: &str| command.text(name).unwrap_or_default().to_string();
+
+    match command.path.as_slice() {
+        ["info"] => database_info(options, pool).await,
+        ["note", "list"] => {
+            let notes = match command.text("folder") {
+                Some(path) => {
+                    let folder = resolve_path(pool, None, path).await?;
+                    list_folder(pool, folder.as_ref()).await?.1
+                }
+                None => all_notes(pool).await?,
+            };
+            CliOutput::new(notes_text(&notes, &options.dates), notes)
+        }
+        ["note", "cat"] => {
+            let note = resolve_note(pool, None, &arg("note")).await?;
+            let content = note_content(pool, &note).await?;
+            CliOutput::new(content.clone(), json!({ "note": note, "content": content }))
+        }
+        ["note", "new"] => {
+            let folder = match command.text("folder") {
+                Some(path) => resolve_path(pool, None, path).await?,
+                None => None,
+            };
+            let note = create_note(pool, &arg("title"), folder.as_ref()).await?;
+            CliOutput::new(
+                notes_table(std::slice::from_ref(&note), &options.dates),
+                note,
+            )
+        }
+        ["note", "append"] => {
+            let note = resolve_note(pool, None, &arg("note")).await?;
+            let text = match arg("text").as_str() {
+                "-" => read_input("-")?,
+                text => text.to_string(),
+            };
+            append_to_note(pool, &note, text.trim_end_matches('\n')).await?;
+            CliOutput::new(t("shell.note.appended", &[("title", &note.title)]), note)
+        }
+        ["note", "rm"] => {
+            let note = resolve_note(pool, None, &arg("note")).await?;
+            delete_note(pool, &note).await?;
+            CliOutput::new(
+                t(
+                    "shell.note.deleted",
+                    &[("title", &note.title), ("id", &note.reference())],
+                ),
+                note,
+            )
+        }
+        ["note", "mv"] => {
+            let note = resolve_note(pool, None, &arg("note")).await?;
+            let folder = resolve_path(pool, None, &arg("folder")).await?;
+            let moved = move_note(pool, &note, folder.as_ref()).await?;
+            CliOutput::new(
+                t(
+                    "shell.note.moved",
+                    &[
+                        ("title", &moved.title),
+                        ("folder", &moved.folder.as_deref().unwrap_or("/")),
+                        ("id", &moved.reference()),
+                    ],
+                ),
+                moved,
+            )
+        }
+        ["note", "rename"] => {
+            let note = resolve_note(pool, None, &arg("note")).await?;
+            rename_note(pool, &note, &arg("title")).await?;
+            let renamed = NoteRow {
+                title: arg("title").trim().to_string(),
+                ..note.clone()
+            };
+            CliOutput::new(
+                t(
+                    "shell.note.renamed",
+                    &[("title", &note.title), ("new_title", &renamed.title)],
+                ),
+                renamed,
+            )
+        }
+        ["folder", "ls"] => {
+            let folder = resolve_path(pool, None, &arg("path")).await?;
+            let (folders, notes) = list_folder(pool, folder.as_ref()).await?;
+            let text = if folders.is_empty() && notes.is_empty() {
+                t("shell.folder_empty", &[])
+            } else {
+                listing_table(&folders, &notes, &options.dates)
+            };
+            CliOutput::new(text, json!({ "folders": folders, "notes": notes }))
+        }
+        ["folder", "tree"] => CliOutput::new(
+            folder_tree(pool).await?,
+            json!({ "folders": all_folders(pool).await?, "notes": all_notes(pool).await? }),
+        ),
+        ["folder", "new"] => {
+            let parent = match command.text("parent") {
+                Some(path) => resolve_path(pool, None, path).await?,
+                None => None,
+            };
+            add_folder(pool, &arg("name"), parent.as_ref()).await?;
+            let folder = resolve_folder(pool, parent.as_ref(), arg("name").trim()).await?;
+            CliOutput::new(t("shell.folder.created", &[("name", &folder.name)]), folder)
+        }
+        ["folder", "rm"] => {
+            let folder = resolve_folder(pool, None, &arg("folder")).await?;
+            let (subfolders, notes) = delete_folder(pool, &folder).await?;
+            CliOutput::new(
+                t(
+                    "shell.folder.deleted",
+                    &[
+                        ("name", &folder.name),
+                        ("subfolders", &subfolders),
+                        ("notes", &notes),
+                    ],
+                ),
+                json!({ "folder": folder, "subfolders": subfolders, "notes": notes }),
+            )
+        }
+        ["folder", "mv"] => {
+            let folder = resolve_folder(pool, None, &arg("folder")).await?;
+            let parent = resolve_path(pool, None, &arg("parent")).await?;
+            move_folder(pool, &folder, parent.as_ref()).await?;
+            CliOutput::new(
+                t(
+                    "shell.folder.moved",
+                    &[
+                        ("name", &folder.name),
+                        (
+                            "parent",
+                            &parent
+                                .as_ref()
+                                .map(|parent| parent.name.as_str())
+                                .unwrap_or("/"),
+                        ),
+                    ],
+                ),
+                FolderRow {
+                    parent_id: parent.map(|parent| parent.id),
+                    ..folder
+                },
+            )
+        }
+        ["folder", "rename"] => {
+            let folder = resolve_folder(pool, None, &arg("folder")).await?;
+            rename_folder(pool, &folder, &arg("name")).await?;
+            let renamed = FolderRow {
+                name: arg("name").trim().to_string(),
+                ..folder.clone()
+            };
+            CliOutput::new(
+                t(
+                    "shell.folder.renamed",
+                    &[("name", &folder.name), ("new_name", &renamed.name)],
+                ),
+                renamed,
+            )
+        }
+        ["tag", "ls"] => {
+            let tags = list_tags(pool).await?;
+            let text = if tags.is_empty() {
+                t("cli.no_tags", &[])
+            } else {
+                let rows: Vec<Vec<String>> = tags
+                    .iter()
+                    .map(|tag| vec![tag.id.to_string(), tag.name.clone(), tag.notes.to_string()])
+                    .collect();
+                format_table(&["id", "name", "notes"], &rows)
+            };
+            CliOutput::new(text, tags)
+        }
+        ["tag", "new"] => {
+            add_tag(pool, &arg("name")).await?;
+            Ok(CliOutput::message(t(
+                "shell.tag.created",
+                &[("name", &arg("name"))],
+            )))
+        }
+        ["tag", "rm"] => {
+            delete_tag(pool, &arg("name")).await?;
+            Ok(CliOutput::message(t(
+                "shell.tag.deleted",
+                &[("name", &arg("name"))],
+            )))
+        }
+        ["tag", action @ ("add" | "remove")] => {
+            let note = resolve_note(pool, None, &arg("note")).await?;
+            let target = [(note.id, note.kind.as_str())];
+            let changed = if *action == "add" {
+                tag_notes(pool, &arg("name"), &target).await?
+            } else {
+                untag_notes(pool, &arg("name"), &target).await?
+            };
+            let key = match (*action, changed) {
+                ("add", 0) => "cli.tag.already_tagged",
+                ("add", _) => "cli.tag.added",
+                (_, 0) => "cli.tag.not_tagged",
+                _ => "cli.tag.removed",
+            };
+            let text = t(key, &[("name", &arg("name")), ("title", &note.title)]);
+            CliOutput::new(
+                text,
+                json!({ "tag": arg("name"), "note": note, "changed": changed > 0 }),
+            )
+        }
+        ["tag", "notes"] => {
+            let notes = notes_with_tag(pool, &arg("name")).await?;
+            CliOutput::new(notes_text(&notes, &options.dates), notes)
+        }
+        ["search"] => {
+            let notes = search_notes(pool, &arg("text")).await?;
+            CliOutput::new(notes_text(&notes, &options.dates), notes)
+        }
+        ["export", "note"] => {
+            let note = resolve_note(pool, None, &arg("note")).await?;
+            let content = note_content(pool, &note).await?;
+            match command.text("file") {
+                Some(file) => {
+                    std::fs::write(file, &content).map_err(|e| {
+                        FenrisError::io("cli.write_failed", &[("path", &file), ("error", &e)])
+                    })?;
+                    CliOutput::new(
+                        t(
+                            "cli.note_exported",
+                            &[("title", &note.title), ("path", &file)],
+                        ),
+                        json!({ "note": note, "file": file }),
+                    )
+                }
+                None => {
+                    CliOutput::new(content.clone(), json!({ "note": note, "content": content }))
+                }
+            }
+        }
+        ["export", "folder"] => {
+            let folder = resolve_path(pool, None, &arg("folder")).await?;
+            let files = export_folder(pool, folder, Path::new(&arg("directory"))).await?;
+            CliOutput::new(
+                t(
+                    "cli.folder_exported",
+                    &[("count", &files.len()), ("path", &arg("directory"))],
+                ),
+                files,
+            )
+        }
+        _ => Err(FenrisError::validation(
+            "command.not_implemented",
+            &[("command", &command.name())],
+        )),
+    }
+}
+
+fn notes_text(notes: &[NoteRow], dates: &DateSettings) -> String {
+    if notes.is_empty() {
+        t("cli.no_notes", &[])
+    } else {
+        notes_table(notes, dates)
+    }
+}
+
+// `-` stands for standard input so JSON and note text can be piped in
+fn read_input(path: &str) -> Result<String, FenrisError> {
+    if path == "-" {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| FenrisError::io("cli.stdin_failed", &[("error", &e)]))?;
+        return Ok(input);
+    }
+    std::fs::read_to_string(path)
+        .map_err(|e| FenrisError::io("cli.read_failed", &[("path", &path), ("error", &e)]))
+}
+
+fn database_path(options: &CliOptions) -> Result<PathBuf, FenrisError> {
+    if let Some(path) = &options.database {
+        return Ok(path.clone());
+    }
+    if let Ok(path) = std::env::var(DATABASE_ENV) {
+        return Ok(PathBuf::from(path));
+    }
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER).join(DATABASE_FILE))
+        .ok_or_else(|| FenrisError::io("cli.config_dir_missing", &[]))
+}
+
+// Never creates, migrates or switches the journal mode of the database: the app owns the schema,
+// and changing the journal mode needs an exclusive lock that would fail while the app is open.
+// Writes wait up to the busy timeout for the app to finish its own transaction.
+async fn open_database(options: &CliOptions, writable: bool) -> Result<Pool<Sqlite>, FenrisError> {
+    let path = database_path(options)?;
+    if !path.exists() {
+        return Err(FenrisError::not_found(
+            "cli.database_not_found",
+            &[("path", &path.display())],
+        ));
+    }
+
+    let connect_options = SqliteConnectOptions::new()
+        .filename(&path)
+        .read_only(!writable)
+        .busy_timeout(options.busy_timeout);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(connect_options)
+        .await
+        .map_err(|e| {
+            FenrisError::sqlx("cli.database_open_failed", &e, &[("path", &path.display())])
+        })?;
+
+    let version = schema_version(&pool).await?;
+    let expected = sqlite_migrations()
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0);
+    if version < expected {
+        return Err(FenrisError::database(
+            "cli.schema_outdated",
+            &[("version", &version), ("expected", &expected)],
+        ));
+    }
+    if version > expected {
+        return Err(FenrisError::database(
+            "cli.schema_newer",
+            &[("version", &version), ("expected", &expected)],
+        ));
+    }
+    Ok(pool)
+}
+
+// Highest migration the SQL plugin applied, 0 when the app never opened the database
+async fn schema_version(pool: &Pool<Sqlite>) -> Result<i64, FenrisError> {
+    let (exists,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("cli.schema_read_failed", &e, &[]))?;
+    if exists == 0 {
+        return Ok(0);
+    }
+
+    let (version,): (Option<i64>,) =
+        sqlx::query_as("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| FenrisError::sqlx("cli.schema_read_failed", &e, &[]))?;
+    Ok(version.unwrap_or(0))
+}
+
+async fn database_info(
+    options: &CliOptions,
+    pool: &Pool<Sqlite>,
+) -> Result<CliOutput, FenrisError> {
+    let path = database_path(options)?;
+    let (journal_mode,): (String,) = sqlx::query_as("PRAGMA journal_mode")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| FenrisError::sqlx("cli.journal_mode_failed", &e, &[]))?;
+    let (notes, folders, tags): (i64, i64, i64) = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM single_notes) + (SELECT COUNT(*) FROM note),
+                (SELECT COUNT(*) FROM folders),
+                (SELECT COUNT(*) FROM tags)",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("cli.count_failed", &e, &[]))?;
+    let version = schema_version(pool).await?;
+
+    let text = t(
+        "cli.info",
+        &[
+            ("database", &path.display()),
+            ("journal_mode", &journal_mode),
+            ("version", &version),
+            ("notes", &notes),
+            ("folders", &folders),
+            ("tags", &tags),
+        ],
+    );
+    CliOutput::new(
+        text,
+        json!({
+            "database": path,
+            "journal_mode": journal_mode,
+            "schema_version": version,
+            "notes": notes,
+            "folders": folders,
+            "tags": tags,
+        }),
+    )
+}
+
+// Mirrors the folder hierarchy as directories with one Markdown file per note
+async fn export_folder(
+    pool: &Pool<Sqlite>,
+    folder: Option<FolderRow>,
+    directory: &Path,
+) -> Result<Vec<PathBuf>, FenrisError> {
+    let mut files = Vec::new();
+    let mut pending = vec![(folder, directory.to_path_buf())];
+
+    while let Some((folder, directory)) = pending.pop() {
+        std::fs::create_dir_all(&directory).map_err(|e| {
+            FenrisError::io(
+                "cli.create_dir_failed",
+                &[("path", &directory.display()), ("error", &e)],
+            )
+        })?;
+        let (folders, notes) = list_folder(pool, folder.as_ref()).await?;
+        let mut used = HashSet::new();
+
+        for subfolder in folders {
+            let name = unique_file_name(&subfolder.name, "", &mut used);
+            pending.push((Some(subfolder), directory.join(name)));
+        }
+        for note in notes {
+            let file = directory.join(unique_file_name(&note.title, ".md", &mut used));
+            let content = note_content(pool, &note).await?;
+            std::fs::write(&file, content).map_err(|e| {
+                FenrisError::io(
+                    "cli.write_failed",
+                    &[("path", &file.display()), ("error", &e)],
+                )
+            })?;
+            files.push(file);
+        }
+    }
+    Ok(files)
+}
+
+// Titles can hold characters file systems reject, and two notes can share a title
+fn unique_file_name(title: &str, extension: &str, used: &mut HashSet<String>) -> String {
+    let base: String = title
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let base = match base.trim_matches('.') {
+        "" => String::from("untitled"),
+        _ => base,
+    };
+
+    let mut name = format!("{}{}", base, extension);
+    let mut counter = 2;
+    while !used.insert(name.to_lowercase()) {
+        name = format!("{} ({}){}", base, counter, extension);
+        counter += 1;
+    }
+    name
+}
@@ -1,3 +1,15 @@
 mod cli_commands_helper;
+mod cli_headless;
+mod shell_completion;
+mod shell_engine;
+mod shell_notes;
+mod shell_registry;
+mod shell_settings;
+mod shell_table;
+mod shell_tags;
+mod shell_tokenizer;
 
-pub use cli_commands_helper::*;
\ No newline at end of file
+pub use cli_commands_helper::*;
+pub use cli_headless::*;
+pub use shell_completion::*;
+pub use shell_engine::*;
@@ -0,0 +1,266 @@
+use crate::cli::shell_registry::{
+    find_command, next_arg, ArgKind, ArgSource, CommandSpec, COMMANDS,
+};
+use crate::cli::shell_settings::load_aliases;
+use crate::cli::shell_tokenizer::{pipe_offsets, quote_word, tokenize_partial, PartialWord};
+use crate::error::FenrisError;
+use crate::sqlite::app_sqlite_pool;
+use crate::store::get_available_models_with_custom;
+use crate::theme::list_of_themes;
+use serde::Serialize;
+use tauri::{command, AppHandle};
+
+const MAX_CANDIDATES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionKind {
+    Command,
+    Subcommand,
+    Choice,
+    Folder,
+    Note,
+    Tag,
+    Theme,
+    Model,
+    Alias,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompletionCandidate {
+    /// Text to insert, quoted when needed
+    pub value: String,
+    pub label: String,
+    pub kind: CompletionKind,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShellCompletions {
+    /// Character range of the input to replace with a candidate value
+    pub start: usize,
+    pub end: usize,
+    pub candidates: Vec<CompletionCandidate>,
+    /// Longest prefix shared by all candidates, already quoted
+    pub common_prefix: String,
+}
+
+// What the word under the cursor is expected to be
+enum Slot {
+    Commands(&'static [CommandSpec], CompletionKind),
+    Choices(&'static [&'static str]),
+    Source(ArgSource),
+    Nothing,
+}
+
+#[command]
+pub async fn complete_shell_input(
+    app: AppHandle,
+    line: String,
+    cursor: Option<usize>,
+) -> Result<ShellCompletions, FenrisError> {
+    // The cursor is a character index coming from the input element
+    let cursor_chars = cursor.unwrap_or(usize::MAX);
+    let cursor_byte = line
+        .char_indices()
+        .nth(cursor_chars)
+        .map(|(index, _)| index)
+        .unwrap_or(line.len());
+    let before = &line[..cursor_byte];
+    // Only the current pipeline stage matters
+    let stage_start = pipe_offsets(before)
+        .last()
+        .map(|offset| offset + 1)
+        .unwrap_or(0);
+
+    let (words, mut partial) = tokenize_partial(&before[stage_start..]);
+    partial.start += stage_start;
+    let mut candidates = match slot_for(&words) {
+        Slot::Commands(commands, kind) => {
+            let mut candidates = command_candidates(commands, kind, &partial.text);
+            if words.is_empty() {
+                candidates
+                    .extend(source_candidates(&app, ArgSource::Aliases, &partial.text).await?);
+            }
+            candidates
+        }
+        Slot::Choices(values) => values
+            .iter()
+            .filter(|value| matches_prefix(value, &partial.text))
+            .map(|value| candidate(value, CompletionKind::Choice, None))
+            .collect(),
+        Slot::Source(source) => source_candidates(&app, source, &partial.text).await?,
+        Slot::Nothing => Vec::new(),
+    };
+    candidates.truncate(MAX_CANDIDATES);
+
+    for candidate in &mut candidates {
+        candidate.value = quote_word(&candidate.label, partial.open_quote);
+    }
+
+    Ok(ShellCompletions {
+        start: before[..partial.start].chars().count(),
+        end: before.chars().count(),
+        common_prefix: common_prefix(&candidates, &partial),
+        candidates,
+    })
+}
+
+fn slot_for(words: &[String]) -> Slot {
+    let Some(first) = words.first() else {
+        return Slot::Commands(COMMANDS, CompletionKind::Command);
+    };
+    let Some(mut spec) = find_command(COMMANDS, first) else {
+        return Slot::Nothing;
+    };
+
+    let mut rest = &words[1..];
+    while !spec.subcommands.is_empty() {
+        match rest.first() {
+            None => return Slot::Commands(spec.subcommands, CompletionKind::Subcommand),
+            Some(word) => match find_command(spec.subcommands, word) {
+                Some(sub) => {
+                    spec = sub;
+                    rest = &rest[1..];
+                }
+                None => return Slot::Nothing,
+            },
+        }
+    }
+
+    let Some(arg) = next_arg(spec, rest) else {
+        return Slot::Nothing;
+    };
+
+    match (arg.kind, arg.source) {
+        // `help add fo` completes like the command line it describes
+        (_, ArgSource::Commands) => match slot_for(rest) {
+            Slot::Commands(commands, kind) => Slot::Commands(commands, kind),
+            _ => Slot::Nothing,
+        },
+        (ArgKind::Choice(values), _) => Slot::Choices(values),
+        (_, source) => Slot::Source(source),
+    }
+}
+
+fn command_candidates(
+    commands: &'static [CommandSpec],
+    kind: CompletionKind,
+    prefix: &str,
+) -> Vec<CompletionCandidate> {
+    commands
+        .iter()
+        .filter(|spec| matches_prefix(spec.name, prefix))
+        .map(|spec| candidate(spec.name, kind, Some(spec.summary())))
+        .collect()
+}
+
+async fn source_candidates(
+    app: &AppHandle,
+    source: ArgSource,
+    prefix: &str,
+) -> Result<Vec<CompletionCandidate>, FenrisError> {
+    let (names, kind) = match source {
+        ArgSource::None | ArgSource::Commands => return Ok(Vec::new()),
+        ArgSource::Themes => {
+            let themes = list_of_themes()?;
+            return Ok(themes
+                .into_iter()
+                .filter(|theme| matches_prefix(theme.name, prefix))
+                .map(|theme| {
+                    candidate(
+                        theme.name,
+                        CompletionKind::Theme,
+                        Some(theme.display.to_string()),
+                    )
+                })
+                .collect());
+        }
+        ArgSource::Aliases => (
+            load_aliases(app)?.into_keys().collect(),
+            CompletionKind::Alias,
+        ),
+        ArgSource::Models => (
+            get_available_models_with_custom(app.clone()).await?,
+            CompletionKind::Model,
+        ),
+        ArgSource::Folders => (
+            database_names(app, "SELECT name FROM folders ORDER BY name COLLATE NOCASE").await?,
+            CompletionKind::Folder,
+        ),
+        ArgSource::Notes => (
+            database_names(
+                app,
+                "SELECT title FROM single_notes UNION SELECT title FROM note ORDER BY 1 COLLATE NOCASE",
+            )
+            .await?,
+            CompletionKind::Note,
+        ),
+        ArgSource::Tags => (
+            database_names(app, "SELECT name FROM tags ORDER BY name COLLATE NOCASE").await?,
+            CompletionKind::Tag,
+        ),
+    };
+
+    Ok(names
+        .iter()
+        .filter(|name| matches_prefix(name, prefix))
+        .map(|name| candidate(name, kind, None))
+        .collect())
+}
+
+async fn database_names(app: &AppHandle, sql: &str) -> Result<Vec<String>, FenrisError> {
+    let pool = app_sqlite_pool(app).await?;
+    let rows: Vec<(String,)> = sqlx::query_as(sql)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| FenrisError::sqlx("completion.load_failed", &e, &[]))?;
+    Ok(rows.into_iter().map(|(name,)| name).collect())
+}
+
+fn candidate(
+    label: &str,
+    kind: CompletionKind,
+    description: Option<String>,
+) -> CompletionCandidate {
+    CompletionCandidate {
+        value: label.to_string(),
+        label: label.to_string(),
+        kind,
+        description,
+    }
+}
+
+fn matches_prefix(value: &str, prefix: &str) -> bool {
+    value.to_lowercase().starts_with(&prefix.to_lowercase())
+}
+
+// Extends the typed word as far as every candidate agrees, keeping the user's quote style
+fn common_prefix(candidates: &[CompletionCandidate], partial: &PartialWord) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    if candidates.len() == 1 {
+        return first.value.clone();
+    }
+
+    let mut prefix: Vec<char> = first.label.chars().collect();
+    for candidate in &candidates[1..] {
+        let shared = prefix
+            .iter()
+            .zip(candidate.label.chars())
+            .take_while(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+            .count();
+        prefix.truncate(shared);
+    }
+
+    let prefix: String = prefix.into_iter().collect();
+    if prefix.chars().count() <= partial.text.chars().count() {
+        return String::new();
+    }
+    // Left unterminated so the user can keep typing inside the quotes
+    match quote_word(&prefix, partial.open_quote) {
+        quoted if quoted.len() > prefix.len() => quoted[..quoted.len() - 1].to_string(),
+        plain => plain,
+    }
+}
@@ -0,0 +1,1020 @@
+use crate::cli::shell_notes::{
+    add_folder, append_to_note, create_note, delete_folder, delete_note, folder_by_id, folder_path,
+    folder_tree, list_folder, listing_names, listing_table, move_folder, move_note, note_content,
+    notes_table, rename_folder, rename_note, resolve_folder, resolve_note, resolve_path,
+    search_notes, FolderRow, NoteRow,
+};
+use crate::cli::shell_registry::{help_text, parse_tokens, ParsedCommand};
+use crate::cli::shell_settings::{
+    get_startup_script, load_aliases, load_session_cwd, remove_alias, save_alias, save_session_cwd,
+    script_path, set_startup_script, DEFAULT_SESSION,
+};
+use crate::cli::shell_tags::{add_tag, delete_tag, tag_notes, untag_notes};
+use crate::cli::shell_tokenizer::{split_first_word, split_pipeline, tokenize};
+use crate::error::{lock, FenrisError};
+use crate::i18n::{set_active_locale, t};
+use crate::ollama::handle_shell_ai_status_command;
+use crate::sqlite::app_sqlite_pool;
+use crate::state::{
+    clear_shell_history, record_shell_history, HistoryEntry, ShellManager, ShellState,
+};
+use crate::store::{
+    detected_timezone, format_date_with, handle_shell_ai_model_command, handle_shell_theme_command,
+    load_date_settings, save_code_theme, save_date_locale, save_date_timezone, DateSettings,
+    DateStyle,
+};
+use crate::theme::list_of_themes;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::collections::BTreeMap;
+use tauri::{command, AppHandle, Manager};
+
+// UI side effects the frontend applies after a command ran
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ShellAction {
+    Navigate { route: String },
+    SetLanguage { code: String },
+    ReloadTheme,
+    SetVisibility { target: String, visible: bool },
+    OpenNote { id: i64, note_type: String },
+    Clear,
+    Exit,
+}
+
+// Structured results passed from one pipeline stage to the next
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShellItem {
+    Note {
+        id: i64,
+        title: String,
+        /// "single" or "folder", as stored in note_tags
+        note_type: &'static str,
+    },
+    Text {
+        value: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShellOutput {
+    /// Command line that ran, after `!n` history expansion
+    pub line: String,
+    /// Resolved command path, e.g. "add folder"
+    pub command: String,
+    pub success: bool,
+    pub output: String,
+    /// Set when the command failed, e.g. kind "not_found" with code "note.not_found"
+    pub error: Option<FenrisError>,
+    /// Applied in order, a script can produce several
+    pub actions: Vec<ShellAction>,
+    pub items: Vec<ShellItem>,
+    /// Working folder of the session once the command ran, e.g. "/Work/Drafts"
+    pub cwd: String,
+}
+
+impl ShellOutput {
+    fn empty() -> Self {
+        Self {
+            line: String::new(),
+            command: String::new(),
+            success: true,
+            output: String::new(),
+            error: None,
+            actions: Vec::new(),
+            items: Vec::new(),
+            cwd: String::from("/"),
+        }
+    }
+
+    fn text(command: &ParsedCommand, output: impl Into<String>) -> Self {
+        Self {
+            command: command.name(),
+            output: output.into(),
+            ..Self::empty()
+        }
+    }
+
+    fn action(command: &ParsedCommand, output: impl Into<String>, action: ShellAction) -> Self {
+        Self {
+            actions: vec![action],
+            ..Self::text(command, output)
+        }
+    }
+
+    fn items(command: &ParsedCommand, output: impl Into<String>, items: Vec<ShellItem>) -> Self {
+        Self {
+            items,
+            ..Self::text(command, output)
+        }
+    }
+
+    fn error(command: String, error: FenrisError) -> Self {
+        Self {
+            command,
+            success: false,
+            output: error.message.clone(),
+            error: Some(error),
+            ..Self::empty()
+        }
+    }
+}
+
+// Each session keeps its own working folder, the app terminal uses the default one
+#[command]
+pub async fn run_shell_command(
+    app: AppHandle,
+    line: String,
+    session: Option<String>,
+) -> ShellOutput {
+    let session = session.as_deref().unwrap_or(DEFAULT_SESSION);
+    let line = match expand_history(&app, &line) {
+        Ok(line) => line,
+        Err(e) => {
+            return ShellOutput {
+                line,
+                cwd: session_path(&app, session).await,
+                ..ShellOutput::error(String::new(), e)
+            }
+        }
+    };
+
+    let output = ShellOutput {
+        line: line.clone(),
+        ..run_line(&app, session, &line).await
+    };
+    // A history that cannot be saved should not turn a successful command into a failure
+    let _ = record_shell_history(&app, &line, output.success);
+    ShellOutput {
+        cwd: session_path(&app, session).await,
+        ..output
+    }
+}
+
+// Runs the configured startup script, if any, so the frontend can apply its actions
+#[command]
+pub async fn run_startup_script(app: AppHandle) -> Result<Option<ShellOutput>, FenrisError> {
+    let Some(path) = get_startup_script(&app)? else {
+        return Ok(None);
+    };
+    let output = run_script(&app, DEFAULT_SESSION, &path, true).await;
+    Ok(Some(ShellOutput {
+        line: format!("script run {}", path),
+        command: String::from("script run"),
+        cwd: session_path(&app, DEFAULT_SESSION).await,
+        ..output
+    }))
+}
+
+#[command]
+pub async fn get_shell_cwd(app: AppHandle, session: Option<String>) -> Result<String, FenrisError> {
+    let session = session.as_deref().unwrap_or(DEFAULT_SESSION);
+    let pool = app_sqlite_pool(&app).await?;
+    let cwd = working_folder(&app, &pool, session).await?;
+    folder_path(&pool, cwd.as_ref()).await
+}
+
+// The folder `cd` moved the session into, a folder deleted since then sends it back to the top
+async fn working_folder(
+    app: &AppHandle,
+    pool: &Pool<Sqlite>,
+    session: &str,
+) -> Result<Option<FolderRow>, FenrisError> {
+    let Some(id) = load_session_cwd(app, session)? else {
+        return Ok(None);
+    };
+    let folder = folder_by_id(pool, id).await?;
+    if folder.is_none() {
+        save_session_cwd(app, session, None)?;
+    }
+    Ok(folder)
+}
+
+// Prompt path for the session, falling back to the top level when it cannot be read
+async fn session_path(app: &AppHandle, session: &str) -> String {
+    get_shell_cwd(app.clone(), Some(session.to_string()))
+        .await
+        .unwrap_or_else(|_| String::from("/"))
+}
+
+async fn run_line(app: &AppHandle, session: &str, line: &str) -> ShellOutput {
+    let aliases = match load_aliases(app) {
+        Ok(aliases) => aliases,
+        Err(e) => return ShellOutput::error(String::new(), e),
+    };
+    let stages = match pipeline_stages(line, &aliases) {
+        Ok(stages) => stages,
+        Err(e) => return ShellOutput::error(String::new(), e),
+    };
+
+    // Scripts are only started from the prompt, so a script cannot run another one
+    if let [command] = stages.as_slice() {
+        if command.path == ["script", "run"] {
+            let path = command.text("path").unwrap_or_default();
+            let keep_going = command.text("on-error") == Some("continue");
+            return ShellOutput {
+                command: command.name(),
+                ..run_script(app, session, path, keep_going).await
+            };
+        }
+    }
+    run_pipeline(app, session, &stages).await
+}
+
+// Splits the line into stages and expands an alias in the first word of each one
+fn pipeline_stages(
+    line: &str,
+    aliases: &BTreeMap<String, String>,
+) -> Result<Vec<ParsedCommand>, FenrisError> {
+    let mut stages = Vec::new();
+    for stage in split_pipeline(line)? {
+        let stage = match split_first_word(&stage) {
+            Some((word, rest)) if aliases.contains_key(&word) => {
+                format!("{} {}", aliases[&word], rest)
+            }
+            _ => stage,
+        };
+        // An alias may itself be a pipeline
+        for part in split_pipeline(&stage)? {
+            stages.push(parse_tokens(&tokenize(&part)?)?);
+        }
+    }
+    Ok(stages)
+}
+
+async fn run_pipeline(app: &AppHandle, session: &str, stages: &[ParsedCommand]) -> ShellOutput {
+    let mut output = ShellOutput::empty();
+    for (index, stage) in stages.iter().enumerate() {
+        if index > 0 && !stage.reads_input {
+            return ShellOutput::error(
+                stage.name(),
+                FenrisError::validation("shell.no_piped_input", &[("command", &stage.name())]),
+            );
+        }
+        match execute(app, session, stage, &output.items).await {
+            Ok(next) => {
+                let mut actions = std::mem::take(&mut output.actions);
+                actions.extend(next.actions);
+                output = ShellOutput { actions, ..next };
+            }
+            Err(e) if stages.len() > 1 => {
+                let message = t(
+                    "shell.pipeline_stage_error",
+                    &[("command", &stage.name()), ("error", &e)],
+                );
+                return ShellOutput::error(stage.name(), FenrisError { message, ..e });
+            }
+            Err(e) => return ShellOutput::error(stage.name(), e),
+        }
+    }
+    output
+}
+
+// Runs a .fenris file line by line, blank lines and lines starting with # are skipped
+async fn run_script(app: &AppHandle, session: &str, path: &str, keep_going: bool) -> ShellOutput {
+    let script = match script_path(path).and_then(|path| {
+        std::fs::read_to_string(&path).map_err(|e| {
+            FenrisError::io(
+                "script.read_failed",
+                &[("path", &path.display()), ("error", &e)],
+            )
+        })
+    }) {
+        Ok(script) => script,
+        Err(e) => return ShellOutput::error(String::from("script run"), e),
+    };
+    let aliases = match load_aliases(app) {
+        Ok(aliases) => aliases,
+        Err(e) => return ShellOutput::error(String::from("script run"), e),
+    };
+
+    let mut transcript = Vec::new();
+    let mut actions = Vec::new();
+    let mut failures = 0;
+    for (index, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let output = match pipeline_stages(line, &aliases) {
+            Ok(stages) if stages.iter().any(|stage| stage.path == ["script", "run"]) => {
+                ShellOutput::error(
+                    String::from("script run"),
+                    FenrisError::validation("script.nested", &[]),
+                )
+            }
+            Ok(stages) => run_pipeline(app, session, &stages).await,
+            Err(e) => ShellOutput::error(String::new(), e),
+        };
+        actions.extend(output.actions);
+
+        transcript.push(format!("> {}", line));
+        if output.success {
+            if !output.output.is_empty() {
+                transcript.push(output.output);
+            }
+            continue;
+        }
+
+        failures += 1;
+        transcript.push(t(
+            "shell.script.line_error",
+            &[("line", &(index + 1)), ("error", &output.output)],
+        ));
+        if !keep_going {
+            transcript.push(t("shell.script.stopped", &[]));
+            break;
+        }
+    }
+
+    if keep_going && failures > 0 {
+        transcript.push(t(
+            "shell.script.finished_with_errors",
+            &[("count", &failures)],
+        ));
+    }
+    ShellOutput {
+        command: String::from("script run"),
+        success: failures == 0,
+        output: transcript.join("\n"),
+        error: (failures > 0).then(|| FenrisError::validation("script.failed", &[])),
+        actions,
+        ..ShellOutput::empty()
+    }
+}
+
+// `!!` re-runs the last command, `!n` entry n and `!-n` the nth most recent one
+fn expand_history(app: &AppHandle, line: &str) -> Result<String, FenrisError> {
+    let trimmed = line.trim();
+    let Some(event) = trimmed.strip_prefix('!') else {
+        return Ok(line.to_string());
+    };
+    if event.is_empty() {
+        return Ok(line.to_string());
+    }
+
+    let manager = app.state::<ShellManager>();
+    let shell_state = lock(&manager.state)?;
+    history_event(&shell_state, event)
+        .map(|entry| entry.command.clone())
+        .ok_or_else(|| FenrisError::not_found("history.event_not_found", &[("event", &trimmed)]))
+}
+
+// `event` is what follows the first `!`: "!", "n" or "-n"
+fn history_event<'a>(shell_state: &'a ShellState, event: &str) -> Option<&'a HistoryEntry> {
+    let count = shell_state.history.len();
+    let number = match event {
+        "!" => Some(count),
+        _ => match event.strip_prefix('-') {
+            Some(offset) => offset
+                .parse::<usize>()
+                .ok()
+                .and_then(|offset| (count + 1).checked_sub(offset)),
+            None => event.parse::<usize>().ok(),
+        },
+    };
+    number.and_then(|number| shell_state.entry(number))
+}
+
+async fn execute(
+    app: &AppHandle,
+    session: &str,
+    command: &ParsedCommand,
+    input: &[ShellItem],
+) -> Result<ShellOutput, FenrisError> {
+    let arg = |name: &str| command.text(name).unwrap_or_default().to_string();
+
+    let output = match command.path.as_slice() {
+        ["help"] => {
+            let topic = tokenize(&arg("command"))?;
+            ShellOutput::text(command, help_text(&topic)?)
+        }
+        ["clear"] => ShellOutput::action(command, "", ShellAction::Clear),
+        ["exit"] => ShellOutput::action(command, "", ShellAction::Exit),
+        ["date"] => {
+            let settings = load_date_settings(app)?;
+            ShellOutput::text(command, format_date_with(&settings, &arg("format"))?)
+        }
+        ["date", "timezone"] => {
+            let text = match command.text("zone") {
+                Some(zone) => {
+                    let name = save_date_timezone(app, zone)?;
+                    t("shell.date.timezone_set", &[("timezone", &name)])
+                }
+                None => {
+                    let settings = load_date_settings(app)?;
+                    t(
+                        "shell.date.timezone",
+                        &[
+                            ("timezone", &settings.timezone.name()),
+                            ("system", &detected_timezone()),
+                            ("offset", &settings.now().offset()),
+                        ],
+                    )
+                }
+            };
+            ShellOutput::text(command, text)
+        }
+        ["date", "locale"] => {
+            let text = match command.text("code") {
+                Some(code) => {
+                    let locale = save_date_locale(app, code)?;
+                    t("shell.date.locale_set", &[("locale", &locale)])
+                }
+                None => t(
+                    "shell.date.locale",
+                    &[("locale", &load_date_settings(app)?.locale)],
+                ),
+            };
+            ShellOutput::text(command, text)
+        }
+        ["version"] => ShellOutput::text(
+            command,
+            t("shell.version", &[("version", &env!("CARGO_PKG_VERSION"))]),
+        ),
+        ["pwd"] => ShellOutput::text(
+            command,
+            get_shell_cwd(app.clone(), Some(session.to_string())).await?,
+        ),
+        ["cd"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let folder =
+                resolve_path(&pool, cwd.as_ref(), command.text("path").unwrap_or("/")).await?;
+            save_session_cwd(app, session, folder.as_ref().map(|folder| folder.id))?;
+            ShellOutput::text(command, "")
+        }
+        ["ls"] | ["folder", "ls"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let path = command
+                .text("path")
+                .or(command.text("folder"))
+                .unwrap_or("");
+            let folder = resolve_path(&pool, cwd.as_ref(), path).await?;
+            let (folders, notes) = list_folder(&pool, folder.as_ref()).await?;
+            let text = if folders.is_empty() && notes.is_empty() {
+                t("shell.folder_empty", &[])
+            } else if command.path == ["ls"] && !command.flag("long") {
+                listing_names(&folders, &notes)
+            } else {
+                listing_table(&folders, &notes, &load_date_settings(app)?)
+            };
+            ShellOutput::items(command, text, note_items(&notes))
+        }
+        ["echo"] => ShellOutput::items(
+            command,
+            arg("text"),
+            vec![ShellItem::Text { value: arg("text") }],
+        ),
+        ["goto"] => {
+            let page = arg("page");
+            let route = match page.as_str() {
+                "ai" => "/ai-chatbot",
+                "settings" => "/settings",
+                "json" => "/json",
+                _ => "/home",
+            };
+            ShellOutput::action(
+                command,
+                t("shell.navigating", &[("page", &page)]),
+                ShellAction::Navigate {
+                    route: route.to_string(),
+                },
+            )
+        }
+        ["lang"] => {
+            // Backend messages switch right away, so the reply is already in the new language
+            let code = set_active_locale(&arg("code"))?;
+            // Dates follow the interface language, `date locale` can still set them apart
+            save_date_locale(app, code)?;
+            ShellOutput::action(
+                command,
+                t("shell.language_changed", &[("language", &code)]),
+                ShellAction::SetLanguage {
+                    code: code.to_string(),
+                },
+            )
+        }
+        [visibility @ ("hide" | "show")] => {
+            let visible = *visibility == "show";
+            ShellOutput::action(
+                command,
+                t(
+                    if visible {
+                        "shell.visibility.showing"
+                    } else {
+                        "shell.visibility.hiding"
+                    },
+                    &[("target", &arg("target"))],
+                ),
+                ShellAction::SetVisibility {
+                    target: arg("target"),
+                    visible,
+                },
+            )
+        }
+        ["theme"] => {
+            let message = handle_shell_theme_command(app.clone(), arg("name")).await?;
+            ShellOutput::action(command, message, ShellAction::ReloadTheme)
+        }
+        ["code-theme"] => {
+            let themes = list_of_themes()?;
+            let theme = themes
+                .iter()
+                .find(|theme| theme.name.eq_ignore_ascii_case(&arg("name")))
+                .ok_or_else(|| {
+                    let names: Vec<&str> = themes.iter().map(|theme| theme.name).collect();
+                    FenrisError::validation(
+                        "theme.unknown_code_theme",
+                        &[("theme", &arg("name")), ("available", &names.join(", "))],
+                    )
+                })?;
+            save_code_theme(app, theme.value.to_string())?;
+            ShellOutput::text(
+                command,
+                t("shell.code_theme_set", &[("theme", &theme.display)]),
+            )
+        }
+        ["ai", "status"] => {
+            ShellOutput::text(command, handle_shell_ai_status_command(app.clone()).await?)
+        }
+        ["ai", "model"] => ShellOutput::text(
+            command,
+            handle_shell_ai_model_command(app.clone(), arg("name")).await?,
+        ),
+        ["add", "folder"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            add_folder(&pool, &arg("name"), cwd.as_ref()).await?;
+            ShellOutput::text(
+                command,
+                t("shell.folder.created", &[("name", &arg("name"))]),
+            )
+        }
+        ["delete", "folder"] | ["folder", "rm"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let folder = resolve_folder(&pool, cwd.as_ref(), &arg("folder")).await?;
+            let (subfolders, notes) = delete_folder(&pool, &folder).await?;
+            ShellOutput::text(
+                command,
+                t(
+                    "shell.folder.deleted",
+                    &[
+                        ("name", &folder.name),
+                        ("subfolders", &subfolders),
+                        ("notes", &notes),
+                    ],
+                ),
+            )
+        }
+        ["update", "folder"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let folder = resolve_folder(&pool, cwd.as_ref(), &arg("current-name")).await?;
+            rename_folder(&pool, &folder, &arg("new-name")).await?;
+            ShellOutput::text(
+                command,
+                t(
+                    "shell.folder.renamed",
+                    &[
+                        ("name", &arg("current-name")),
+                        ("new_name", &arg("new-name")),
+                    ],
+                ),
+            )
+        }
+        ["add", "note"] | ["note", "new"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let folder = match command.text("folder") {
+                Some(folder) => resolve_path(&pool, cwd.as_ref(), folder).await?,
+                None => cwd,
+            };
+            let note = create_note(&pool, &arg("title"), folder.as_ref()).await?;
+            ShellOutput::items(
+                command,
+                notes_table(std::slice::from_ref(&note), &load_date_settings(app)?),
+                note_items(&[note]),
+            )
+        }
+        ["delete", "note"] | ["note", "rm"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let note = resolve_note(&pool, cwd.as_ref(), &arg("note")).await?;
+            delete_note(&pool, &note).await?;
+            ShellOutput::text(
+                command,
+                t(
+                    "shell.note.deleted",
+                    &[("title", &note.title), ("id", &note.reference())],
+                ),
+            )
+        }
+        ["note", "open"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let note = resolve_note(&pool, cwd.as_ref(), &arg("note")).await?;
+            ShellOutput {
+                items: note_items(std::slice::from_ref(&note)),
+                ..ShellOutput::action(
+                    command,
+                    t("shell.note.opening", &[("title", &note.title)]),
+                    ShellAction::OpenNote {
+                        id: note.id,
+                        note_type: note.kind.as_str().to_string(),
+                    },
+                )
+            }
+        }
+        ["note", "cat"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let note = resolve_note(&pool, cwd.as_ref(), &arg("note")).await?;
+            let content = note_content(&pool, &note).await?;
+            ShellOutput::items(
+                command,
+                content.clone(),
+                vec![ShellItem::Text { value: content }],
+            )
+        }
+        ["note", "mv"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let note = resolve_note(&pool, cwd.as_ref(), &arg("note")).await?;
+            let folder = resolve_path(&pool, cwd.as_ref(), &arg("folder")).await?;
+            let moved = move_note(&pool, &note, folder.as_ref()).await?;
+            ShellOutput::items(
+                command,
+                t(
+                    "shell.note.moved",
+                    &[
+                        ("title", &moved.title),
+                        ("folder", &moved.folder.as_deref().unwrap_or("/")),
+                        ("id", &moved.reference()),
+                    ],
+                ),
+                note_items(&[moved]),
+            )
+        }
+        ["note", "rename"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let note = resolve_note(&pool, cwd.as_ref(), &arg("note")).await?;
+            rename_note(&pool, &note, &arg("title")).await?;
+            ShellOutput::text(
+                command,
+                t(
+                    "shell.note.renamed",
+                    &[("title", &note.title), ("new_title", &arg("title").trim())],
+                ),
+            )
+        }
+        ["note", "append"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let note = resolve_note(&pool, cwd.as_ref(), &arg("note")).await?;
+            append_to_note(&pool, &note, &arg("text")).await?;
+            ShellOutput::text(command, t("shell.note.appended", &[("title", &note.title)]))
+        }
+        ["folder", "tree"] => {
+            ShellOutput::text(command, folder_tree(&app_sqlite_pool(app).await?).await?)
+        }
+        ["folder", "mv"] => {
+            let pool = app_sqlite_pool(app).await?;
+            let cwd = working_folder(app, &pool, session).await?;
+            let folder = resolve_folder(&pool, cwd.as_ref(), &arg("folder")).await?;
+            let parent = resolve_path(&pool, cwd.as_ref(), &arg("parent")).await?;
+            move_folder(&pool, &folder, parent.as_ref()).await?;
+            ShellOutput::text(
+                command,
+                t(
+                    "shell.folder.moved",
+                    &[
+                        ("name", &folder.name),
+                        (
+                            "parent",
+                            &parent
+                                .map(|parent| parent.name)
+                                .unwrap_or_else(|| String::from("/")),
+                        ),
+                    ],
+                ),
+            )
+        }
+        ["add", "tag"] => {
+            add_tag(&app_sqlite_pool(app).await?, &arg("name")).await?;
+            ShellOutput::text(command, t("shell.tag.created", &[("name", &arg("name"))]))
+        }
+        ["delete", "tag"] => {
+            delete_tag(&app_sqlite_pool(app).await?, &arg("name")).await?;
+            ShellOutput::text(command, t("shell.tag.deleted", &[("name", &arg("name"))]))
+        }
+        ["history"] => {
+            let dates = load_date_settings(app)?;
+            let manager = app.state::<ShellManager>();
+            let shell_state = lock(&manager.state)?;
+            let count = match command.integer("count") {
+                Some(count) if count < 1 => {
+                    return Err(FenrisError::validation("history.invalid_count", &[]))
+                }
+                Some(count) => count as usize,
+                None => shell_state.history.len(),
+            };
+            let skip = shell_state.history.len().saturating_sub(count);
+            let entries: Vec<(usize, &HistoryEntry)> = shell_state
+                .history
+                .iter()
+                .enumerate()
+                .skip(skip)
+                .map(|(index, entry)| (index + 1, entry))
+                .collect();
+            ShellOutput::text(command, format_history(&entries, &dates))
+        }
+        ["history", "search"] => {
+            let dates = load_date_settings(app)?;
+            let query = arg("text").to_lowercase();
+            let manager = app.state::<ShellManager>();
+            let shell_state = lock(&manager.state)?;
+            let entries: Vec<(usize, &HistoryEntry)> = shell_state
+                .history
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| entry.command.to_lowercase().contains(&query))
+                .map(|(index, entry)| (index + 1, entry))
+                .collect();
+            if entries.is_empty() {
+                ShellOutput::text(
+                    command,
+                    t("shell.history.no_match", &[("text", &arg("text"))]),
+                )
+            } else {
+                ShellOutput::text(command, format_history(&entries, &dates))
+            }
+        }
+        ["history", "clear"] => {
+            clear_shell_history(app)?;
+            ShellOutput::text(command, t("shell.history.cleared", &[]))
+        }
+        ["search"] => {
+            let notes = search_notes(&app_sqlite_pool(app).await?, &arg("text")).await?;
+            let text = if notes.is_empty() {
+                t("shell.search.no_match", &[("text", &arg("text"))])
+            } else {
+                notes_table(&notes, &load_date_settings(app)?)
+            };
+            ShellOutput::items(command, text, note_items(&notes))
+        }
+        ["tag", action @ ("add" | "remove")] => {
+            let notes: Vec<(i64, &str)> = input
+                .iter()
+                .filter_map(|item| match item {
+                    ShellItem::Note { id, note_type, .. } => Some((*id, *note_type)),
+                    ShellItem::Text { .. } => None,
+                })
+                .collect();
+            if notes.is_empty() {
+                return Err(FenrisError::validation("tag.no_notes", &[]));
+            }
+            let pool = app_sqlite_pool(app).await?;
+            let changed = if *action == "add" {
+                tag_notes(&pool, &arg("name"), &notes).await?
+            } else {
+                untag_notes(&pool, &arg("name"), &notes).await?
+            };
+            let key = if *action == "add" {
+                "shell.tag.added"
+            } else {
+                "shell.tag.removed"
+            };
+            ShellOutput::items(
+                command,
+                t(key, &[("name", &arg("name")), ("count", &changed)]),
+                input.to_vec(),
+            )
+        }
+        ["count"] => ShellOutput::text(command, input.len().to_string()),
+        ["alias"] => {
+            let aliases = load_aliases(app)?;
+            let text = match (command.text("name"), command.text("command")) {
+                (None, _) if aliases.is_empty() => t("shell.alias.none", &[]),
+                (None, _) => aliases
+                    .iter()
+                    .map(|(name, value)| format!("alias {}='{}'", name, value))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                (Some(name), None) => aliases
+                    .get(name)
+                    .map(|value| format!("alias {}='{}'", name, value))
+                    .ok_or_else(|| FenrisError::not_found("alias.not_found", &[("name", &name)]))?,
+                (Some(name), Some(value)) => {
+                    save_alias(app, name, value)?;
+                    t("shell.alias.saved", &[("name", &name)])
+                }
+            };
+            ShellOutput::text(command, text)
+        }
+        ["unalias"] => {
+            remove_alias(app, &arg("name"))?;
+            ShellOutput::text(command, t("shell.alias.removed", &[("name", &arg("name"))]))
+        }
+        ["script", "run"] => return Err(FenrisError::validation("script.nested", &[])),
+        ["script", "startup"] => {
+            let text = match command.text("path") {
+                Some(path) => {
+                    let path = set_startup_script(app, Some(path))?.unwrap_or_default();
+                    t("shell.startup.set", &[("path", &path)])
+                }
+                None => match get_startup_script(app)? {
+                    Some(path) => t("shell.startup.current", &[("path", &path)]),
+                    None => t("shell.startup.none", &[]),
+                },
+            };
+            ShellOutput::text(command, text)
+        }
+        ["script", "startup", "clear"] => {
+            set_startup_script(app, None)?;
+            ShellOutput::text(command, t("shell.startup.cleared", &[]))
+        }
+        ["vacuum"] => {
+            let pool = app_sqlite_pool(app).await?;
+            sqlx::query("VACUUM")
+                .execute(&pool)
+                .await
+                .map_err(|e| FenrisError::sqlx("database.vacuum_failed", &e, &[]))?;
+            ShellOutput::text(command, t("shell.vacuum_done", &[]))
+        }
+        _ => {
+            return Err(FenrisError::validation(
+                "command.not_implemented",
+                &[("command", &command.name())],
+            ))
+        }
+    };
+
+    Ok(output)
+}
+
+// One line per entry: number, time, exit status and the command itself
+fn format_history(entries: &[(usize, &HistoryEntry)], dates: &DateSettings) -> String {
+    if entries.is_empty() {
+        return t("shell.history.empty", &[]);
+    }
+    let width = entries
+        .last()
+        .map(|(number, _)| number.to_string().len())
+        .unwrap_or(1);
+    entries
+        .iter()
+        .map(|(number, entry)| {
+            format!(
+                "{:>width$}  {}  [{}]  {}",
+                number,
+                dates.format_stored(&entry.timestamp, DateStyle::DateTime),
+                entry.exit_status,
+                entry.command,
+                width = width
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn note_items(notes: &[NoteRow]) -> Vec<ShellItem> {
+    notes
+        .iter()
+        .map(|note| ShellItem::Note {
+            id: note.id,
+            title: note.title.clone(),
+            note_type: note.kind.as_str(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Timezone;
+    use chrono::{FixedOffset, Offset, Utc};
+
+    fn aliases(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, command)| (name.to_string(), command.to_string()))
+            .collect()
+    }
+
+    fn entry(command: &str, timestamp: &str, exit_status: i32) -> HistoryEntry {
+        HistoryEntry {
+            command: command.to_string(),
+            timestamp: timestamp.to_string(),
+            exit_status,
+        }
+    }
+
+    fn history(commands: &[&str]) -> ShellState {
+        ShellState {
+            history: commands
+                .iter()
+                .map(|command| entry(command, "2024-03-05 14:07:09", 0))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parses_each_pipeline_stage() {
+        let stages = pipeline_stages("search todo list | tag add urgent", &aliases(&[])).unwrap();
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].path, ["search"]);
+        assert_eq!(stages[0].text("text"), Some("todo list"));
+        assert_eq!(stages[1].path, ["tag", "add"]);
+        assert!(stages[1].reads_input);
+    }
+
+    #[test]
+    fn expands_aliases_in_the_first_word_of_each_stage() {
+        let aliases = aliases(&[("ll", "ls -l"), ("n", "count")]);
+        let stages = pipeline_stages("ll \"My Folder\" | n", &aliases).unwrap();
+        assert_eq!(stages[0].path, ["ls"]);
+        assert!(stages[0].flag("long"));
+        assert_eq!(stages[0].text("path"), Some("My Folder"));
+        assert_eq!(stages[1].path, ["count"]);
+
+        // Only the first word is looked up
+        let stages = pipeline_stages("echo ll", &aliases).unwrap();
+        assert_eq!(stages[0].text("text"), Some("ll"));
+    }
+
+    #[test]
+    fn expands_aliases_that_are_pipelines() {
+        let aliases = aliases(&[("todo", "search todo | tag add")]);
+        let stages = pipeline_stages("todo urgent | count", &aliases).unwrap();
+        let paths: Vec<String> = stages.iter().map(ParsedCommand::name).collect();
+        assert_eq!(paths, ["search", "tag add", "count"]);
+        assert_eq!(stages[1].text("name"), Some("urgent"));
+    }
+
+    #[test]
+    fn reports_pipeline_errors() {
+        let no_aliases = aliases(&[]);
+        let code = |line: &str| pipeline_stages(line, &no_aliases).unwrap_err().code;
+        assert_eq!(code("ls |"), "shell.empty_pipeline_stage");
+        assert_eq!(code("echo \"open"), "shell.unterminated_double_quote");
+        assert_eq!(code("nope"), "command.not_found");
+        assert_eq!(code("search todo | goto"), "command.missing_argument");
+    }
+
+    #[test]
+    fn resolves_history_events() {
+        let state = history(&["ls", "pwd", "date"]);
+        let command = |event: &str| history_event(&state, event).map(|e| e.command.as_str());
+        assert_eq!(command("!"), Some("date"));
+        assert_eq!(command("1"), Some("ls"));
+        assert_eq!(command("-1"), Some("date"));
+        assert_eq!(command("-3"), Some("ls"));
+        assert_eq!(command("0"), None);
+        assert_eq!(command("4"), None);
+        assert_eq!(command("-4"), None);
+        assert_eq!(command("ls"), None);
+
+        assert_eq!(history_event(&history(&[]), "!").map(|e| &e.command), None);
+    }
+
+    #[test]
+    fn formats_history_with_aligned_numbers() {
+        let dates = DateSettings {
+            timezone: Timezone::Fixed(Utc.fix()),
+            locale: "en",
+        };
+        let first = entry("ls", "2024-03-05 14:07:09", 0);
+        let second = entry("nope", "2024-03-05 14:08:00", 1);
+        assert_eq!(
+            format_history(&[(9, &first), (10, &second)], &dates),
+            " 9  03/05/2024 02:07:09 PM  [0]  ls\n10  03/05/2024 02:08:00 PM  [1]  nope"
+        );
+
+        let german = DateSettings {
+            timezone: Timezone::Fixed(FixedOffset::east_opt(3600).unwrap()),
+            locale: "de",
+        };
+        assert_eq!(
+            format_history(&[(1, &first)], &german),
+            "1  05.03.2024 15:07:09  [0]  ls"
+        );
+    }
+
+    #[test]
+    fn error_output_carries_the_message() {
+        let error = FenrisError::not_found("command.not_found", &[("command", &"nope")]);
+        let output = ShellOutput::error(String::from("nope"), error);
+        assert!(!output.success);
+        assert_eq!(output.output, "Command not found: nope");
+        assert_eq!(
+            output.error.unwrap().kind,
+            crate::error::ErrorKind::NotFound
+        );
+    }
+}
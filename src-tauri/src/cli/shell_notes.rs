@@ -0,0 +1,981 @@
+use crate::cli::shell_table::format_table;
+use crate::error::FenrisError;
+use crate::i18n::t;
+use crate::store::{DateSettings, DateStyle};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::collections::HashSet;
+
+// Single notes and folder notes live in different tables, so ids are only unique per kind
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteKind {
+    Single,
+    Folder,
+}
+
+impl NoteKind {
+    // Value used in note_tags.note_type
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NoteKind::Single => "single",
+            NoteKind::Folder => "folder",
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            NoteKind::Single => "single_notes",
+            NoteKind::Folder => "note",
+        }
+    }
+
+    fn prefix(self) -> char {
+        match self {
+            NoteKind::Single => 's',
+            NoteKind::Folder => 'f',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteRow {
+    pub id: i64,
+    pub kind: NoteKind,
+    pub title: String,
+    pub folder_id: Option<i64>,
+    pub folder: Option<String>,
+    /// Content length in characters
+    pub size: i64,
+    pub tags: i64,
+    pub date_modified: String,
+}
+
+impl NoteRow {
+    /// Unique id shown in tables, e.g. "s12" for a single note and "f7" for a folder note
+    pub fn reference(&self) -> String {
+        format!("{}{}", self.kind.prefix(), self.id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FolderRow {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub date_modified: String,
+}
+
+type NoteTuple = (
+    i64,
+    String,
+    String,
+    Option<i64>,
+    Option<String>,
+    i64,
+    i64,
+    String,
+);
+type FolderTuple = (i64, String, Option<i64>, String);
+
+// Both note tables as one, callers append their own WHERE and ORDER BY
+const ALL_NOTES: &str =
+    "SELECT id, kind, title, folder_id, folder, size, tags, date_modified FROM (
+    SELECT id, 'single' AS kind, title, NULL AS folder_id, NULL AS folder,
+           length(content) AS size,
+           (SELECT COUNT(*) FROM note_tags
+            WHERE note_tags.note_id = single_notes.id AND note_tags.note_type = 'single') AS tags,
+           CAST(date_modified AS TEXT) AS date_modified
+    FROM single_notes
+    UNION ALL
+    SELECT note.id, 'folder', note.title, note.folder_id, folders.name,
+           length(note.content),
+           (SELECT COUNT(*) FROM note_tags
+            WHERE note_tags.note_id = note.id AND note_tags.note_type = 'folder'),
+           CAST(note.date_modified AS TEXT)
+    FROM note LEFT JOIN folders ON folders.id = note.folder_id
+)";
+
+const ALL_FOLDERS: &str =
+    "SELECT id, name, parent_id, CAST(date_modified AS TEXT) AS date_modified FROM folders";
+
+fn note_row((id, kind, title, folder_id, folder, size, tags, date_modified): NoteTuple) -> NoteRow {
+    NoteRow {
+        id,
+        kind: if kind == "folder" {
+            NoteKind::Folder
+        } else {
+            NoteKind::Single
+        },
+        title,
+        folder_id,
+        folder,
+        size,
+        tags,
+        date_modified,
+    }
+}
+
+fn folder_row((id, name, parent_id, date_modified): FolderTuple) -> FolderRow {
+    FolderRow {
+        id,
+        name,
+        parent_id,
+        date_modified,
+    }
+}
+
+async fn notes_by_id(
+    pool: &Pool<Sqlite>,
+    id: i64,
+    kind: Option<NoteKind>,
+) -> Result<Vec<NoteRow>, FenrisError> {
+    let rows: Vec<NoteTuple> = sqlx::query_as(&format!(
+        "{} WHERE id = ?1 AND (?2 IS NULL OR kind = ?2) ORDER BY kind",
+        ALL_NOTES
+    ))
+    .bind(id)
+    .bind(kind.map(NoteKind::as_str))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("note.read_failed", &e, &[]))?;
+    Ok(rows.into_iter().map(note_row).collect())
+}
+
+async fn notes_by_title(pool: &Pool<Sqlite>, title: &str) -> Result<Vec<NoteRow>, FenrisError> {
+    let rows: Vec<NoteTuple> = sqlx::query_as(&format!(
+        "{} WHERE title = ? COLLATE NOCASE ORDER BY folder, id",
+        ALL_NOTES
+    ))
+    .bind(title)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("note.read_failed", &e, &[]))?;
+    let notes: Vec<NoteRow> = rows.into_iter().map(note_row).collect();
+
+    // An exact match wins over notes that only differ in case
+    let exact: Vec<NoteRow> = notes
+        .iter()
+        .filter(|note| note.title == title)
+        .cloned()
+        .collect();
+    Ok(if exact.is_empty() { notes } else { exact })
+}
+
+// "s12" and "f7" name one note, "12" may match a note of either kind
+fn parse_note_reference(reference: &str) -> Option<(Option<NoteKind>, i64)> {
+    let (kind, digits) = match reference.chars().next()? {
+        's' | 'S' => (Some(NoteKind::Single), &reference[1..]),
+        'f' | 'F' => (Some(NoteKind::Folder), &reference[1..]),
+        _ => (None, reference),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().map(|id| (kind, id))
+}
+
+// Accepts an id, a title or a folder path ending in a title. Titles shared by several notes are
+// listed so the user can pick an id, notes in the working folder win over notes elsewhere
+pub async fn resolve_note(
+    pool: &Pool<Sqlite>,
+    cwd: Option<&FolderRow>,
+    reference: &str,
+) -> Result<NoteRow, FenrisError> {
+    let reference = reference.trim();
+    let mut notes = Vec::new();
+
+    if let Some((path, title)) = reference.rsplit_once('/') {
+        let folder = resolve_path(pool, cwd, if path.is_empty() { "/" } else { path }).await?;
+        notes = notes_by_title(pool, title)
+            .await?
+            .into_iter()
+            .filter(|note| in_folder(note, folder.as_ref()))
+            .collect();
+    } else {
+        if let Some((kind, id)) = parse_note_reference(reference) {
+            notes = notes_by_id(pool, id, kind).await?;
+        }
+        if notes.is_empty() {
+            let all = notes_by_title(pool, reference).await?;
+            let here: Vec<NoteRow> = all
+                .iter()
+                .filter(|note| in_folder(note, cwd))
+                .cloned()
+                .collect();
+            notes = if here.is_empty() { all } else { here };
+        }
+    }
+
+    match notes.len() {
+        0 => Err(FenrisError::not_found(
+            "note.not_found",
+            &[("note", &reference)],
+        )),
+        1 => Ok(notes.remove(0)),
+        count => Err(FenrisError::conflict(
+            "note.ambiguous",
+            &[
+                ("note", &reference),
+                ("count", &count),
+                ("matches", &note_matches_table(&notes)),
+            ],
+        )),
+    }
+}
+
+fn in_folder(note: &NoteRow, folder: Option<&FolderRow>) -> bool {
+    note.folder_id == folder.map(|folder| folder.id)
+}
+
+pub async fn folder_by_id(pool: &Pool<Sqlite>, id: i64) -> Result<Option<FolderRow>, FenrisError> {
+    let row: Option<FolderTuple> = sqlx::query_as(&format!("{} WHERE id = ?", ALL_FOLDERS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| FenrisError::sqlx("folder.read_failed", &e, &[]))?;
+    Ok(row.map(folder_row))
+}
+
+async fn child_folder(
+    pool: &Pool<Sqlite>,
+    parent_id: Option<i64>,
+    name: &str,
+) -> Result<Option<FolderRow>, FenrisError> {
+    let rows: Vec<FolderTuple> = sqlx::query_as(&format!(
+        "{} WHERE parent_id IS ? AND name = ? COLLATE NOCASE ORDER BY id",
+        ALL_FOLDERS
+    ))
+    .bind(parent_id)
+    .bind(name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("folder.read_failed", &e, &[]))?;
+    let mut folders: Vec<FolderRow> = rows.into_iter().map(folder_row).collect();
+    let index = folders
+        .iter()
+        .position(|folder| folder.name == name)
+        .unwrap_or(0);
+    Ok((!folders.is_empty()).then(|| folders.swap_remove(index)))
+}
+
+// Resolves `/`, `..`, `Work/Drafts` or `/Work` against the working folder, None is the top level.
+// A bare name or id that is not inside the working folder still finds the folder anywhere
+pub async fn resolve_path(
+    pool: &Pool<Sqlite>,
+    cwd: Option<&FolderRow>,
+    path: &str,
+) -> Result<Option<FolderRow>, FenrisError> {
+    let path = path.trim();
+    if !path.is_empty() && !path.contains('/') && path != "." && path != ".." {
+        if let Some(child) = child_folder(pool, cwd.map(|folder| folder.id), path).await? {
+            return Ok(Some(child));
+        }
+        return find_folder(pool, path).await.map(Some);
+    }
+
+    let mut current = if path.starts_with('/') {
+        None
+    } else {
+        cwd.cloned()
+    };
+    for component in path.split('/') {
+        current = match component {
+            "" | "." => current,
+            ".." => match current.and_then(|folder| folder.parent_id) {
+                Some(parent_id) => folder_by_id(pool, parent_id).await?,
+                None => None,
+            },
+            name => Some(
+                child_folder(pool, current.map(|folder| folder.id), name)
+                    .await?
+                    .ok_or_else(|| {
+                        FenrisError::not_found("folder.not_found", &[("folder", &path)])
+                    })?,
+            ),
+        };
+    }
+    Ok(current)
+}
+
+// Like resolve_path, for commands that need an actual folder rather than the top level
+pub async fn resolve_folder(
+    pool: &Pool<Sqlite>,
+    cwd: Option<&FolderRow>,
+    reference: &str,
+) -> Result<FolderRow, FenrisError> {
+    resolve_path(pool, cwd, reference)
+        .await?
+        .ok_or_else(|| FenrisError::validation("folder.top_level", &[]))
+}
+
+pub async fn folder_path(
+    pool: &Pool<Sqlite>,
+    folder: Option<&FolderRow>,
+) -> Result<String, FenrisError> {
+    let mut names = Vec::new();
+    let mut seen = HashSet::new();
+    let mut current = folder.cloned();
+    while let Some(folder) = current {
+        if !seen.insert(folder.id) {
+            break;
+        }
+        names.push(folder.name);
+        current = match folder.parent_id {
+            Some(parent_id) => folder_by_id(pool, parent_id).await?,
+            None => None,
+        };
+    }
+    names.reverse();
+    Ok(format!("/{}", names.join("/")))
+}
+
+// Folder anywhere in the tree by id or name
+async fn find_folder(pool: &Pool<Sqlite>, reference: &str) -> Result<FolderRow, FenrisError> {
+    if let Ok(id) = reference.parse::<i64>() {
+        if let Some(folder) = folder_by_id(pool, id).await? {
+            return Ok(folder);
+        }
+    }
+
+    let rows: Vec<FolderTuple> = sqlx::query_as(&format!(
+        "{} WHERE name = ? COLLATE NOCASE ORDER BY id",
+        ALL_FOLDERS
+    ))
+    .bind(reference)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("folder.read_failed", &e, &[]))?;
+    let mut folders: Vec<FolderRow> = rows.into_iter().map(folder_row).collect();
+    if let Some(index) = folders.iter().position(|folder| folder.name == reference) {
+        return Ok(folders.swap_remove(index));
+    }
+
+    match folders.len() {
+        0 => Err(FenrisError::not_found(
+            "folder.not_found",
+            &[("folder", &reference)],
+        )),
+        1 => Ok(folders.remove(0)),
+        count => Err(FenrisError::conflict(
+            "folder.ambiguous",
+            &[
+                ("folder", &reference),
+                ("count", &count),
+                ("matches", &folder_matches_table(&folders)),
+            ],
+        )),
+    }
+}
+
+async fn note_by_id(pool: &Pool<Sqlite>, kind: NoteKind, id: i64) -> Result<NoteRow, FenrisError> {
+    notes_by_id(pool, id, Some(kind))
+        .await?
+        .pop()
+        .ok_or_else(|| {
+            FenrisError::not_found(
+                "note.not_found",
+                &[("note", &format!("{}{}", kind.prefix(), id))],
+            )
+        })
+}
+
+pub async fn all_notes(pool: &Pool<Sqlite>) -> Result<Vec<NoteRow>, FenrisError> {
+    let rows: Vec<NoteTuple> =
+        sqlx::query_as(&format!("{} ORDER BY title COLLATE NOCASE", ALL_NOTES))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| FenrisError::sqlx("note.read_failed", &e, &[]))?;
+    Ok(rows.into_iter().map(note_row).collect())
+}
+
+pub async fn notes_with_tag(pool: &Pool<Sqlite>, tag: &str) -> Result<Vec<NoteRow>, FenrisError> {
+    let rows: Vec<NoteTuple> = sqlx::query_as(&format!(
+        "SELECT * FROM ({}) AS notes WHERE EXISTS (
+            SELECT 1 FROM note_tags JOIN tags ON tags.id = note_tags.tag_id
+            WHERE tags.name = ? AND note_tags.note_id = notes.id
+              AND note_tags.note_type = notes.kind
+         )
+         ORDER BY title COLLATE NOCASE",
+        ALL_NOTES
+    ))
+    .bind(tag)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("note.read_failed", &e, &[]))?;
+    Ok(rows.into_iter().map(note_row).collect())
+}
+
+pub async fn search_notes(pool: &Pool<Sqlite>, text: &str) -> Result<Vec<NoteRow>, FenrisError> {
+    let text = non_blank(text, "search.text_empty")?;
+    let pattern = format!(
+        "%{}%",
+        text.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+
+    let rows: Vec<NoteTuple> = sqlx::query_as(&format!(
+        "{} WHERE title LIKE ?1 ESCAPE '\\'
+            OR id IN (SELECT id FROM single_notes WHERE content LIKE ?1 ESCAPE '\\') AND kind = 'single'
+            OR id IN (SELECT id FROM note WHERE content LIKE ?1 ESCAPE '\\') AND kind = 'folder'
+         ORDER BY title COLLATE NOCASE",
+        ALL_NOTES
+    ))
+    .bind(pattern)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("note.search_failed", &e, &[]))?;
+    Ok(rows.into_iter().map(note_row).collect())
+}
+
+pub async fn create_note(
+    pool: &Pool<Sqlite>,
+    title: &str,
+    folder: Option<&FolderRow>,
+) -> Result<NoteRow, FenrisError> {
+    let title = non_blank(title, "note.title_empty")?;
+    let now = timestamp();
+
+    let result = match folder {
+        Some(folder) => sqlx::query(
+            "INSERT INTO note (title, content, folder_id, date_created, date_modified) VALUES (?, '', ?, ?, ?)",
+        )
+        .bind(title)
+        .bind(folder.id)
+        .bind(&now)
+        .bind(&now)
+        .execute(pool)
+        .await,
+        None => sqlx::query(
+            "INSERT INTO single_notes (title, content, date_created, date_modified) VALUES (?, '', ?, ?)",
+        )
+        .bind(title)
+        .bind(&now)
+        .bind(&now)
+        .execute(pool)
+        .await,
+    }
+    .map_err(|e| FenrisError::sqlx("note.create_failed", &e, &[]))?;
+
+    let kind = if folder.is_some() {
+        NoteKind::Folder
+    } else {
+        NoteKind::Single
+    };
+    note_by_id(pool, kind, result.last_insert_rowid()).await
+}
+
+pub async fn note_content(pool: &Pool<Sqlite>, note: &NoteRow) -> Result<String, FenrisError> {
+    let (content,): (String,) = sqlx::query_as(&format!(
+        "SELECT content FROM {} WHERE id = ?",
+        note.kind.table()
+    ))
+    .bind(note.id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("note.read_failed", &e, &[]))?;
+    Ok(content)
+}
+
+pub async fn delete_note(pool: &Pool<Sqlite>, note: &NoteRow) -> Result<(), FenrisError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+
+    sqlx::query("DELETE FROM note_tags WHERE note_id = ? AND note_type = ?")
+        .bind(note.id)
+        .bind(note.kind.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| FenrisError::sqlx("note.delete_failed", &e, &[]))?;
+    sqlx::query(&format!("DELETE FROM {} WHERE id = ?", note.kind.table()))
+        .bind(note.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| FenrisError::sqlx("note.delete_failed", &e, &[]))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("note.delete_failed", &e, &[]))
+}
+
+// Moving between the top level and a folder copies the note to the other table, keeping its tags
+pub async fn move_note(
+    pool: &Pool<Sqlite>,
+    note: &NoteRow,
+    folder: Option<&FolderRow>,
+) -> Result<NoteRow, FenrisError> {
+    let target_id = folder.map(|folder| folder.id);
+    if note.folder_id == target_id {
+        return Err(FenrisError::conflict(
+            "note.already_in",
+            &[
+                ("note", &note.title),
+                (
+                    "folder",
+                    &folder.map(|folder| folder.name.as_str()).unwrap_or("/"),
+                ),
+            ],
+        ));
+    }
+
+    let now = timestamp();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+
+    let (kind, id) = match (note.kind, target_id) {
+        (NoteKind::Folder, Some(folder_id)) => {
+            sqlx::query("UPDATE note SET folder_id = ?, date_modified = ? WHERE id = ?")
+                .bind(folder_id)
+                .bind(&now)
+                .bind(note.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| FenrisError::sqlx("note.move_failed", &e, &[]))?;
+            (NoteKind::Folder, note.id)
+        }
+        (_, target) => {
+            let (kind, insert) = match target {
+                Some(_) => (
+                    NoteKind::Folder,
+                    "INSERT INTO note (title, content, folder_id, date_created, date_modified)
+                     SELECT title, content, ?, date_created, ? FROM single_notes WHERE id = ?",
+                ),
+                None => (
+                    NoteKind::Single,
+                    "INSERT INTO single_notes (title, content, date_created, date_modified)
+                     SELECT title, content, date_created, ? FROM note WHERE id = ?",
+                ),
+            };
+            let mut query = sqlx::query(insert);
+            if let Some(folder_id) = target {
+                query = query.bind(folder_id);
+            }
+            let new_id = query
+                .bind(&now)
+                .bind(note.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| FenrisError::sqlx("note.move_failed", &e, &[]))?
+                .last_insert_rowid();
+
+            sqlx::query(&format!("DELETE FROM {} WHERE id = ?", note.kind.table()))
+                .bind(note.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| FenrisError::sqlx("note.move_failed", &e, &[]))?;
+            sqlx::query(
+                "UPDATE note_tags SET note_id = ?, note_type = ? WHERE note_id = ? AND note_type = ?",
+            )
+            .bind(new_id)
+            .bind(kind.as_str())
+            .bind(note.id)
+            .bind(note.kind.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FenrisError::sqlx("note.move_failed", &e, &[]))?;
+            (kind, new_id)
+        }
+    };
+
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("note.move_failed", &e, &[]))?;
+    note_by_id(pool, kind, id).await
+}
+
+pub async fn rename_note(
+    pool: &Pool<Sqlite>,
+    note: &NoteRow,
+    title: &str,
+) -> Result<(), FenrisError> {
+    let title = non_blank(title, "note.title_empty")?;
+    sqlx::query(&format!(
+        "UPDATE {} SET title = ?, date_modified = ? WHERE id = ?",
+        note.kind.table()
+    ))
+    .bind(title)
+    .bind(timestamp())
+    .bind(note.id)
+    .execute(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("note.rename_failed", &e, &[]))?;
+    Ok(())
+}
+
+// Adds the text as a new line at the end of the note
+pub async fn append_to_note(
+    pool: &Pool<Sqlite>,
+    note: &NoteRow,
+    text: &str,
+) -> Result<(), FenrisError> {
+    sqlx::query(&format!(
+        "UPDATE {} SET content = CASE WHEN content = '' THEN ?1 ELSE content || char(10) || ?1 END,
+                date_modified = ?2
+         WHERE id = ?3",
+        note.kind.table()
+    ))
+    .bind(text)
+    .bind(timestamp())
+    .bind(note.id)
+    .execute(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("note.append_failed", &e, &[]))?;
+    Ok(())
+}
+
+pub async fn add_folder(
+    pool: &Pool<Sqlite>,
+    name: &str,
+    parent: Option<&FolderRow>,
+) -> Result<(), FenrisError> {
+    let name = folder_name(name)?;
+    let now = timestamp();
+
+    sqlx::query(
+        "INSERT INTO folders (name, parent_id, date_created, date_modified) VALUES (?, ?, ?, ?)",
+    )
+    .bind(name)
+    .bind(parent.map(|parent| parent.id))
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| match e.as_database_error() {
+        Some(db) if db.is_unique_violation() => {
+            FenrisError::conflict("folder.exists", &[("name", &name)])
+        }
+        _ => FenrisError::sqlx("folder.create_failed", &e, &[]),
+    })?;
+    Ok(())
+}
+
+pub async fn rename_folder(
+    pool: &Pool<Sqlite>,
+    folder: &FolderRow,
+    new_name: &str,
+) -> Result<(), FenrisError> {
+    let new_name = folder_name(new_name)?;
+    sqlx::query("UPDATE folders SET name = ?, date_modified = ? WHERE id = ?")
+        .bind(new_name)
+        .bind(timestamp())
+        .bind(folder.id)
+        .execute(pool)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db) if db.is_unique_violation() => {
+                FenrisError::conflict("folder.exists", &[("name", &new_name)])
+            }
+            _ => FenrisError::sqlx("folder.rename_failed", &e, &[]),
+        })?;
+    Ok(())
+}
+
+// Subfolders and notes directly inside the folder, or at the top level when none is given
+pub async fn list_folder(
+    pool: &Pool<Sqlite>,
+    folder: Option<&FolderRow>,
+) -> Result<(Vec<FolderRow>, Vec<NoteRow>), FenrisError> {
+    let folder_id = folder.map(|folder| folder.id);
+    let folders: Vec<FolderTuple> = sqlx::query_as(&format!(
+        "{} WHERE parent_id IS ? ORDER BY name COLLATE NOCASE",
+        ALL_FOLDERS
+    ))
+    .bind(folder_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("folder.read_failed", &e, &[]))?;
+
+    let notes: Vec<NoteTuple> = sqlx::query_as(&format!(
+        "{} WHERE kind = ? AND folder_id IS ? ORDER BY title COLLATE NOCASE",
+        ALL_NOTES
+    ))
+    .bind(if folder.is_some() { "folder" } else { "single" })
+    .bind(folder_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("note.read_failed", &e, &[]))?;
+
+    Ok((
+        folders.into_iter().map(folder_row).collect(),
+        notes.into_iter().map(note_row).collect(),
+    ))
+}
+
+pub async fn all_folders(pool: &Pool<Sqlite>) -> Result<Vec<FolderRow>, FenrisError> {
+    let rows: Vec<FolderTuple> =
+        sqlx::query_as(&format!("{} ORDER BY name COLLATE NOCASE", ALL_FOLDERS))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| FenrisError::sqlx("folder.read_failed", &e, &[]))?;
+    Ok(rows.into_iter().map(folder_row).collect())
+}
+
+pub async fn folder_tree(pool: &Pool<Sqlite>) -> Result<String, FenrisError> {
+    let folders = all_folders(pool).await?;
+    let notes = all_notes(pool).await?;
+
+    // Folders whose parent no longer exists are shown at the top level
+    let ids: HashSet<i64> = folders.iter().map(|folder| folder.id).collect();
+    let parent_of = |folder: &FolderRow| folder.parent_id.filter(|id| ids.contains(id));
+
+    let mut lines = vec![String::from("/")];
+    let mut visited = HashSet::new();
+    tree_lines(
+        None,
+        "",
+        &folders,
+        &notes,
+        &parent_of,
+        &mut visited,
+        &mut lines,
+    );
+    Ok(lines.join("\n"))
+}
+
+fn tree_lines(
+    parent: Option<i64>,
+    indent: &str,
+    folders: &[FolderRow],
+    notes: &[NoteRow],
+    parent_of: &dyn Fn(&FolderRow) -> Option<i64>,
+    visited: &mut HashSet<i64>,
+    lines: &mut Vec<String>,
+) {
+    let children: Vec<&FolderRow> = folders
+        .iter()
+        .filter(|folder| parent_of(folder) == parent && !visited.contains(&folder.id))
+        .collect();
+    let child_notes: Vec<&NoteRow> = notes
+        .iter()
+        .filter(|note| match parent {
+            Some(id) => note.kind == NoteKind::Folder && note.folder_id == Some(id),
+            None => note.kind == NoteKind::Single,
+        })
+        .collect();
+
+    let total = children.len() + child_notes.len();
+    for (index, folder) in children.into_iter().enumerate() {
+        let last = index + 1 == total;
+        visited.insert(folder.id);
+        lines.push(format!(
+            "{}{}{}/",
+            indent,
+            if last { "└── " } else { "├── " },
+            folder.name
+        ));
+        let indent = format!("{}{}", indent, if last { "    " } else { "│   " });
+        tree_lines(
+            Some(folder.id),
+            &indent,
+            folders,
+            notes,
+            parent_of,
+            visited,
+            lines,
+        );
+    }
+    for (index, note) in child_notes.iter().enumerate() {
+        let last = total - child_notes.len() + index + 1 == total;
+        lines.push(format!(
+            "{}{}{} ({})",
+            indent,
+            if last { "└── " } else { "├── " },
+            note.title,
+            note.reference()
+        ));
+    }
+}
+
+// Ids of the folder and every folder below it
+async fn folder_subtree(pool: &Pool<Sqlite>, folder: &FolderRow) -> Result<Vec<i64>, FenrisError> {
+    let rows: Vec<(i64,)> = sqlx::query_as(
+        "WITH RECURSIVE subtree(id) AS (
+             SELECT ?
+             UNION
+             SELECT folders.id FROM folders JOIN subtree ON folders.parent_id = subtree.id
+         )
+         SELECT id FROM subtree",
+    )
+    .bind(folder.id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("folder.read_failed", &e, &[]))?;
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+// Deletes the folder with its subfolders and their notes, returns (subfolders, notes) removed
+pub async fn delete_folder(
+    pool: &Pool<Sqlite>,
+    folder: &FolderRow,
+) -> Result<(u64, u64), FenrisError> {
+    let subtree = folder_subtree(pool, folder).await?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+
+    let mut notes = 0;
+    for id in &subtree {
+        sqlx::query(
+            "DELETE FROM note_tags WHERE note_type = 'folder'
+             AND note_id IN (SELECT id FROM note WHERE folder_id = ?)",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| FenrisError::sqlx("folder.delete_failed", &e, &[]))?;
+        notes += sqlx::query("DELETE FROM note WHERE folder_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FenrisError::sqlx("folder.delete_failed", &e, &[]))?
+            .rows_affected();
+    }
+    // Deepest folders first, so cascading foreign keys never remove notes behind our back
+    for id in subtree.iter().rev() {
+        sqlx::query("DELETE FROM folders WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FenrisError::sqlx("folder.delete_failed", &e, &[]))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("folder.delete_failed", &e, &[]))?;
+    Ok((subtree.len() as u64 - 1, notes))
+}
+
+pub async fn move_folder(
+    pool: &Pool<Sqlite>,
+    folder: &FolderRow,
+    parent: Option<&FolderRow>,
+) -> Result<(), FenrisError> {
+    if let Some(parent) = parent {
+        if folder_subtree(pool, folder).await?.contains(&parent.id) {
+            return Err(FenrisError::conflict(
+                "folder.move_into_itself",
+                &[("name", &folder.name)],
+            ));
+        }
+    }
+
+    sqlx::query("UPDATE folders SET parent_id = ?, date_modified = ? WHERE id = ?")
+        .bind(parent.map(|parent| parent.id))
+        .bind(timestamp())
+        .bind(folder.id)
+        .execute(pool)
+        .await
+        .map_err(|e| FenrisError::sqlx("folder.move_failed", &e, &[]))?;
+    Ok(())
+}
+
+// Dates are stored in UTC and shown in the timezone and locale from the date settings
+pub fn notes_table(notes: &[NoteRow], dates: &DateSettings) -> String {
+    let rows: Vec<Vec<String>> = notes
+        .iter()
+        .map(|note| {
+            vec![
+                note.reference(),
+                note.title.clone(),
+                note.folder.clone().unwrap_or_else(|| String::from("/")),
+                note.size.to_string(),
+                dates.format_stored(&note.date_modified, DateStyle::DateTime),
+            ]
+        })
+        .collect();
+    format_table(&["id", "title", "folder", "size", "modified"], &rows)
+}
+
+// Candidates for an ambiguous reference, only what is needed to pick one
+fn note_matches_table(notes: &[NoteRow]) -> String {
+    let rows: Vec<Vec<String>> = notes
+        .iter()
+        .map(|note| {
+            vec![
+                note.reference(),
+                note.title.clone(),
+                note.folder.clone().unwrap_or_else(|| String::from("/")),
+            ]
+        })
+        .collect();
+    format_table(&["id", "title", "folder"], &rows)
+}
+
+fn folder_matches_table(folders: &[FolderRow]) -> String {
+    let rows: Vec<Vec<String>> = folders
+        .iter()
+        .map(|folder| vec![folder.id.to_string(), folder.name.clone()])
+        .collect();
+    format_table(&["id", "name"], &rows)
+}
+
+// Folder contents in one table, folders first
+pub fn listing_table(folders: &[FolderRow], notes: &[NoteRow], dates: &DateSettings) -> String {
+    let rows: Vec<Vec<String>> = folders
+        .iter()
+        .map(|folder| {
+            vec![
+                folder.id.to_string(),
+                t("shell.listing.folder", &[]),
+                String::from("-"),
+                String::from("-"),
+                dates.format_stored(&folder.date_modified, DateStyle::DateTime),
+                format!("{}/", folder.name),
+            ]
+        })
+        .chain(notes.iter().map(|note| {
+            vec![
+                note.reference(),
+                t("shell.listing.note", &[]),
+                note.size.to_string(),
+                note.tags.to_string(),
+                dates.format_stored(&note.date_modified, DateStyle::DateTime),
+                note.title.clone(),
+            ]
+        }))
+        .collect();
+    format_table(&["id", "type", "size", "tags", "modified", "name"], &rows)
+}
+
+// Plain `ls`, folders first and marked with a trailing slash
+pub fn listing_names(folders: &[FolderRow], notes: &[NoteRow]) -> String {
+    folders
+        .iter()
+        .map(|folder| format!("{}/", folder.name))
+        .chain(notes.iter().map(|note| note.title.clone()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Folder names are path components, so they cannot contain '/' or be '.' or '..'
+fn folder_name(name: &str) -> Result<&str, FenrisError> {
+    let name = non_blank(name, "folder.name_empty")?;
+    if name.contains('/') || name == "." || name == ".." {
+        return Err(FenrisError::validation(
+            "folder.invalid_name",
+            &[("name", &name)],
+        ));
+    }
+    Ok(name)
+}
+
+// The code names the error raised for a blank value, e.g. "note.title_empty"
+pub fn non_blank<'a>(value: &'a str, code: &str) -> Result<&'a str, FenrisError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(FenrisError::validation(code, &[]));
+    }
+    Ok(value)
+}
+
+pub fn timestamp() -> String {
+    let now: DateTime<Utc> = Utc::now();
+    now.format("%Y-%m-%d %H:%M:%S").to_string()
+}
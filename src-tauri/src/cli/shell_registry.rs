@@ -0,0 +1,1180 @@
+use crate::error::FenrisError;
+use crate::i18n::t;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "type", content = "values", rename_all = "lowercase")]
+pub enum ArgKind {
+    Text,
+    Integer,
+    Choice(&'static [&'static str]),
+    /// Takes every remaining word, joined by single spaces
+    Rest,
+    /// Literal switch such as "-l", skipped when the word is something else
+    Flag(&'static str),
+}
+
+// Where completion candidates for an argument come from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgSource {
+    None,
+    Commands,
+    Folders,
+    Notes,
+    Tags,
+    Themes,
+    Models,
+    Aliases,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub required: bool,
+    pub source: ArgSource,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    /// Catalog key of the one-line description shown by help and completion
+    pub summary: &'static str,
+    pub args: &'static [ArgSpec],
+    pub subcommands: &'static [CommandSpec],
+    pub examples: &'static [&'static str],
+    /// Whether the command consumes the results of the previous pipeline stage
+    pub reads_input: bool,
+}
+
+#[derive(Debug)]
+pub struct ParsedCommand {
+    /// Command and subcommand names, e.g. ["add", "folder"]
+    pub path: Vec<&'static str>,
+    pub args: Map<String, Value>,
+    pub reads_input: bool,
+}
+
+impl ParsedCommand {
+    pub fn name(&self) -> String {
+        self.path.join(" ")
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        self.args.get(name).and_then(Value::as_str)
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        self.args.get(name).and_then(Value::as_i64)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.args
+            .get(name)
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+}
+
+const fn arg(name: &'static str, kind: ArgKind) -> ArgSpec {
+    ArgSpec {
+        name,
+        kind,
+        required: true,
+        source: ArgSource::None,
+    }
+}
+
+const fn optional(name: &'static str, kind: ArgKind) -> ArgSpec {
+    ArgSpec {
+        name,
+        kind,
+        required: false,
+        source: ArgSource::None,
+    }
+}
+
+impl CommandSpec {
+    pub fn summary(&self) -> String {
+        t(self.summary, &[])
+    }
+
+    // Lets a group run on its own, its arguments apply when no subcommand is given
+    const fn with_args(self, args: &'static [ArgSpec]) -> Self {
+        CommandSpec { args, ..self }
+    }
+
+    const fn reads_input(self) -> Self {
+        CommandSpec {
+            reads_input: true,
+            ..self
+        }
+    }
+}
+
+impl ArgSpec {
+    const fn from(self, source: ArgSource) -> Self {
+        ArgSpec { source, ..self }
+    }
+}
+
+const fn leaf(
+    name: &'static str,
+    summary: &'static str,
+    args: &'static [ArgSpec],
+    examples: &'static [&'static str],
+) -> CommandSpec {
+    CommandSpec {
+        name,
+        summary,
+        args,
+        subcommands: &[],
+        examples,
+        reads_input: false,
+    }
+}
+
+const fn group(
+    name: &'static str,
+    summary: &'static str,
+    subcommands: &'static [CommandSpec],
+) -> CommandSpec {
+    CommandSpec {
+        name,
+        summary,
+        args: &[],
+        subcommands,
+        examples: &[],
+        reads_input: false,
+    }
+}
+
+pub const PAGES: &[&str] = &["home", "settings", "json", "ai"];
+pub const LANGUAGES: &[&str] = &["en", "fr", "de"];
+pub const TOGGLE_TARGETS: &[&str] = &["navbar", "json-stats"];
+pub const APP_THEMES: &[&str] = &["light", "dark", "nord"];
+pub const ON_ERROR: &[&str] = &["stop", "continue"];
+
+pub static COMMANDS: &[CommandSpec] = &[
+    leaf(
+        "help",
+        "commands.shell.help",
+        &[optional("command", ArgKind::Rest).from(ArgSource::Commands)],
+        &["help", "help add folder"],
+    ),
+    leaf("clear", "commands.shell.clear", &[], &[]),
+    group(
+        "date",
+        "commands.shell.date",
+        &[
+            leaf(
+                "timezone",
+                "commands.shell.date timezone",
+                &[optional("zone", ArgKind::Text)],
+                &[
+                    "date timezone",
+                    "date timezone +01:00",
+                    "date timezone local",
+                ],
+            ),
+            leaf(
+                "locale",
+                "commands.shell.date locale",
+                &[optional("code", ArgKind::Choice(LANGUAGES))],
+                &["date locale de"],
+            ),
+        ],
+    )
+    .with_args(&[optional("format", ArgKind::Rest)]),
+    leaf("version", "commands.shell.version", &[], &[]),
+    leaf("pwd", "commands.shell.pwd", &[], &[]),
+    leaf(
+        "cd",
+        "commands.shell.cd",
+        &[optional("path", ArgKind::Text).from(ArgSource::Folders)],
+        &["cd Work", "cd ../Archive", "cd /"],
+    ),
+    leaf(
+        "ls",
+        "commands.shell.ls",
+        &[
+            optional("long", ArgKind::Flag("-l")),
+            optional("path", ArgKind::Text).from(ArgSource::Folders),
+        ],
+        &["ls", "ls -l", "ls -l ../Archive"],
+    ),
+    leaf(
+        "echo",
+        "commands.shell.echo",
+        &[optional("text", ArgKind::Rest)],
+        &["echo hello world"],
+    ),
+    leaf(
+        "goto",
+        "commands.shell.goto",
+        &[arg("page", ArgKind::Choice(PAGES))],
+        &["goto settings"],
+    ),
+    leaf(
+        "theme",
+        "commands.shell.theme",
+        &[arg("name", ArgKind::Choice(APP_THEMES))],
+        &["theme dark"],
+    ),
+    leaf(
+        "code-theme",
+        "commands.shell.code-theme",
+        &[arg("name", ArgKind::Text).from(ArgSource::Themes)],
+        &["code-theme atomDark"],
+    ),
+    leaf(
+        "lang",
+        "commands.shell.lang",
+        &[arg("code", ArgKind::Choice(LANGUAGES))],
+        &["lang fr"],
+    ),
+    leaf(
+        "hide",
+        "commands.shell.hide",
+        &[arg("target", ArgKind::Choice(TOGGLE_TARGETS))],
+        &["hide navbar"],
+    ),
+    leaf(
+        "show",
+        "commands.shell.show",
+        &[arg("target", ArgKind::Choice(TOGGLE_TARGETS))],
+        &["show navbar"],
+    ),
+    group(
+        "add",
+        "commands.shell.add",
+        &[
+            leaf(
+                "folder",
+                "commands.shell.add folder",
+                &[arg("name", ArgKind::Text)],
+                &["add folder \"Client A\""],
+            ),
+            leaf(
+                "note",
+                "commands.shell.add note",
+                &[arg("title", ArgKind::Text)],
+                &["add note \"Meeting notes\""],
+            ),
+            leaf(
+                "tag",
+                "commands.shell.add tag",
+                &[arg("name", ArgKind::Text)],
+                &["add tag work"],
+            ),
+        ],
+    ),
+    group(
+        "delete",
+        "commands.shell.delete",
+        &[
+            leaf(
+                "folder",
+                "commands.shell.delete folder",
+                &[arg("folder", ArgKind::Text).from(ArgSource::Folders)],
+                &["delete folder \"Client A\""],
+            ),
+            leaf(
+                "note",
+                "commands.shell.delete note",
+                &[arg("note", ArgKind::Text).from(ArgSource::Notes)],
+                &["delete note \"Meeting notes\""],
+            ),
+            leaf(
+                "tag",
+                "commands.shell.delete tag",
+                &[arg("name", ArgKind::Text).from(ArgSource::Tags)],
+                &["delete tag work"],
+            ),
+        ],
+    ),
+    group(
+        "update",
+        "commands.shell.update",
+        &[leaf(
+            "folder",
+            "commands.shell.update folder",
+            &[
+                arg("current-name", ArgKind::Text).from(ArgSource::Folders),
+                arg("new-name", ArgKind::Text),
+            ],
+            &["update folder \"Old Name\" \"New Name\""],
+        )],
+    ),
+    group(
+        "ai",
+        "commands.shell.ai",
+        &[
+            leaf("status", "commands.shell.ai status", &[], &[]),
+            leaf(
+                "model",
+                "commands.shell.ai model",
+                &[arg("name", ArgKind::Text).from(ArgSource::Models)],
+                &["ai model llama3"],
+            ),
+        ],
+    ),
+    group(
+        "history",
+        "commands.shell.history",
+        &[
+            leaf(
+                "search",
+                "commands.shell.history search",
+                &[arg("text", ArgKind::Rest)],
+                &["history search folder"],
+            ),
+            leaf("clear", "commands.shell.history clear", &[], &[]),
+        ],
+    )
+    .with_args(&[optional("count", ArgKind::Integer)]),
+    group(
+        "note",
+        "commands.shell.note",
+        &[
+            leaf(
+                "new",
+                "commands.shell.note new",
+                &[
+                    arg("title", ArgKind::Text),
+                    optional("folder", ArgKind::Text).from(ArgSource::Folders),
+                ],
+                &["note new \"Meeting notes\"", "note new Ideas Work"],
+            ),
+            leaf(
+                "open",
+                "commands.shell.note open",
+                &[arg("note", ArgKind::Text).from(ArgSource::Notes)],
+                &["note open \"Meeting notes\"", "note open s12"],
+            ),
+            leaf(
+                "cat",
+                "commands.shell.note cat",
+                &[arg("note", ArgKind::Text).from(ArgSource::Notes)],
+                &["note cat f7"],
+            ),
+            leaf(
+                "rm",
+                "commands.shell.note rm",
+                &[arg("note", ArgKind::Text).from(ArgSource::Notes)],
+                &["note rm s12"],
+            ),
+            leaf(
+                "mv",
+                "commands.shell.note mv",
+                &[
+                    arg("note", ArgKind::Text).from(ArgSource::Notes),
+                    arg("folder", ArgKind::Text).from(ArgSource::Folders),
+                ],
+                &["note mv Ideas Work", "note mv f7 /"],
+            ),
+            leaf(
+                "rename",
+                "commands.shell.note rename",
+                &[
+                    arg("note", ArgKind::Text).from(ArgSource::Notes),
+                    arg("title", ArgKind::Text),
+                ],
+                &["note rename s12 \"Weekly meeting\""],
+            ),
+            leaf(
+                "append",
+                "commands.shell.note append",
+                &[
+                    arg("note", ArgKind::Text).from(ArgSource::Notes),
+                    arg("text", ArgKind::Rest),
+                ],
+                &["note append Ideas try the new editor"],
+            ),
+        ],
+    ),
+    group(
+        "folder",
+        "commands.shell.folder",
+        &[
+            leaf(
+                "ls",
+                "commands.shell.folder ls",
+                &[optional("folder", ArgKind::Text).from(ArgSource::Folders)],
+                &["folder ls", "folder ls Work"],
+            ),
+            leaf("tree", "commands.shell.folder tree", &[], &[]),
+            leaf(
+                "rm",
+                "commands.shell.folder rm",
+                &[arg("folder", ArgKind::Text).from(ArgSource::Folders)],
+                &["folder rm Archive"],
+            ),
+            leaf(
+                "mv",
+                "commands.shell.folder mv",
+                &[
+                    arg("folder", ArgKind::Text).from(ArgSource::Folders),
+                    arg("parent", ArgKind::Text).from(ArgSource::Folders),
+                ],
+                &["folder mv Drafts Work", "folder mv Drafts /"],
+            ),
+        ],
+    ),
+    leaf(
+        "search",
+        "commands.shell.search",
+        &[arg("text", ArgKind::Rest)],
+        &["search todo", "search todo | tag add urgent"],
+    ),
+    group(
+        "tag",
+        "commands.shell.tag",
+        &[
+            leaf(
+                "add",
+                "commands.shell.tag add",
+                &[arg("name", ArgKind::Text).from(ArgSource::Tags)],
+                &["search todo | tag add urgent"],
+            )
+            .reads_input(),
+            leaf(
+                "remove",
+                "commands.shell.tag remove",
+                &[arg("name", ArgKind::Text).from(ArgSource::Tags)],
+                &["search done | tag remove urgent"],
+            )
+            .reads_input(),
+        ],
+    ),
+    leaf(
+        "count",
+        "commands.shell.count",
+        &[],
+        &["search todo | count"],
+    )
+    .reads_input(),
+    leaf(
+        "alias",
+        "commands.shell.alias",
+        &[
+            optional("name", ArgKind::Text).from(ArgSource::Aliases),
+            optional("command", ArgKind::Rest),
+        ],
+        &["alias", "alias todo \"search todo | tag add urgent\""],
+    ),
+    leaf(
+        "unalias",
+        "commands.shell.unalias",
+        &[arg("name", ArgKind::Text).from(ArgSource::Aliases)],
+        &["unalias todo"],
+    ),
+    group(
+        "script",
+        "commands.shell.script",
+        &[
+            leaf(
+                "run",
+                "commands.shell.script run",
+                &[
+                    arg("path", ArgKind::Text),
+                    optional("on-error", ArgKind::Choice(ON_ERROR)),
+                ],
+                &[
+                    "script run setup.fenris",
+                    "script run setup.fenris continue",
+                ],
+            ),
+            group(
+                "startup",
+                "commands.shell.script startup",
+                &[leaf(
+                    "clear",
+                    "commands.shell.script startup clear",
+                    &[],
+                    &[],
+                )],
+            )
+            .with_args(&[optional("path", ArgKind::Text)]),
+        ],
+    ),
+    leaf("vacuum", "commands.shell.vacuum", &[], &[]),
+    leaf("exit", "commands.shell.exit", &[], &[]),
+];
+
+// Subcommands of the headless `fenris-cli` binary, notes are resolved from the top level
+pub static CLI_COMMANDS: &[CommandSpec] = &[
+    leaf(
+        "help",
+        "commands.cli.help",
+        &[optional("command", ArgKind::Rest)],
+        &["fenris-cli help", "fenris-cli help note"],
+    ),
+    leaf("version", "commands.cli.version", &[], &[]),
+    leaf(
+        "date",
+        "commands.cli.date",
+        &[optional("format", ArgKind::Rest)],
+        &["fenris-cli date iso", "fenris-cli date %Y-%m-%d"],
+    ),
+    leaf("info", "commands.cli.info", &[], &[]),
+    group(
+        "note",
+        "commands.cli.note",
+        &[
+            leaf(
+                "list",
+                "commands.cli.note list",
+                &[optional("folder", ArgKind::Text)],
+                &["fenris-cli note list", "fenris-cli note list Work/Drafts"],
+            ),
+            leaf(
+                "cat",
+                "commands.cli.note cat",
+                &[arg("note", ArgKind::Text)],
+                &["fenris-cli note cat Work/Ideas", "fenris-cli note cat s12"],
+            ),
+            leaf(
+                "new",
+                "commands.cli.note new",
+                &[
+                    arg("title", ArgKind::Text),
+                    optional("folder", ArgKind::Text),
+                ],
+                &["fenris-cli note new \"Meeting notes\" Work"],
+            ),
+            leaf(
+                "append",
+                "commands.cli.note append",
+                &[arg("note", ArgKind::Text), arg("text", ArgKind::Rest)],
+                &[
+                    "fenris-cli note append Ideas try the new editor",
+                    "date | fenris-cli note append Journal -",
+                ],
+            ),
+            leaf(
+                "rm",
+                "commands.cli.note rm",
+                &[arg("note", ArgKind::Text)],
+                &["fenris-cli note rm s12"],
+            ),
+            leaf(
+                "mv",
+                "commands.cli.note mv",
+                &[arg("note", ArgKind::Text), arg("folder", ArgKind::Text)],
+                &["fenris-cli note mv Ideas Work"],
+            ),
+            leaf(
+                "rename",
+                "commands.cli.note rename",
+                &[arg("note", ArgKind::Text), arg("title", ArgKind::Text)],
+                &["fenris-cli note rename s12 \"Weekly meeting\""],
+            ),
+        ],
+    ),
+    group(
+        "folder",
+        "commands.cli.folder",
+        &[
+            leaf(
+                "ls",
+                "commands.cli.folder ls",
+                &[optional("path", ArgKind::Text)],
+                &["fenris-cli folder ls", "fenris-cli folder ls Work/Drafts"],
+            ),
+            leaf("tree", "commands.cli.folder tree", &[], &[]),
+            leaf(
+                "new",
+                "commands.cli.folder new",
+                &[
+                    arg("name", ArgKind::Text),
+                    optional("parent", ArgKind::Text),
+                ],
+                &["fenris-cli folder new Drafts Work"],
+            ),
+            leaf(
+                "rm",
+                "commands.cli.folder rm",
+                &[arg("folder", ArgKind::Text)],
+                &["fenris-cli folder rm Archive"],
+            ),
+            leaf(
+                "mv",
+                "commands.cli.folder mv",
+                &[arg("folder", ArgKind::Text), arg("parent", ArgKind::Text)],
+                &["fenris-cli folder mv Drafts /"],
+            ),
+            leaf(
+                "rename",
+                "commands.cli.folder rename",
+                &[arg("folder", ArgKind::Text), arg("name", ArgKind::Text)],
+                &["fenris-cli folder rename Drafts Ideas"],
+            ),
+        ],
+    ),
+    group(
+        "tag",
+        "commands.cli.tag",
+        &[
+            leaf("ls", "commands.cli.tag ls", &[], &[]),
+            leaf(
+                "new",
+                "commands.cli.tag new",
+                &[arg("name", ArgKind::Text)],
+                &["fenris-cli tag new urgent"],
+            ),
+            leaf(
+                "rm",
+                "commands.cli.tag rm",
+                &[arg("name", ArgKind::Text)],
+                &["fenris-cli tag rm urgent"],
+            ),
+            leaf(
+                "add",
+                "commands.cli.tag add",
+                &[arg("name", ArgKind::Text), arg("note", ArgKind::Text)],
+                &["fenris-cli tag add urgent Work/Ideas"],
+            ),
+            leaf(
+                "remove",
+                "commands.cli.tag remove",
+                &[arg("name", ArgKind::Text), arg("note", ArgKind::Text)],
+                &["fenris-cli tag remove urgent s12"],
+            ),
+            leaf(
+                "notes",
+                "commands.cli.tag notes",
+                &[arg("name", ArgKind::Text)],
+                &["fenris-cli tag notes urgent"],
+            ),
+        ],
+    ),
+    leaf(
+        "search",
+        "commands.cli.search",
+        &[arg("text", ArgKind::Rest)],
+        &["fenris-cli search todo"],
+    ),
+    group(
+        "export",
+        "commands.cli.export",
+        &[
+            leaf(
+                "note",
+                "commands.cli.export note",
+                &[arg("note", ArgKind::Text), optional("file", ArgKind::Text)],
+                &[
+                    "fenris-cli export note Ideas ideas.md",
+                    "fenris-cli export note s12",
+                ],
+            ),
+            leaf(
+                "folder",
+                "commands.cli.export folder",
+                &[
+                    arg("folder", ArgKind::Text),
+                    arg("directory", ArgKind::Text),
+                ],
+                &["fenris-cli export folder / backup"],
+            ),
+        ],
+    ),
+    group(
+        "json",
+        "commands.cli.json",
+        &[
+            leaf(
+                "format",
+                "commands.cli.json format",
+                &[arg("file", ArgKind::Text)],
+                &[
+                    "fenris-cli json format data.json",
+                    "curl -s $URL | fenris-cli json format -",
+                ],
+            ),
+            leaf(
+                "minify",
+                "commands.cli.json minify",
+                &[arg("file", ArgKind::Text)],
+                &["fenris-cli json minify data.json"],
+            ),
+        ],
+    ),
+];
+
+// Argument the next word binds to after `words`, flags that were not typed are skipped
+pub fn next_arg(spec: &'static CommandSpec, words: &[String]) -> Option<&'static ArgSpec> {
+    let mut args = spec.args.iter().peekable();
+    for word in words {
+        loop {
+            let arg = args.peek()?;
+            match arg.kind {
+                ArgKind::Rest => return Some(arg),
+                ArgKind::Flag(flag) if flag != word => {
+                    args.next();
+                }
+                _ => {
+                    args.next();
+                    break;
+                }
+            }
+        }
+    }
+    args.find(|arg| !matches!(arg.kind, ArgKind::Flag(_)))
+}
+
+pub fn find_command<'a>(commands: &'a [CommandSpec], name: &str) -> Option<&'a CommandSpec> {
+    commands
+        .iter()
+        .find(|spec| spec.name.eq_ignore_ascii_case(name))
+}
+
+pub fn parse_tokens(tokens: &[String]) -> Result<ParsedCommand, FenrisError> {
+    parse_command(COMMANDS, tokens)
+}
+
+// Resolves the command and its subcommands, then binds the remaining words to typed arguments
+pub fn parse_command(
+    commands: &'static [CommandSpec],
+    tokens: &[String],
+) -> Result<ParsedCommand, FenrisError> {
+    let first = tokens
+        .first()
+        .ok_or_else(|| FenrisError::validation("command.missing", &[]))?;
+    let mut spec = find_command(commands, first)
+        .ok_or_else(|| FenrisError::not_found("command.not_found", &[("command", first)]))?;
+    let mut path = vec![spec.name];
+    let mut rest = &tokens[1..];
+
+    while !spec.subcommands.is_empty() {
+        if !spec.args.is_empty()
+            && rest
+                .first()
+                .is_none_or(|token| find_command(spec.subcommands, token).is_none())
+        {
+            break;
+        }
+        let available = names(spec.subcommands);
+        let token = rest.first().ok_or_else(|| {
+            FenrisError::validation(
+                "command.missing_subcommand",
+                &[("command", &path.join(" ")), ("available", &available)],
+            )
+        })?;
+        spec = find_command(spec.subcommands, token).ok_or_else(|| {
+            FenrisError::validation(
+                "command.unknown_subcommand",
+                &[
+                    ("subcommand", token),
+                    ("command", &path.join(" ")),
+                    ("available", &available),
+                ],
+            )
+        })?;
+        path.push(spec.name);
+        rest = &rest[1..];
+    }
+
+    let usage = usage(&path, spec);
+    let mut args = Map::new();
+    let mut consumed = 0;
+
+    for arg in spec.args {
+        if let ArgKind::Rest = arg.kind {
+            let remaining = &rest[consumed..];
+            consumed = rest.len();
+            if remaining.is_empty() {
+                if arg.required {
+                    return Err(missing_argument(arg, &usage));
+                }
+            } else {
+                args.insert(arg.name.to_string(), Value::String(remaining.join(" ")));
+            }
+            break;
+        }
+
+        if let ArgKind::Flag(flag) = arg.kind {
+            if rest.get(consumed).is_some_and(|token| token == flag) {
+                consumed += 1;
+                args.insert(arg.name.to_string(), Value::Bool(true));
+            }
+            continue;
+        }
+
+        let Some(token) = rest.get(consumed) else {
+            if arg.required {
+                return Err(missing_argument(arg, &usage));
+            }
+            continue;
+        };
+        consumed += 1;
+        args.insert(arg.name.to_string(), parse_value(arg, token)?);
+    }
+
+    if consumed < rest.len() {
+        return Err(FenrisError::validation(
+            "command.too_many_arguments",
+            &[("usage", &usage)],
+        ));
+    }
+
+    Ok(ParsedCommand {
+        path,
+        args,
+        reads_input: spec.reads_input,
+    })
+}
+
+fn missing_argument(arg: &ArgSpec, usage: &str) -> FenrisError {
+    FenrisError::validation(
+        "command.missing_argument",
+        &[("argument", &arg.name), ("usage", &usage)],
+    )
+}
+
+fn parse_value(arg: &ArgSpec, token: &str) -> Result<Value, FenrisError> {
+    match arg.kind {
+        ArgKind::Text | ArgKind::Rest => Ok(Value::String(token.to_string())),
+        ArgKind::Flag(_) => Ok(Value::Bool(true)),
+        ArgKind::Integer => token.parse::<i64>().map(Value::from).map_err(|_| {
+            FenrisError::validation(
+                "command.invalid_number",
+                &[("value", &token), ("argument", &arg.name)],
+            )
+        }),
+        ArgKind::Choice(values) => values
+            .iter()
+            .find(|value| value.eq_ignore_ascii_case(token))
+            .map(|value| Value::String(value.to_string()))
+            .ok_or_else(|| {
+                FenrisError::validation(
+                    "command.invalid_choice",
+                    &[
+                        ("value", &token),
+                        ("argument", &arg.name),
+                        ("expected", &values.join(", ")),
+                    ],
+                )
+            }),
+    }
+}
+
+pub fn usage(path: &[&str], spec: &CommandSpec) -> String {
+    let mut usage = path.join(" ");
+    for arg in spec.args {
+        let label = match arg.kind {
+            ArgKind::Choice(values) => values.join("|"),
+            ArgKind::Rest => format!("{}...", arg.name),
+            ArgKind::Flag(flag) => flag.to_string(),
+            _ => arg.name.to_string(),
+        };
+        if arg.required {
+            usage.push_str(&format!(" <{}>", label));
+        } else {
+            usage.push_str(&format!(" [{}]", label));
+        }
+    }
+    usage
+}
+
+// Every runnable command under `spec` as (usage, summary) pairs
+fn leaf_usages(
+    path: &mut Vec<&'static str>,
+    spec: &'static CommandSpec,
+    out: &mut Vec<(String, String)>,
+) {
+    path.push(spec.name);
+    if spec.subcommands.is_empty() || !spec.args.is_empty() {
+        out.push((usage(path, spec), spec.summary()));
+    }
+    for sub in spec.subcommands {
+        leaf_usages(path, sub, out);
+    }
+    path.pop();
+}
+
+pub fn help_text(topic: &[String]) -> Result<String, FenrisError> {
+    help_for(COMMANDS, topic)
+}
+
+// Help is generated from the registry, either for every command or for the given topic
+pub fn help_for(commands: &'static [CommandSpec], topic: &[String]) -> Result<String, FenrisError> {
+    let mut lines = Vec::new();
+
+    if topic.is_empty() {
+        for spec in commands {
+            leaf_usages(&mut Vec::new(), spec, &mut lines);
+        }
+        let width = lines
+            .iter()
+            .map(|(usage, _)| usage.len())
+            .max()
+            .unwrap_or(0);
+        let mut text = format!("{}\n", t("shell.help.available", &[]));
+        for (usage, summary) in lines {
+            text.push_str(&format!(
+                "  {:<width$}  {}\n",
+                usage,
+                summary,
+                width = width
+            ));
+        }
+        text.push_str(&format!("\n{}", t("shell.help.details", &[])));
+        return Ok(text);
+    }
+
+    let no_help = || FenrisError::not_found("help.not_found", &[("topic", &topic.join(" "))]);
+    let mut spec = find_command(commands, &topic[0]).ok_or_else(no_help)?;
+    let mut path = vec![spec.name];
+    for word in &topic[1..] {
+        spec = find_command(spec.subcommands, word).ok_or_else(no_help)?;
+        path.push(spec.name);
+    }
+
+    let mut text = format!("{} - {}\n", path.join(" "), spec.summary());
+    let parent = &path[..path.len() - 1];
+    leaf_usages(&mut parent.to_vec(), spec, &mut lines);
+    text.push_str(&format!("\n{}\n", t("shell.help.usage", &[])));
+    for (usage, summary) in &lines {
+        if spec.subcommands.is_empty() {
+            text.push_str(&format!("  {}\n", usage));
+        } else {
+            text.push_str(&format!("  {}  {}\n", usage, summary));
+        }
+    }
+
+    let examples: Vec<&str> = std::iter::once(spec)
+        .chain(spec.subcommands.iter())
+        .flat_map(|spec| spec.examples.iter().copied())
+        .collect();
+    if !examples.is_empty() {
+        text.push_str(&format!("\n{}\n", t("shell.help.examples", &[])));
+        for example in examples {
+            text.push_str(&format!("  {}\n", example));
+        }
+    }
+
+    Ok(text.trim_end().to_string())
+}
+
+fn names(commands: &[CommandSpec]) -> String {
+    commands
+        .iter()
+        .map(|spec| spec.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::translate;
+
+    static TEST_COMMANDS: &[CommandSpec] = &[
+        leaf(
+            "goto",
+            "commands.shell.goto",
+            &[arg("page", ArgKind::Choice(PAGES))],
+            &["goto json"],
+        ),
+        leaf(
+            "head",
+            "commands.shell.echo",
+            &[
+                arg("count", ArgKind::Integer),
+                optional("text", ArgKind::Rest),
+            ],
+            &[],
+        ),
+        leaf(
+            "ls",
+            "commands.shell.ls",
+            &[
+                optional("long", ArgKind::Flag("-l")),
+                optional("path", ArgKind::Text),
+            ],
+            &["ls -l"],
+        ),
+        group(
+            "add",
+            "commands.shell.add",
+            &[leaf(
+                "folder",
+                "commands.shell.add folder",
+                &[arg("name", ArgKind::Text)],
+                &["add folder Work"],
+            )],
+        ),
+        group(
+            "date",
+            "commands.shell.date",
+            &[leaf(
+                "locale",
+                "commands.shell.date locale",
+                &[optional("code", ArgKind::Choice(LANGUAGES))],
+                &[],
+            )],
+        )
+        .with_args(&[optional("format", ArgKind::Rest)]),
+    ];
+
+    fn parse(line: &str) -> Result<ParsedCommand, FenrisError> {
+        let tokens: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse_command(TEST_COMMANDS, &tokens)
+    }
+
+    fn error(line: &str) -> FenrisError {
+        parse(line).unwrap_err()
+    }
+
+    #[test]
+    fn resolves_subcommands_case_insensitively() {
+        let command = parse("ADD Folder Work").unwrap();
+        assert_eq!(command.path, ["add", "folder"]);
+        assert_eq!(command.text("name"), Some("Work"));
+    }
+
+    #[test]
+    fn reports_missing_and_unknown_commands() {
+        assert_eq!(error("").code, "command.missing");
+        assert_eq!(error("nope").code, "command.not_found");
+        assert_eq!(error("add").code, "command.missing_subcommand");
+
+        let unknown = error("add file");
+        assert_eq!(unknown.code, "command.unknown_subcommand");
+        assert_eq!(unknown.context["available"], "folder");
+    }
+
+    #[test]
+    fn reports_missing_required_arguments_with_usage() {
+        let missing = error("add folder");
+        assert_eq!(missing.code, "command.missing_argument");
+        assert_eq!(missing.context["argument"], "name");
+        assert_eq!(missing.context["usage"], "add folder <name>");
+
+        assert_eq!(error("head").context["usage"], "head <count> [text...]");
+    }
+
+    #[test]
+    fn parses_typed_arguments() {
+        let command = parse("head 3 some more words").unwrap();
+        assert_eq!(command.integer("count"), Some(3));
+        assert_eq!(command.text("text"), Some("some more words"));
+
+        assert_eq!(error("head three").code, "command.invalid_number");
+
+        // Choices are matched case-insensitively and stored in their declared form
+        assert_eq!(parse("goto JSON").unwrap().text("page"), Some("json"));
+        let invalid = error("goto nowhere");
+        assert_eq!(invalid.code, "command.invalid_choice");
+        assert_eq!(invalid.context["expected"], PAGES.join(", "));
+    }
+
+    #[test]
+    fn leaves_optional_arguments_unset() {
+        let command = parse("head 1").unwrap();
+        assert_eq!(command.text("text"), None);
+
+        let command = parse("ls").unwrap();
+        assert!(!command.flag("long"));
+        assert_eq!(command.text("path"), None);
+    }
+
+    #[test]
+    fn binds_flags_only_when_typed() {
+        let command = parse("ls -l Work").unwrap();
+        assert!(command.flag("long"));
+        assert_eq!(command.text("path"), Some("Work"));
+
+        let command = parse("ls Work").unwrap();
+        assert!(!command.flag("long"));
+        assert_eq!(command.text("path"), Some("Work"));
+    }
+
+    #[test]
+    fn rejects_extra_arguments() {
+        let extra = error("ls -l Work Archive");
+        assert_eq!(extra.code, "command.too_many_arguments");
+        assert_eq!(extra.context["usage"], "ls [-l] [path]");
+    }
+
+    #[test]
+    fn groups_with_arguments_run_on_their_own() {
+        let command = parse("date %H:%M").unwrap();
+        assert_eq!(command.path, ["date"]);
+        assert_eq!(command.text("format"), Some("%H:%M"));
+
+        let command = parse("date locale de").unwrap();
+        assert_eq!(command.path, ["date", "locale"]);
+        assert_eq!(command.text("code"), Some("de"));
+
+        assert_eq!(parse("date").unwrap().path, ["date"]);
+    }
+
+    #[test]
+    fn next_arg_skips_flags_that_were_not_typed() {
+        let ls = find_command(TEST_COMMANDS, "ls").unwrap();
+        assert_eq!(next_arg(ls, &[]).map(|arg| arg.name), Some("path"));
+        assert_eq!(
+            next_arg(ls, &[String::from("-l")]).map(|arg| arg.name),
+            Some("path")
+        );
+        assert!(next_arg(ls, &[String::from("Work")]).is_none());
+    }
+
+    #[test]
+    fn help_lists_every_runnable_command() {
+        let help = help_for(TEST_COMMANDS, &[]).unwrap();
+        for usage in [
+            "goto <home|settings|json|ai>",
+            "head <count> [text...]",
+            "ls [-l] [path]",
+            "add folder <name>",
+            "date [format...]",
+            "date locale [en|fr|de]",
+        ] {
+            assert!(help.contains(usage), "{} missing from\n{}", usage, help);
+        }
+        // Groups without arguments of their own are not runnable
+        let usages: Vec<&str> = help
+            .lines()
+            .filter_map(|line| line.trim_start().split("  ").next())
+            .collect();
+        assert!(!usages.contains(&"add"));
+    }
+
+    #[test]
+    fn help_for_a_topic_shows_usage_and_examples() {
+        let topic = [String::from("add"), String::from("folder")];
+        let help = help_for(TEST_COMMANDS, &topic).unwrap();
+        assert!(help.starts_with("add folder - "));
+        assert!(help.contains("  add folder <name>"));
+        assert!(help.contains("  add folder Work"));
+
+        // A group lists its subcommands together with their examples
+        let help = help_for(TEST_COMMANDS, &[String::from("add")]).unwrap();
+        assert!(help.contains("  add folder Work"));
+    }
+
+    #[test]
+    fn help_for_an_unknown_topic_fails() {
+        let topic = [String::from("add"), String::from("file")];
+        let error = help_for(TEST_COMMANDS, &topic).unwrap_err();
+        assert_eq!(error.code, "help.not_found");
+        assert_eq!(error.context["topic"], "add file");
+    }
+
+    #[test]
+    fn every_command_has_a_summary_in_the_catalog() {
+        fn check(commands: &[CommandSpec]) {
+            for spec in commands {
+                assert!(translate(spec.summary, &[]).is_some(), "{}", spec.summary);
+                check(spec.subcommands);
+            }
+        }
+        check(COMMANDS);
+        check(CLI_COMMANDS);
+    }
+}
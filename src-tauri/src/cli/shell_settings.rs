@@ -0,0 +1,148 @@
+use crate::cli::shell_registry::{find_command, COMMANDS};
+use crate::error::FenrisError;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+
+const SHELL_STORE: &str = "shell_settings.json";
+const ALIASES_KEY: &str = "aliases";
+const STARTUP_SCRIPT_KEY: &str = "startup-script";
+const SESSIONS_KEY: &str = "sessions";
+pub const DEFAULT_SESSION: &str = "default";
+pub const SCRIPT_EXTENSION: &str = "fenris";
+
+fn shell_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, FenrisError> {
+    app.store(SHELL_STORE)
+        .map_err(|e| FenrisError::io("settings.open_failed", &[("error", &e)]))
+}
+
+fn save_store(store: &Store<Wry>) -> Result<(), FenrisError> {
+    store
+        .save()
+        .map_err(|e| FenrisError::io("settings.save_failed", &[("error", &e)]))
+}
+
+pub fn load_aliases(app: &AppHandle) -> Result<BTreeMap<String, String>, FenrisError> {
+    let store = shell_store(app)?;
+    Ok(store
+        .get(ALIASES_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_alias(app: &AppHandle, name: &str, command: &str) -> Result<(), FenrisError> {
+    let valid_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid_name {
+        return Err(FenrisError::validation(
+            "alias.invalid_name",
+            &[("name", &name)],
+        ));
+    }
+    if find_command(COMMANDS, name).is_some() {
+        return Err(FenrisError::conflict("alias.builtin", &[("name", &name)]));
+    }
+    if command.trim().is_empty() {
+        return Err(FenrisError::validation("alias.empty_command", &[]));
+    }
+
+    let mut aliases = load_aliases(app)?;
+    aliases.insert(name.to_string(), command.trim().to_string());
+    write_aliases(app, &aliases)
+}
+
+pub fn remove_alias(app: &AppHandle, name: &str) -> Result<(), FenrisError> {
+    let mut aliases = load_aliases(app)?;
+    if aliases.remove(name).is_none() {
+        return Err(FenrisError::not_found(
+            "alias.not_found",
+            &[("name", &name)],
+        ));
+    }
+    write_aliases(app, &aliases)
+}
+
+fn write_aliases(app: &AppHandle, aliases: &BTreeMap<String, String>) -> Result<(), FenrisError> {
+    let store = shell_store(app)?;
+    let value = serde_json::to_value(aliases)
+        .map_err(|e| FenrisError::io("settings.save_failed", &[("error", &e)]))?;
+    store.set(ALIASES_KEY, value);
+    save_store(&store)
+}
+
+pub fn get_startup_script(app: &AppHandle) -> Result<Option<String>, FenrisError> {
+    let store = shell_store(app)?;
+    Ok(store
+        .get(STARTUP_SCRIPT_KEY)
+        .and_then(|value| value.as_str().map(String::from)))
+}
+
+// Stores the absolute path so the script is found whatever the working directory
+pub fn set_startup_script(
+    app: &AppHandle,
+    path: Option<&str>,
+) -> Result<Option<String>, FenrisError> {
+    let store = shell_store(app)?;
+    let path = match path {
+        Some(path) => {
+            let path = script_path(path)?;
+            store.set(
+                STARTUP_SCRIPT_KEY,
+                Value::String(path.display().to_string()),
+            );
+            Some(path.display().to_string())
+        }
+        None => {
+            store.delete(STARTUP_SCRIPT_KEY);
+            None
+        }
+    };
+    save_store(&store)?;
+    Ok(path)
+}
+
+// Working folder of each shell session, None is the top level
+pub fn load_session_cwd(app: &AppHandle, session: &str) -> Result<Option<i64>, FenrisError> {
+    let store = shell_store(app)?;
+    Ok(store
+        .get(SESSIONS_KEY)
+        .and_then(|sessions| sessions.get(session).and_then(Value::as_i64)))
+}
+
+pub fn save_session_cwd(
+    app: &AppHandle,
+    session: &str,
+    folder_id: Option<i64>,
+) -> Result<(), FenrisError> {
+    let store = shell_store(app)?;
+    let mut sessions: BTreeMap<String, Option<i64>> = store
+        .get(SESSIONS_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    sessions.insert(session.to_string(), folder_id);
+    let value = serde_json::to_value(sessions)
+        .map_err(|e| FenrisError::io("settings.save_failed", &[("error", &e)]))?;
+    store.set(SESSIONS_KEY, value);
+    save_store(&store)
+}
+
+pub fn script_path(path: &str) -> Result<PathBuf, FenrisError> {
+    let path = Path::new(path);
+    if path.extension().and_then(|extension| extension.to_str()) != Some(SCRIPT_EXTENSION) {
+        return Err(FenrisError::validation(
+            "script.extension",
+            &[("extension", &SCRIPT_EXTENSION), ("path", &path.display())],
+        ));
+    }
+    path.canonicalize().map_err(|e| {
+        FenrisError::io(
+            "script.open_failed",
+            &[("path", &path.display()), ("error", &e)],
+        )
+    })
+}
@@ -0,0 +1,43 @@
+use crate::i18n::t;
+
+// Left-aligned columns separated by two spaces, the last column is not padded.
+// Columns are keys below "shell.table" in the catalogs, e.g. "title"
+pub fn format_table(columns: &[&str], rows: &[Vec<String>]) -> String {
+    let headers: Vec<String> = columns
+        .iter()
+        .map(|column| t(&format!("shell.table.{}", column), &[]))
+        .collect();
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| {
+        let last = cells.len().saturating_sub(1);
+        cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                if index == last {
+                    cell.to_string()
+                } else {
+                    format!("{:<width$}", cell, width = widths[index])
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    std::iter::once(format_row(headers.iter().map(String::as_str).collect()))
+        .chain(
+            rows.iter()
+                .map(|row| format_row(row.iter().map(String::as_str).collect())),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,161 @@
+use crate::cli::shell_notes::{non_blank, timestamp};
+use crate::error::FenrisError;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagRow {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    /// Number of notes carrying the tag
+    pub notes: i64,
+}
+
+pub async fn list_tags(pool: &Pool<Sqlite>) -> Result<Vec<TagRow>, FenrisError> {
+    let rows: Vec<(i64, String, Option<String>, i64)> = sqlx::query_as(
+        "SELECT tags.id, tags.name, tags.color, COUNT(note_tags.id)
+         FROM tags LEFT JOIN note_tags ON note_tags.tag_id = tags.id
+         GROUP BY tags.id ORDER BY tags.name COLLATE NOCASE",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("tag.read_failed", &e, &[]))?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, name, color, notes)| TagRow {
+            id,
+            name,
+            color,
+            notes,
+        })
+        .collect())
+}
+
+pub async fn add_tag(pool: &Pool<Sqlite>, name: &str) -> Result<(), FenrisError> {
+    let name = non_blank(name, "tag.name_empty")?;
+
+    sqlx::query("INSERT INTO tags (name, date_created) VALUES (?, ?)")
+        .bind(name)
+        .bind(timestamp())
+        .execute(pool)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db) if db.is_unique_violation() => {
+                FenrisError::conflict("tag.exists", &[("name", &name)])
+            }
+            _ => FenrisError::sqlx("tag.create_failed", &e, &[]),
+        })?;
+    Ok(())
+}
+
+pub async fn delete_tag(pool: &Pool<Sqlite>, name: &str) -> Result<(), FenrisError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+
+    let (id,): (i64,) = sqlx::query_as("SELECT id FROM tags WHERE name = ?")
+        .bind(name)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| FenrisError::sqlx("tag.read_failed", &e, &[]))?
+        .ok_or_else(|| FenrisError::not_found("tag.not_found", &[("name", &name)]))?;
+
+    for sql in [
+        "DELETE FROM note_tags WHERE tag_id = ?",
+        "DELETE FROM json_document_tags WHERE tag_id = ?",
+        "DELETE FROM tags WHERE id = ?",
+    ] {
+        sqlx::query(sql)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FenrisError::sqlx("tag.delete_failed", &e, &[]))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("tag.delete_failed", &e, &[]))
+}
+
+// Returns how many notes gained the tag, creating the tag first when it does not exist
+pub async fn tag_notes(
+    pool: &Pool<Sqlite>,
+    name: &str,
+    notes: &[(i64, &str)],
+) -> Result<u64, FenrisError> {
+    let name = non_blank(name, "tag.name_empty")?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+
+    sqlx::query("INSERT OR IGNORE INTO tags (name, date_created) VALUES (?, ?)")
+        .bind(name)
+        .bind(timestamp())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| FenrisError::sqlx("tag.create_failed", &e, &[]))?;
+    let (tag_id,): (i64,) = sqlx::query_as("SELECT id FROM tags WHERE name = ?")
+        .bind(name)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| FenrisError::sqlx("tag.read_failed", &e, &[]))?;
+
+    let mut changed = 0;
+    for (note_id, note_type) in notes {
+        changed += sqlx::query(
+            "INSERT OR IGNORE INTO note_tags (note_id, tag_id, note_type, date_created) VALUES (?, ?, ?, ?)",
+        )
+        .bind(note_id)
+        .bind(tag_id)
+        .bind(note_type)
+        .bind(timestamp())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| FenrisError::sqlx("tag.apply_failed", &e, &[]))?
+        .rows_affected();
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("tag.apply_failed", &e, &[]))?;
+    Ok(changed)
+}
+
+pub async fn untag_notes(
+    pool: &Pool<Sqlite>,
+    name: &str,
+    notes: &[(i64, &str)],
+) -> Result<u64, FenrisError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+
+    let (tag_id,): (i64,) = sqlx::query_as("SELECT id FROM tags WHERE name = ?")
+        .bind(name)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| FenrisError::sqlx("tag.read_failed", &e, &[]))?
+        .ok_or_else(|| FenrisError::not_found("tag.not_found", &[("name", &name)]))?;
+
+    let mut changed = 0;
+    for (note_id, note_type) in notes {
+        changed +=
+            sqlx::query("DELETE FROM note_tags WHERE note_id = ? AND tag_id = ? AND note_type = ?")
+                .bind(note_id)
+                .bind(tag_id)
+                .bind(note_type)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| FenrisError::sqlx("tag.remove_failed", &e, &[]))?
+                .rows_affected();
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("tag.remove_failed", &e, &[]))?;
+    Ok(changed)
+}
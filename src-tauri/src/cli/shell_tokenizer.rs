@@ -0,0 +1,325 @@
+use crate::error::FenrisError;
+
+// The word under the cursor while the line is still being typed
+#[derive(Debug, Default)]
+pub struct PartialWord {
+    pub text: String,
+    /// Byte offset where the word starts, including an opening quote
+    pub start: usize,
+    pub open_quote: Option<char>,
+}
+
+struct Scan {
+    words: Vec<(usize, String)>,
+    open_quote: Option<char>,
+    trailing_escape: bool,
+    ends_in_word: bool,
+}
+
+// Splits a command line into words, shell style:
+// - whitespace separates words unless quoted or escaped
+// - single quotes keep everything literally
+// - double quotes allow \" \\ \n and \t escapes
+// - a backslash outside quotes escapes the next character
+pub fn tokenize(line: &str) -> Result<Vec<String>, FenrisError> {
+    let scan = scan(line);
+    match scan.open_quote {
+        Some('\'') => {
+            return Err(FenrisError::validation(
+                "shell.unterminated_single_quote",
+                &[],
+            ))
+        }
+        Some(_) => {
+            return Err(FenrisError::validation(
+                "shell.unterminated_double_quote",
+                &[],
+            ))
+        }
+        None if scan.trailing_escape => {
+            return Err(FenrisError::validation("shell.trailing_backslash", &[]))
+        }
+        None => {}
+    }
+    Ok(scan.words.into_iter().map(|(_, word)| word).collect())
+}
+
+// Lenient variant for completion: unterminated quotes are allowed and the last word is returned
+// separately when the line does not end in whitespace
+pub fn tokenize_partial(line: &str) -> (Vec<String>, PartialWord) {
+    let mut scan = scan(line);
+    let current = if scan.ends_in_word {
+        scan.words.pop().map(|(start, text)| PartialWord {
+            text,
+            start,
+            open_quote: scan.open_quote,
+        })
+    } else {
+        None
+    };
+
+    let words = scan.words.into_iter().map(|(_, word)| word).collect();
+    (
+        words,
+        current.unwrap_or(PartialWord {
+            start: line.len(),
+            ..PartialWord::default()
+        }),
+    )
+}
+
+fn scan(line: &str) -> Scan {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    // Set by quotes too, so `""` is kept as an empty word
+    let mut word_start: Option<usize> = None;
+    let mut open_quote = None;
+    let mut trailing_escape = false;
+    let mut chars = line.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        if let Some(quote) = open_quote {
+            match c {
+                c if c == quote => open_quote = None,
+                '\\' if quote == '"' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => current.push('\n'),
+                    Some('t') => current.push('\t'),
+                    Some(c @ ('"' | '\\')) => current.push(c),
+                    Some(c) => {
+                        current.push('\\');
+                        current.push(c);
+                    }
+                    None => trailing_escape = true,
+                },
+                c => current.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(start) = word_start.take() {
+                    words.push((start, std::mem::take(&mut current)));
+                }
+            }
+            '\'' | '"' => {
+                word_start.get_or_insert(index);
+                open_quote = Some(c);
+            }
+            '\\' => {
+                word_start.get_or_insert(index);
+                match chars.next() {
+                    Some((_, c)) => current.push(c),
+                    None => trailing_escape = true,
+                }
+            }
+            c => {
+                word_start.get_or_insert(index);
+                current.push(c);
+            }
+        }
+    }
+
+    let ends_in_word = word_start.is_some();
+    if let Some(start) = word_start {
+        words.push((start, current));
+    }
+    Scan {
+        words,
+        open_quote,
+        trailing_escape,
+        ends_in_word,
+    }
+}
+
+// Byte offsets of every `|` that is neither quoted nor escaped
+pub fn pipe_offsets(line: &str) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut open_quote: Option<char> = None;
+    let mut chars = line.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match (open_quote, c) {
+            (Some('"'), '\\') | (None, '\\') => {
+                chars.next();
+            }
+            (Some(quote), c) if c == quote => open_quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => open_quote = Some(c),
+            (None, '|') => offsets.push(index),
+            (None, _) => {}
+        }
+    }
+    offsets
+}
+
+// Splits `search todo | tag add urgent` into the raw text of each stage
+pub fn split_pipeline(line: &str) -> Result<Vec<String>, FenrisError> {
+    if line.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stages = Vec::new();
+    let mut start = 0;
+    for offset in pipe_offsets(line)
+        .into_iter()
+        .chain(std::iter::once(line.len()))
+    {
+        let stage = line[start..offset].trim();
+        if stage.is_empty() {
+            return Err(FenrisError::validation("shell.empty_pipeline_stage", &[]));
+        }
+        stages.push(stage.to_string());
+        start = offset + 1;
+    }
+    Ok(stages)
+}
+
+// The first word of a command line and the raw, still quoted text after it
+pub fn split_first_word(line: &str) -> Option<(String, &str)> {
+    let mut words = scan(line).words.into_iter();
+    let (_, first) = words.next()?;
+    let rest = words.next().map(|(start, _)| &line[start..]).unwrap_or("");
+    Some((first, rest))
+}
+
+// Quotes a word only when it would not survive tokenizing as is
+pub fn quote_word(word: &str, preferred_quote: Option<char>) -> String {
+    let needs_quotes = word.is_empty()
+        || word
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | '|'));
+    if !needs_quotes && preferred_quote.is_none() {
+        return word.to_string();
+    }
+
+    if preferred_quote == Some('\'') && !word.contains('\'') {
+        return format!("'{}'", word);
+    }
+    format!("\"{}\"", word.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        tokenize(line).unwrap()
+    }
+
+    fn error_code(line: &str) -> String {
+        tokenize(line).unwrap_err().code
+    }
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(words("  add   folder\tWork "), ["add", "folder", "Work"]);
+        assert!(words("   ").is_empty());
+    }
+
+    #[test]
+    fn keeps_quoted_text_together() {
+        assert_eq!(
+            words("add folder \"Client A\""),
+            ["add", "folder", "Client A"]
+        );
+        assert_eq!(words("echo 'a \"b\" c'"), ["echo", "a \"b\" c"]);
+        assert_eq!(words("echo pre\"fix ed\"post"), ["echo", "prefix edpost"]);
+    }
+
+    #[test]
+    fn keeps_empty_quotes_as_a_word() {
+        assert_eq!(words("echo \"\" ''"), ["echo", "", ""]);
+    }
+
+    #[test]
+    fn handles_escapes() {
+        assert_eq!(
+            words(r"add folder Client\ A"),
+            ["add", "folder", "Client A"]
+        );
+        assert_eq!(
+            words(r#"echo "say \"hi\"\n\ttab \\ \x""#),
+            ["echo", "say \"hi\"\n\ttab \\ \\x"]
+        );
+        // Single quotes keep backslashes literally
+        assert_eq!(words(r"echo 'a\nb'"), ["echo", r"a\nb"]);
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes_and_trailing_backslash() {
+        assert_eq!(error_code("echo 'open"), "shell.unterminated_single_quote");
+        assert_eq!(error_code("echo \"open"), "shell.unterminated_double_quote");
+        assert_eq!(
+            error_code(r#"echo "open\"#),
+            "shell.unterminated_double_quote"
+        );
+        assert_eq!(error_code(r"echo end\"), "shell.trailing_backslash");
+    }
+
+    #[test]
+    fn partial_tokenize_returns_the_word_being_typed() {
+        let (words, current) = tokenize_partial("add folder \"Cli");
+        assert_eq!(words, ["add", "folder"]);
+        assert_eq!(current.text, "Cli");
+        assert_eq!(current.start, 11);
+        assert_eq!(current.open_quote, Some('"'));
+
+        let (words, current) = tokenize_partial("add ");
+        assert_eq!(words, ["add"]);
+        assert_eq!(current.text, "");
+        assert_eq!(current.start, 4);
+    }
+
+    #[test]
+    fn splits_pipeline_on_unquoted_pipes() {
+        assert_eq!(
+            split_pipeline("search todo | tag add urgent").unwrap(),
+            ["search todo", "tag add urgent"]
+        );
+        assert_eq!(
+            split_pipeline(r#"echo "a | b" 'c | d' e\|f | count"#).unwrap(),
+            [r#"echo "a | b" 'c | d' e\|f"#, "count"]
+        );
+        assert!(split_pipeline("  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_empty_pipeline_stages() {
+        for line in ["| ls", "ls |", "ls || count"] {
+            assert_eq!(
+                split_pipeline(line).unwrap_err().code,
+                "shell.empty_pipeline_stage"
+            );
+        }
+    }
+
+    #[test]
+    fn splits_first_word_from_raw_rest() {
+        assert_eq!(
+            split_first_word("  ll  \"My Folder\" x"),
+            Some((String::from("ll"), "\"My Folder\" x"))
+        );
+        assert_eq!(split_first_word("ll"), Some((String::from("ll"), "")));
+        assert_eq!(split_first_word("  "), None);
+    }
+
+    #[test]
+    fn quoted_words_tokenize_back_to_themselves() {
+        for word in [
+            "plain",
+            "two words",
+            "",
+            "it's",
+            "say \"hi\"",
+            r"back\slash",
+            "a|b",
+        ] {
+            let quoted = quote_word(word, None);
+            assert_eq!(words(&quoted), [word], "{}", quoted);
+            assert_eq!(split_pipeline(&quoted).unwrap(), [quoted.as_str()]);
+        }
+        assert_eq!(quote_word("plain", None), "plain");
+        assert_eq!(quote_word("a b", Some('\'')), "'a b'");
+    }
+}
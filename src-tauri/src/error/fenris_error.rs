@@ -0,0 +1,108 @@
+use crate::i18n::t;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::sync::{Mutex, MutexGuard};
+
+// Primary result codes, extended codes such as SQLITE_BUSY_SNAPSHOT keep them in the low byte
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+
+// Lets the frontend pick the right UI without parsing messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    NotFound,
+    Validation,
+    Conflict,
+    Io,
+    Network,
+    Database,
+    Internal,
+}
+
+// Error returned by every command, the frontend receives it as {kind, code, message, context}
+#[derive(Debug, Clone, Serialize)]
+pub struct FenrisError {
+    pub kind: ErrorKind,
+    /// Key below "errors" in the catalogs, e.g. "note.not_found"
+    pub code: String,
+    /// Message in the active locale
+    pub message: String,
+    /// Values filled into the message, e.g. {"note": "Todo"}
+    pub context: BTreeMap<String, String>,
+}
+
+impl FenrisError {
+    pub fn with_kind(kind: ErrorKind, code: &str, args: &[(&str, &dyn Display)]) -> Self {
+        Self {
+            kind,
+            code: code.to_string(),
+            message: t(&format!("errors.{}", code), args),
+            context: args
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    pub fn not_found(code: &str, args: &[(&str, &dyn Display)]) -> Self {
+        Self::with_kind(ErrorKind::NotFound, code, args)
+    }
+
+    pub fn validation(code: &str, args: &[(&str, &dyn Display)]) -> Self {
+        Self::with_kind(ErrorKind::Validation, code, args)
+    }
+
+    pub fn conflict(code: &str, args: &[(&str, &dyn Display)]) -> Self {
+        Self::with_kind(ErrorKind::Conflict, code, args)
+    }
+
+    pub fn io(code: &str, args: &[(&str, &dyn Display)]) -> Self {
+        Self::with_kind(ErrorKind::Io, code, args)
+    }
+
+    pub fn network(code: &str, args: &[(&str, &dyn Display)]) -> Self {
+        Self::with_kind(ErrorKind::Network, code, args)
+    }
+
+    pub fn database(code: &str, args: &[(&str, &dyn Display)]) -> Self {
+        Self::with_kind(ErrorKind::Database, code, args)
+    }
+
+    pub fn internal(code: &str, args: &[(&str, &dyn Display)]) -> Self {
+        Self::with_kind(ErrorKind::Internal, code, args)
+    }
+
+    // SQLITE_BUSY and SQLITE_LOCKED become "database.locked" whatever the query was, so callers
+    // can tell a retry apart from a failed query. The error is added to args as "error"
+    pub fn sqlx(code: &str, error: &sqlx::Error, args: &[(&str, &dyn Display)]) -> Self {
+        let locked = error
+            .as_database_error()
+            .and_then(|e| e.code())
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED));
+        if locked {
+            return Self::database("database.locked", &[("error", error)]);
+        }
+
+        let mut args = args.to_vec();
+        args.push(("error", error));
+        Self::database(code, &args)
+    }
+}
+
+impl Display for FenrisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for FenrisError {}
+
+// A command that panicked while holding the lock poisons it, later calls report that instead of panicking too
+pub fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, FenrisError> {
+    mutex
+        .lock()
+        .map_err(|e| FenrisError::internal("state.unavailable", &[("error", &e)]))
+}
@@ -0,0 +1,3 @@
+mod fenris_error;
+
+pub use fenris_error::*;
@@ -0,0 +1,87 @@
+use crate::error::FenrisError;
+use serde_json::Value;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use tauri::command;
+
+// Same languages as the frontend translations, English is the fallback for missing keys
+pub const LOCALES: &[&str] = &["en", "fr", "de"];
+const CATALOG_SOURCES: [&str; 3] = [
+    include_str!("translations/en.json"),
+    include_str!("translations/fr.json"),
+    include_str!("translations/de.json"),
+];
+
+static ACTIVE_LOCALE: AtomicUsize = AtomicUsize::new(0);
+static CATALOGS: OnceLock<Vec<Value>> = OnceLock::new();
+
+fn catalogs() -> &'static [Value] {
+    CATALOGS.get_or_init(|| {
+        CATALOG_SOURCES
+            .iter()
+            .map(|source| serde_json::from_str(source).expect("invalid message catalog"))
+            .collect()
+    })
+}
+
+fn lookup(locale: usize, key: &str) -> Option<&'static str> {
+    key.split('.')
+        .try_fold(&catalogs()[locale], |value, part| value.get(part))?
+        .as_str()
+}
+
+// Same placeholders as i18next, e.g. "Folder '{{name}}' created"
+fn interpolate(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{{{}}}}}", name), &value.to_string())
+        })
+}
+
+pub fn locale() -> &'static str {
+    LOCALES[ACTIVE_LOCALE.load(Ordering::Relaxed)]
+}
+
+// Accepts i18next codes such as "fr-FR" and environment values such as "de_DE.UTF-8"
+pub fn supported_locale(code: &str) -> Option<&'static str> {
+    let language = code.split(['-', '_', '.']).next().unwrap_or_default();
+    LOCALES
+        .iter()
+        .find(|locale| locale.eq_ignore_ascii_case(language))
+        .copied()
+}
+
+pub fn set_active_locale(code: &str) -> Result<&'static str, FenrisError> {
+    let locale = supported_locale(code).ok_or_else(|| {
+        FenrisError::validation(
+            "locale.unknown",
+            &[("locale", &code), ("available", &LOCALES.join(", "))],
+        )
+    })?;
+    let index = LOCALES.iter().position(|code| *code == locale).unwrap_or(0);
+    ACTIVE_LOCALE.store(index, Ordering::Relaxed);
+    Ok(locale)
+}
+
+// None when the key is missing from the active catalog and from the English one
+pub fn translate(key: &str, args: &[(&str, &dyn Display)]) -> Option<String> {
+    lookup(ACTIVE_LOCALE.load(Ordering::Relaxed), key)
+        .or_else(|| lookup(0, key))
+        .map(|template| interpolate(template, args))
+}
+
+// Message for the key in the active locale, the key itself when no catalog has it
+pub fn t(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    translate(key, args).unwrap_or_else(|| key.to_string())
+}
+
+#[command]
+pub fn get_locale() -> String {
+    locale().to_string()
+}
+
+#[command]
+pub fn set_locale(locale: String) -> Result<String, FenrisError> {
+    set_active_locale(&locale).map(String::from)
+}
@@ -0,0 +1,427 @@
+use crate::error::{lock, FenrisError};
+use crate::json::api_request::{execute_api_request, ApiRequest, ApiResponse, KeyValue};
+use crate::json::json_formatter::FormatOptions;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{from_str, from_value, to_string_pretty, to_value, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, LazyLock, Mutex};
+use tauri::{command, AppHandle, Manager, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+
+const COLLECTION_STORE: &str = "api-collections.json";
+const MAX_HISTORY_ENTRIES: usize = 500;
+
+static VARIABLE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z0-9_.-]+)\s*\}\}").unwrap());
+
+// Held for every read-modify-write of the store, so concurrent commands don't drop each other's changes
+#[derive(Default)]
+pub struct ApiStoreLock(pub Mutex<()>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedRequest {
+    pub name: String,
+    pub request: ApiRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiCollection {
+    pub name: String,
+    #[serde(default)]
+    pub requests: Vec<SavedRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiEnvironment {
+    pub name: String,
+    #[serde(default)]
+    pub variables: Vec<KeyValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiHistoryEntry {
+    pub collection: String,
+    pub request: String,
+    pub environment: Option<String>,
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    pub duration_ms: u128,
+    pub size_bytes: usize,
+    pub error: Option<String>,
+    pub executed_at: String,
+}
+
+#[command]
+pub fn list_api_collections(app: AppHandle) -> Result<Vec<ApiCollection>, FenrisError> {
+    let store = open_store(&app)?;
+    read_list(&store, "collections")
+}
+
+#[command]
+pub fn save_api_request(
+    app: AppHandle,
+    collection: String,
+    name: String,
+    request: ApiRequest,
+) -> Result<(), FenrisError> {
+    if collection.trim().is_empty() || name.trim().is_empty() {
+        return Err(FenrisError::validation("collection.names_empty", &[]));
+    }
+
+    update_list(
+        &app,
+        "collections",
+        |collections: &mut Vec<ApiCollection>| {
+            let index = match collections.iter().position(|c| c.name == collection) {
+                Some(index) => index,
+                None => {
+                    collections.push(ApiCollection {
+                        name: collection,
+                        requests: Vec::new(),
+                    });
+                    collections.len() - 1
+                }
+            };
+
+            let requests = &mut collections[index].requests;
+            match requests.iter_mut().find(|r| r.name == name) {
+                Some(existing) => existing.request = request,
+                None => requests.push(SavedRequest { name, request }),
+            }
+            Ok(())
+        },
+    )
+}
+
+#[command]
+pub fn delete_api_request(
+    app: AppHandle,
+    collection: String,
+    name: String,
+) -> Result<(), FenrisError> {
+    update_list(
+        &app,
+        "collections",
+        |collections: &mut Vec<ApiCollection>| {
+            let target = collections
+                .iter_mut()
+                .find(|c| c.name == collection)
+                .ok_or_else(|| {
+                    FenrisError::not_found("collection.not_found", &[("name", &collection)])
+                })?;
+            target.requests.retain(|r| r.name != name);
+            Ok(())
+        },
+    )
+}
+
+#[command]
+pub fn delete_api_collection(app: AppHandle, collection: String) -> Result<(), FenrisError> {
+    update_list(
+        &app,
+        "collections",
+        |collections: &mut Vec<ApiCollection>| {
+            collections.retain(|c| c.name != collection);
+            Ok(())
+        },
+    )
+}
+
+#[command]
+pub fn export_api_collection(app: AppHandle, collection: String) -> Result<String, FenrisError> {
+    let store = open_store(&app)?;
+    let collections: Vec<ApiCollection> = read_list(&store, "collections")?;
+
+    let target = collections
+        .iter()
+        .find(|c| c.name == collection)
+        .ok_or_else(|| FenrisError::not_found("collection.not_found", &[("name", &collection)]))?;
+
+    to_string_pretty(target)
+        .map_err(|e| FenrisError::validation("json.format_failed", &[("error", &e)]))
+}
+
+#[command]
+pub fn import_api_collection(
+    app: AppHandle,
+    json_string: String,
+    overwrite: Option<bool>,
+) -> Result<String, FenrisError> {
+    let imported: ApiCollection = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("collection.invalid_file", &[("error", &e)]))?;
+    if imported.name.trim().is_empty() {
+        return Err(FenrisError::validation("collection.name_empty", &[]));
+    }
+
+    let name = imported.name.clone();
+    update_list(
+        &app,
+        "collections",
+        |collections: &mut Vec<ApiCollection>| {
+            match collections.iter().position(|c| c.name == imported.name) {
+                Some(index) if overwrite.unwrap_or(false) => collections[index] = imported,
+                Some(_) => {
+                    return Err(FenrisError::conflict(
+                        "collection.exists",
+                        &[("name", &imported.name)],
+                    ))
+                }
+                None => collections.push(imported),
+            }
+            Ok(())
+        },
+    )?;
+    Ok(name)
+}
+
+#[command]
+pub fn list_api_environments(app: AppHandle) -> Result<Vec<ApiEnvironment>, FenrisError> {
+    let store = open_store(&app)?;
+    read_list(&store, "environments")
+}
+
+#[command]
+pub fn save_api_environment(
+    app: AppHandle,
+    name: String,
+    variables: Vec<KeyValue>,
+) -> Result<(), FenrisError> {
+    if name.trim().is_empty() {
+        return Err(FenrisError::validation("environment.name_empty", &[]));
+    }
+
+    update_list(
+        &app,
+        "environments",
+        |environments: &mut Vec<ApiEnvironment>| {
+            match environments.iter_mut().find(|e| e.name == name) {
+                Some(existing) => existing.variables = variables,
+                None => environments.push(ApiEnvironment { name, variables }),
+            }
+            Ok(())
+        },
+    )
+}
+
+#[command]
+pub fn delete_api_environment(app: AppHandle, name: String) -> Result<(), FenrisError> {
+    update_list(
+        &app,
+        "environments",
+        |environments: &mut Vec<ApiEnvironment>| {
+            environments.retain(|e| e.name != name);
+            Ok(())
+        },
+    )
+}
+
+#[command]
+pub async fn run_saved_api_request(
+    app: AppHandle,
+    collection: String,
+    name: String,
+    environment: Option<String>,
+    options: Option<FormatOptions>,
+) -> Result<ApiResponse, FenrisError> {
+    let store = open_store(&app)?;
+    let collections: Vec<ApiCollection> = read_list(&store, "collections")?;
+
+    let saved = collections
+        .iter()
+        .find(|c| c.name == collection)
+        .ok_or_else(|| FenrisError::not_found("collection.not_found", &[("name", &collection)]))?
+        .requests
+        .iter()
+        .find(|r| r.name == name)
+        .ok_or_else(|| {
+            FenrisError::not_found(
+                "collection.request_not_found",
+                &[("request", &name), ("collection", &collection)],
+            )
+        })?;
+
+    let variables = match &environment {
+        Some(env_name) => {
+            let environments: Vec<ApiEnvironment> = read_list(&store, "environments")?;
+            environments
+                .into_iter()
+                .find(|e| &e.name == env_name)
+                .ok_or_else(|| {
+                    FenrisError::not_found("environment.not_found", &[("name", &env_name)])
+                })?
+                .variables
+        }
+        None => Vec::new(),
+    };
+
+    let request = substitute_variables(&saved.request, &variables)?;
+    let result = execute_api_request(&request, &options.unwrap_or_default()).await;
+
+    // History is written to disk, so it keeps the `{{name}}` template instead of secret values
+    let now: DateTime<Utc> = Utc::now();
+    let entry = ApiHistoryEntry {
+        collection,
+        request: name,
+        environment,
+        method: request.method.to_uppercase(),
+        url: saved.request.url.clone(),
+        status: result.as_ref().ok().map(|r| r.status),
+        duration_ms: result.as_ref().map(|r| r.timing.total_ms).unwrap_or(0),
+        size_bytes: result.as_ref().map(|r| r.size_bytes).unwrap_or(0),
+        error: result
+            .as_ref()
+            .err()
+            .map(|e| redact_variables(&e.message, &variables)),
+        executed_at: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+
+    update_list(&app, "history", |history: &mut Vec<ApiHistoryEntry>| {
+        history.insert(0, entry);
+        history.truncate(MAX_HISTORY_ENTRIES);
+        Ok(())
+    })?;
+
+    result
+}
+
+#[command]
+pub fn list_api_history(
+    app: AppHandle,
+    collection: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ApiHistoryEntry>, FenrisError> {
+    let store = open_store(&app)?;
+    let history: Vec<ApiHistoryEntry> = read_list(&store, "history")?;
+
+    Ok(history
+        .into_iter()
+        .filter(|entry| collection.as_ref().is_none_or(|c| &entry.collection == c))
+        .take(limit.unwrap_or(MAX_HISTORY_ENTRIES))
+        .collect())
+}
+
+#[command]
+pub fn clear_api_history(app: AppHandle) -> Result<(), FenrisError> {
+    update_list(&app, "history", |history: &mut Vec<ApiHistoryEntry>| {
+        history.clear();
+        Ok(())
+    })
+}
+
+// Replaces `{{name}}` in every string of the request, failing on variables the environment lacks
+pub fn substitute_variables(
+    request: &ApiRequest,
+    variables: &[KeyValue],
+) -> Result<ApiRequest, FenrisError> {
+    let pattern = &*VARIABLE_PATTERN;
+    let values: HashMap<&str, &str> = variables
+        .iter()
+        .map(|kv| (kv.key.as_str(), kv.value.as_str()))
+        .collect();
+
+    let mut missing = BTreeSet::new();
+    let mut request_value = to_value(request)
+        .map_err(|e| FenrisError::internal("json.serialize_failed", &[("error", &e)]))?;
+    substitute_in_value(&mut request_value, pattern, &values, &mut missing);
+
+    if !missing.is_empty() {
+        let names: Vec<String> = missing.into_iter().collect();
+        return Err(FenrisError::validation(
+            "environment.undefined_variables",
+            &[("names", &names.join(", "))],
+        ));
+    }
+
+    from_value(request_value)
+        .map_err(|e| FenrisError::validation("collection.invalid_request", &[("error", &e)]))
+}
+
+// Puts `{{name}}` back wherever a variable's value shows up in `text`, longest values first
+fn redact_variables(text: &str, variables: &[KeyValue]) -> String {
+    let mut variables: Vec<&KeyValue> =
+        variables.iter().filter(|kv| !kv.value.is_empty()).collect();
+    variables.sort_by_key(|kv| std::cmp::Reverse(kv.value.len()));
+
+    let mut redacted = text.to_string();
+    for kv in variables {
+        redacted = redacted.replace(&kv.value, &format!("{{{{{}}}}}", kv.key));
+    }
+    redacted
+}
+
+fn substitute_in_value(
+    value: &mut Value,
+    pattern: &Regex,
+    values: &HashMap<&str, &str>,
+    missing: &mut BTreeSet<String>,
+) {
+    match value {
+        Value::String(s) if s.contains("{{") => {
+            let replaced = pattern.replace_all(s, |caps: &regex::Captures| {
+                let name = &caps[1];
+                match values.get(name) {
+                    Some(v) => v.to_string(),
+                    None => {
+                        missing.insert(name.to_string());
+                        caps[0].to_string()
+                    }
+                }
+            });
+            *s = replaced.into_owned();
+        }
+        Value::Array(items) => {
+            for item in items {
+                substitute_in_value(item, pattern, values, missing);
+            }
+        }
+        Value::Object(map) => {
+            for (_, item) in map.iter_mut() {
+                substitute_in_value(item, pattern, values, missing);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn open_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, FenrisError> {
+    app.store(COLLECTION_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))
+}
+
+// A missing key is an empty list, an unreadable one is an error so the next save can't overwrite it
+fn read_list<T: DeserializeOwned>(store: &Store<Wry>, key: &str) -> Result<Vec<T>, FenrisError> {
+    match store.get(key) {
+        Some(value) => from_value(value)
+            .map_err(|e| FenrisError::io("collection.corrupt", &[("key", &key), ("error", &e)])),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn update_list<T, R>(
+    app: &AppHandle,
+    key: &str,
+    change: impl FnOnce(&mut Vec<T>) -> Result<R, FenrisError>,
+) -> Result<R, FenrisError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let store_lock = app.state::<ApiStoreLock>();
+    let _guard = lock(&store_lock.0)?;
+    let store = open_store(app)?;
+    let mut items = read_list(&store, key)?;
+    let result = change(&mut items)?;
+    write_list(&store, key, &items)?;
+    Ok(result)
+}
+
+fn write_list<T: Serialize>(store: &Store<Wry>, key: &str, items: &[T]) -> Result<(), FenrisError> {
+    let value = to_value(items)
+        .map_err(|e| FenrisError::internal("json.serialize_failed", &[("error", &e)]))?;
+    store.set(key, value);
+    store
+        .save()
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))
+}
@@ -0,0 +1,238 @@
+use crate::error::FenrisError;
+use crate::json::json_formatter::{format_value, FormatOptions};
+use reqwest::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{redirect, Client, Method};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, Value};
+use std::time::{Duration, Instant};
+use tauri::command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum RequestBody {
+    Json {
+        content: String,
+    },
+    Form {
+        fields: Vec<KeyValue>,
+    },
+    Raw {
+        content: String,
+        content_type: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum RequestAuth {
+    Bearer { token: String },
+    Basic { username: String, password: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<KeyValue>,
+    pub query: Vec<KeyValue>,
+    pub body: Option<RequestBody>,
+    pub auth: Option<RequestAuth>,
+    pub timeout_ms: Option<u64>,
+    pub follow_redirects: bool,
+    pub max_redirects: usize,
+    pub verify_tls: bool,
+}
+
+impl Default for ApiRequest {
+    fn default() -> Self {
+        Self {
+            method: String::from("GET"),
+            url: String::new(),
+            headers: Vec::new(),
+            query: Vec::new(),
+            body: None,
+            auth: None,
+            timeout_ms: Some(30_000),
+            follow_redirects: true,
+            max_redirects: 10,
+            verify_tls: true,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ResponseTiming {
+    pub headers_ms: u128,
+    pub total_ms: u128,
+}
+
+#[derive(Serialize)]
+pub struct ApiResponse {
+    pub url: String,
+    pub status: u16,
+    pub status_text: String,
+    pub headers: Vec<KeyValue>,
+    pub timing: ResponseTiming,
+    pub size_bytes: usize,
+    pub is_json: bool,
+    pub body: String,
+}
+
+#[command]
+pub async fn send_api_request(
+    request: ApiRequest,
+    options: Option<FormatOptions>,
+) -> Result<ApiResponse, FenrisError> {
+    execute_api_request(&request, &options.unwrap_or_default()).await
+}
+
+pub async fn execute_api_request(
+    request: &ApiRequest,
+    options: &FormatOptions,
+) -> Result<ApiResponse, FenrisError> {
+    if request.url.trim().is_empty() {
+        return Err(FenrisError::validation("api.url_empty", &[]));
+    }
+
+    let method =
+        Method::from_bytes(request.method.trim().to_uppercase().as_bytes()).map_err(|_| {
+            FenrisError::validation("api.invalid_method", &[("method", &request.method)])
+        })?;
+
+    let redirect_policy = if request.follow_redirects {
+        redirect::Policy::limited(request.max_redirects)
+    } else {
+        redirect::Policy::none()
+    };
+
+    let mut client_builder = Client::builder()
+        .redirect(redirect_policy)
+        .danger_accept_invalid_certs(!request.verify_tls);
+    if let Some(timeout_ms) = request.timeout_ms {
+        client_builder = client_builder.timeout(Duration::from_millis(timeout_ms));
+    }
+    let client = client_builder
+        .build()
+        .map_err(|e| FenrisError::network("http.client_failed", &[("error", &e)]))?;
+
+    let mut builder = client.request(method, request.url.trim());
+
+    if !request.query.is_empty() {
+        let query: Vec<(&str, &str)> = request
+            .query
+            .iter()
+            .map(|kv| (kv.key.as_str(), kv.value.as_str()))
+            .collect();
+        builder = builder.query(&query);
+    }
+
+    for header in &request.headers {
+        let name = HeaderName::from_bytes(header.key.trim().as_bytes()).map_err(|_| {
+            FenrisError::validation("api.invalid_header_name", &[("header", &header.key)])
+        })?;
+        let value = HeaderValue::from_str(&header.value).map_err(|_| {
+            FenrisError::validation("api.invalid_header_value", &[("header", &header.key)])
+        })?;
+        builder = builder.header(name, value);
+    }
+
+    builder = match &request.auth {
+        Some(RequestAuth::Bearer { token }) => builder.bearer_auth(token),
+        Some(RequestAuth::Basic { username, password }) => {
+            builder.basic_auth(username, Some(password))
+        }
+        None => builder,
+    };
+
+    builder = match &request.body {
+        Some(RequestBody::Json { content }) => {
+            let parsed: Value = from_str(content)
+                .map_err(|e| FenrisError::validation("api.invalid_body", &[("error", &e)]))?;
+            builder.json(&parsed)
+        }
+        Some(RequestBody::Form { fields }) => {
+            let form: Vec<(&str, &str)> = fields
+                .iter()
+                .map(|kv| (kv.key.as_str(), kv.value.as_str()))
+                .collect();
+            builder.form(&form)
+        }
+        Some(RequestBody::Raw {
+            content,
+            content_type,
+        }) => {
+            let builder = builder.body(content.clone());
+            match content_type {
+                Some(content_type) => builder.header(CONTENT_TYPE, content_type),
+                None => builder,
+            }
+        }
+        None => builder,
+    };
+
+    let start_time = Instant::now();
+    let response = builder.send().await.map_err(|e| {
+        if e.is_timeout() {
+            FenrisError::network("api.timeout", &[])
+        } else if e.is_connect() {
+            FenrisError::network("api.connect_failed", &[("error", &e)])
+        } else {
+            FenrisError::network("api.request_failed", &[("error", &e)])
+        }
+    })?;
+    let headers_ms = start_time.elapsed().as_millis();
+
+    let status = response.status();
+    let url = response.url().to_string();
+    let headers: Vec<KeyValue> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| KeyValue {
+            key: name.to_string(),
+            value: value.to_str().unwrap_or_default().to_string(),
+        })
+        .collect();
+    let declared_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("json"));
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| FenrisError::network("api.response_failed", &[("error", &e)]))?;
+    let total_ms = start_time.elapsed().as_millis();
+    let text = String::from_utf8_lossy(&bytes).to_string();
+
+    // Some APIs send JSON as text/plain, so the body is sniffed as well
+    let parsed = if declared_json || text.trim_start().starts_with(['{', '[']) {
+        from_str::<Value>(&text).ok()
+    } else {
+        None
+    };
+
+    Ok(ApiResponse {
+        url,
+        status: status.as_u16(),
+        status_text: status.canonical_reason().unwrap_or_default().to_string(),
+        headers,
+        timing: ResponseTiming {
+            headers_ms,
+            total_ms,
+        },
+        size_bytes: bytes.len(),
+        is_json: parsed.is_some(),
+        body: match parsed {
+            Some(value) => format_value(&value, options),
+            None => text,
+        },
+    })
+}
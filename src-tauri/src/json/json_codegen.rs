@@ -0,0 +1,656 @@
+use crate::error::FenrisError;
+use crate::json::json_formatter::{format_value, FormatOptions};
+use serde::Deserialize;
+use serde_json::{from_str, json, Map, Value};
+use std::collections::{BTreeSet, HashSet};
+use tauri::command;
+
+// Distinct strings remembered per field before it stops being an enum candidate
+const MAX_TRACKED_STRINGS: usize = 64;
+const SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+// Types and derives the generated Rust code refers to, a struct with one of these names would
+// shadow it or clash with the serde import
+const RUST_RESERVED_TYPES: &[&str] = &[
+    "Self",
+    "String",
+    "Option",
+    "Vec",
+    "Box",
+    "Clone",
+    "Copy",
+    "Debug",
+    "PartialEq",
+    "Eq",
+    "Serialize",
+    "Deserialize",
+];
+
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CodegenLanguage {
+    #[default]
+    Rust,
+    TypeScript,
+    JsonSchema,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CodegenOptions {
+    pub language: CodegenLanguage,
+    pub root_name: String,
+    pub detect_enums: bool,
+    pub max_enum_values: usize,
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self {
+            language: CodegenLanguage::Rust,
+            root_name: String::from("Root"),
+            detect_enums: true,
+            max_enum_values: 8,
+        }
+    }
+}
+
+#[command]
+pub fn generate_types_from_json(
+    samples: Vec<String>,
+    options: Option<CodegenOptions>,
+) -> Result<String, FenrisError> {
+    if samples.is_empty() {
+        return Err(FenrisError::validation("codegen.no_samples", &[]));
+    }
+
+    let mut shape = Shape::default();
+    for (index, sample) in samples.iter().enumerate() {
+        let value: Value = from_str(sample).map_err(|e| {
+            FenrisError::validation(
+                "codegen.invalid_sample",
+                &[("sample", &(index + 1)), ("error", &e)],
+            )
+        })?;
+        shape.observe(&value);
+    }
+
+    let options = options.unwrap_or_default();
+    let root_name = match pascal_case(&options.root_name) {
+        name if name.is_empty() => String::from("Root"),
+        name => name,
+    };
+
+    Ok(match options.language {
+        CodegenLanguage::Rust => RustGenerator::new(&options).generate(&shape, &root_name),
+        CodegenLanguage::TypeScript => {
+            TypeScriptGenerator::new(&options).generate(&shape, &root_name)
+        }
+        CodegenLanguage::JsonSchema => {
+            let mut schema = json_schema_for(&shape, &options);
+            if let Value::Object(map) = &mut schema {
+                map.shift_insert(0, String::from("title"), Value::String(root_name));
+                map.shift_insert(
+                    0,
+                    String::from("$schema"),
+                    Value::String(SCHEMA_DRAFT.to_string()),
+                );
+            }
+            format_value(&schema, &FormatOptions::default())
+        }
+    })
+}
+
+#[derive(Default)]
+struct StringValues {
+    count: usize,
+    values: BTreeSet<String>,
+    overflow: bool,
+}
+
+#[derive(Default)]
+struct ObjectShape {
+    seen: usize,
+    fields: Vec<(String, Shape)>,
+}
+
+// Everything observed at one position across all samples
+#[derive(Default)]
+struct Shape {
+    seen: usize,
+    nulls: usize,
+    bools: bool,
+    integers: bool,
+    unsigned_only: bool,
+    floats: bool,
+    strings: Option<StringValues>,
+    array: Option<Box<Shape>>,
+    object: Option<ObjectShape>,
+}
+
+impl Shape {
+    fn observe(&mut self, value: &Value) {
+        self.seen += 1;
+        match value {
+            Value::Null => self.nulls += 1,
+            Value::Bool(_) => self.bools = true,
+            Value::Number(n) if n.is_f64() => self.floats = true,
+            Value::Number(n) => {
+                self.integers = true;
+                if n.as_i64().is_none() {
+                    self.unsigned_only = true;
+                }
+            }
+            Value::String(s) => {
+                let strings = self.strings.get_or_insert_with(StringValues::default);
+                strings.count += 1;
+                if !strings.overflow {
+                    strings.values.insert(s.clone());
+                    if strings.values.len() > MAX_TRACKED_STRINGS {
+                        strings.overflow = true;
+                        strings.values.clear();
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let element = self.array.get_or_insert_with(Box::default);
+                for item in items {
+                    element.observe(item);
+                }
+            }
+            Value::Object(map) => {
+                let object = self.object.get_or_insert_with(ObjectShape::default);
+                object.seen += 1;
+                for (key, item) in map {
+                    match object.fields.iter_mut().find(|(name, _)| name == key) {
+                        Some((_, field)) => field.observe(item),
+                        None => {
+                            let mut field = Shape::default();
+                            field.observe(item);
+                            object.fields.push((key.clone(), field));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        self.integers || self.floats
+    }
+
+    fn kind_count(&self) -> usize {
+        [
+            self.bools,
+            self.is_numeric(),
+            self.strings.is_some(),
+            self.array.is_some(),
+            self.object.is_some(),
+        ]
+        .iter()
+        .filter(|present| **present)
+        .count()
+    }
+
+    fn nullable(&self) -> bool {
+        self.nulls > 0
+    }
+
+    // Small sets of repeated strings, e.g. a status field, become enums
+    fn enum_values(&self, options: &CodegenOptions) -> Option<Vec<String>> {
+        let strings = self.strings.as_ref()?;
+        if !options.detect_enums || self.kind_count() != 1 || strings.overflow {
+            return None;
+        }
+        let distinct = strings.values.len();
+        let repeated = strings.count >= 3 && distinct * 2 <= strings.count;
+        (distinct > 0 && distinct <= options.max_enum_values && repeated)
+            .then(|| strings.values.iter().cloned().collect())
+    }
+}
+
+fn field_is_optional(field: &Shape, parent: &ObjectShape) -> bool {
+    field.seen < parent.seen
+}
+
+struct RustGenerator<'a> {
+    options: &'a CodegenOptions,
+    definitions: Vec<String>,
+    used_names: HashSet<String>,
+}
+
+impl<'a> RustGenerator<'a> {
+    fn new(options: &'a CodegenOptions) -> Self {
+        Self {
+            options,
+            definitions: Vec::new(),
+            used_names: RUST_RESERVED_TYPES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn generate(mut self, shape: &Shape, root_name: &str) -> String {
+        if shape.object.is_some() && shape.kind_count() == 1 {
+            self.type_ref(shape, root_name);
+        } else {
+            let name = unique_name(&mut self.used_names, root_name);
+            let slot = self.reserve();
+            let item_name = format!("{}Item", root_name);
+            let target = self.type_ref(shape, &item_name);
+            self.definitions[slot] = format!("pub type {} = {};\n", name, target);
+        }
+
+        let mut output = String::from("use serde::{Deserialize, Serialize};\n\n");
+        output.push_str(&self.definitions.join("\n"));
+        output
+    }
+
+    fn reserve(&mut self) -> usize {
+        self.definitions.push(String::new());
+        self.definitions.len() - 1
+    }
+
+    fn type_ref(&mut self, shape: &Shape, name_hint: &str) -> String {
+        let base = self.base_type(shape, name_hint);
+        if shape.nullable() && shape.kind_count() > 0 && base != "serde_json::Value" {
+            format!("Option<{}>", base)
+        } else {
+            base
+        }
+    }
+
+    fn base_type(&mut self, shape: &Shape, name_hint: &str) -> String {
+        if shape.kind_count() != 1 {
+            return String::from("serde_json::Value");
+        }
+        if let Some(values) = shape.enum_values(self.options) {
+            if let Some(name) = self.enum_definition(&values, name_hint) {
+                return name;
+            }
+        }
+
+        if let Some(object) = &shape.object {
+            return self.struct_definition(object, name_hint);
+        }
+        if let Some(element) = &shape.array {
+            let element_type = self.type_ref(element, &singular(name_hint));
+            return format!("Vec<{}>", element_type);
+        }
+        if shape.strings.is_some() {
+            String::from("String")
+        } else if shape.floats {
+            String::from("f64")
+        } else if shape.unsigned_only {
+            String::from("u64")
+        } else if shape.integers {
+            String::from("i64")
+        } else {
+            String::from("bool")
+        }
+    }
+
+    fn struct_definition(&mut self, object: &ObjectShape, name_hint: &str) -> String {
+        let name = unique_name(&mut self.used_names, name_hint);
+        let slot = self.reserve();
+
+        let mut body = String::from("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+        body.push_str(&format!("pub struct {} {{\n", name));
+
+        let mut field_names = HashSet::new();
+        for (key, field) in &object.fields {
+            let mut ident = rust_field_name(key);
+            let mut suffix = 2;
+            while !field_names.insert(ident.clone()) {
+                ident = format!("{}_{}", rust_field_name(key), suffix);
+                suffix += 1;
+            }
+
+            let optional = field_is_optional(field, object);
+            let mut field_type = self.type_ref(field, &pascal_case(key));
+            if optional && !field_type.starts_with("Option<") {
+                field_type = format!("Option<{}>", field_type);
+            }
+
+            if ident.trim_start_matches("r#") != key {
+                body.push_str(&format!("    #[serde(rename = {:?})]\n", key));
+            }
+            if optional {
+                body.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+            }
+            body.push_str(&format!("    pub {}: {},\n", ident, field_type));
+        }
+        body.push_str("}\n");
+
+        self.definitions[slot] = body;
+        name
+    }
+
+    fn enum_definition(&mut self, values: &[String], name_hint: &str) -> Option<String> {
+        let variants: Vec<String> = values.iter().map(|v| rust_variant_name(v)).collect();
+        let distinct: HashSet<&String> = variants.iter().collect();
+        if distinct.len() != variants.len() {
+            return None;
+        }
+
+        let name = unique_name(&mut self.used_names, name_hint);
+        let mut body =
+            String::from("#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\n");
+        body.push_str(&format!("pub enum {} {{\n", name));
+        for (value, variant) in values.iter().zip(&variants) {
+            if value != variant {
+                body.push_str(&format!("    #[serde(rename = {:?})]\n", value));
+            }
+            body.push_str(&format!("    {},\n", variant));
+        }
+        body.push_str("}\n");
+
+        self.definitions.push(body);
+        Some(name)
+    }
+}
+
+struct TypeScriptGenerator<'a> {
+    options: &'a CodegenOptions,
+    definitions: Vec<String>,
+    used_names: HashSet<String>,
+}
+
+impl<'a> TypeScriptGenerator<'a> {
+    fn new(options: &'a CodegenOptions) -> Self {
+        Self {
+            options,
+            definitions: Vec::new(),
+            used_names: HashSet::new(),
+        }
+    }
+
+    fn generate(mut self, shape: &Shape, root_name: &str) -> String {
+        if shape.object.is_some() && shape.kind_count() == 1 && !shape.nullable() {
+            self.type_ref(shape, root_name);
+        } else {
+            let name = unique_name(&mut self.used_names, root_name);
+            let slot = self.reserve();
+            let item_name = format!("{}Item", root_name);
+            let target = self.type_ref(shape, &item_name);
+            self.definitions[slot] = format!("export type {} = {};\n", name, target);
+        }
+        self.definitions.join("\n")
+    }
+
+    fn reserve(&mut self) -> usize {
+        self.definitions.push(String::new());
+        self.definitions.len() - 1
+    }
+
+    fn type_ref(&mut self, shape: &Shape, name_hint: &str) -> String {
+        if shape.seen == 0 {
+            return String::from("unknown");
+        }
+
+        let mut members: Vec<String> = Vec::new();
+        if let Some(values) = shape.enum_values(self.options) {
+            let name = unique_name(&mut self.used_names, name_hint);
+            let literals: Vec<String> = values.iter().map(|v| format!("{:?}", v)).collect();
+            self.definitions.push(format!(
+                "export type {} = {};\n",
+                name,
+                literals.join(" | ")
+            ));
+            members.push(name);
+        } else if shape.strings.is_some() {
+            members.push(String::from("string"));
+        }
+        if shape.is_numeric() {
+            members.push(String::from("number"));
+        }
+        if shape.bools {
+            members.push(String::from("boolean"));
+        }
+        if let Some(element) = &shape.array {
+            let element_type = self.type_ref(element, &singular(name_hint));
+            members.push(if element_type.contains(' ') {
+                format!("({})[]", element_type)
+            } else {
+                format!("{}[]", element_type)
+            });
+        }
+        if let Some(object) = &shape.object {
+            members.push(self.interface_definition(object, name_hint));
+        }
+        if shape.nullable() {
+            members.push(String::from("null"));
+        }
+
+        members.join(" | ")
+    }
+
+    fn interface_definition(&mut self, object: &ObjectShape, name_hint: &str) -> String {
+        let name = unique_name(&mut self.used_names, name_hint);
+        let slot = self.reserve();
+
+        let mut body = format!("export interface {} {{\n", name);
+        for (key, field) in &object.fields {
+            let property = if is_ts_identifier(key) {
+                key.clone()
+            } else {
+                format!("{:?}", key)
+            };
+            let optional = if field_is_optional(field, object) {
+                "?"
+            } else {
+                ""
+            };
+            let field_type = self.type_ref(field, &pascal_case(key));
+            body.push_str(&format!("  {}{}: {};\n", property, optional, field_type));
+        }
+        body.push_str("}\n");
+
+        self.definitions[slot] = body;
+        name
+    }
+}
+
+fn json_schema_for(shape: &Shape, options: &CodegenOptions) -> Value {
+    if shape.seen == 0 {
+        return json!({});
+    }
+
+    let mut variants: Vec<Value> = Vec::new();
+    if let Some(values) = shape.enum_values(options) {
+        variants.push(json!({ "type": "string", "enum": values }));
+    } else if shape.strings.is_some() {
+        variants.push(json!({ "type": "string" }));
+    }
+    if shape.is_numeric() {
+        let number_type = if shape.floats { "number" } else { "integer" };
+        variants.push(json!({ "type": number_type }));
+    }
+    if shape.bools {
+        variants.push(json!({ "type": "boolean" }));
+    }
+    if let Some(element) = &shape.array {
+        let mut schema = Map::new();
+        schema.insert(String::from("type"), json!("array"));
+        if element.seen > 0 {
+            schema.insert(String::from("items"), json_schema_for(element, options));
+        }
+        variants.push(Value::Object(schema));
+    }
+    if let Some(object) = &shape.object {
+        let properties: Map<String, Value> = object
+            .fields
+            .iter()
+            .map(|(key, field)| (key.clone(), json_schema_for(field, options)))
+            .collect();
+        let required: Vec<&String> = object
+            .fields
+            .iter()
+            .filter(|(_, field)| !field_is_optional(field, object))
+            .map(|(key, _)| key)
+            .collect();
+        variants.push(json!({
+            "type": "object",
+            "properties": properties,
+            "required": required
+        }));
+    }
+
+    match (variants.len(), shape.nullable()) {
+        (0, _) => json!({ "type": "null" }),
+        (1, false) => variants.remove(0),
+        // A single nullable type keeps its keywords and widens "type"
+        (1, true) => {
+            let mut schema = variants.remove(0);
+            if let Some(kind) = schema.get("type").cloned() {
+                schema["type"] = json!([kind, "null"]);
+            }
+            if let Some(values) = schema.get_mut("enum").and_then(Value::as_array_mut) {
+                values.push(Value::Null);
+            }
+            schema
+        }
+        (_, nullable) => {
+            if nullable {
+                variants.push(json!({ "type": "null" }));
+            }
+            json!({ "anyOf": variants })
+        }
+    }
+}
+
+fn unique_name(used: &mut HashSet<String>, base: &str) -> String {
+    let base = if base.is_empty() { "Type" } else { base };
+    let mut name = base.to_string();
+    let mut suffix = 2;
+    while !used.insert(name.clone()) {
+        name = format!("{}{}", base, suffix);
+        suffix += 1;
+    }
+    name
+}
+
+// Splits on separators and on lower-to-upper case boundaries, e.g. "userID_list" -> user, ID, list
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = text.chars().collect();
+
+    for (index, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        let previous = index.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(index + 1);
+        let boundary = c.is_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_numeric()
+                    || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn pascal_case(text: &str) -> String {
+    words(text)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if name.ends_with('s') && !name.ends_with("ss") && name.len() > 1 {
+        name[..name.len() - 1].to_string()
+    } else {
+        format!("{}Item", name)
+    }
+}
+
+fn rust_field_name(key: &str) -> String {
+    let snake = words(key)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    match snake.as_str() {
+        "" => String::from("field"),
+        "self" | "super" | "crate" => format!("{}_", snake),
+        _ if snake.starts_with(|c: char| c.is_numeric()) => format!("field_{}", snake),
+        _ if RUST_KEYWORDS.contains(&snake.as_str()) => format!("r#{}", snake),
+        _ => snake,
+    }
+}
+
+fn rust_variant_name(value: &str) -> String {
+    let name = pascal_case(value);
+    match name.chars().next() {
+        None => String::from("Empty"),
+        Some(c) if c.is_numeric() => format!("V{}", name),
+        Some(_) if name == "Self" => String::from("Self_"),
+        Some(_) => name,
+    }
+}
+
+fn is_ts_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust_types(sample: &str) -> String {
+        generate_types_from_json(vec![sample.to_string()], None).unwrap()
+    }
+
+    #[test]
+    fn rust_structs_do_not_shadow_std_types() {
+        let code = rust_types(
+            r#"{"string": {"value": "a"}, "option": {"on": true}, "self": {"id": 1}, "serialize": {"x": 1}}"#,
+        );
+        for name in ["String2", "Option2", "Self2", "Serialize2"] {
+            assert!(
+                code.contains(&format!("pub struct {} {{", name)),
+                "{}",
+                code
+            );
+        }
+        assert!(code.contains("pub string: String2,"), "{}", code);
+        assert!(code.contains("pub value: String,"), "{}", code);
+        assert!(!code.contains("pub struct String {"), "{}", code);
+    }
+}
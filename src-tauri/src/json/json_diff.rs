@@ -0,0 +1,283 @@
+use crate::error::FenrisError;
+use crate::json::json_formatter::{format_value, FormatOptions};
+use crate::json::json_library::load_json_document;
+use crate::sqlite::app_sqlite_pool;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, json, Map, Value};
+use std::collections::HashMap;
+use tauri::{command, AppHandle};
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct DiffOptions {
+    /// Field used to match array elements instead of their index, e.g. "id"
+    pub array_key: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Serialize)]
+pub struct JsonChange {
+    pub kind: ChangeKind,
+    pub path: String,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+}
+
+#[derive(Serialize)]
+pub struct JsonDiffResult {
+    pub identical: bool,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub changes: Vec<JsonChange>,
+    pub patch: Vec<Value>,
+}
+
+#[command]
+pub fn diff_json(
+    left: String,
+    right: String,
+    options: Option<DiffOptions>,
+) -> Result<JsonDiffResult, FenrisError> {
+    let left: Value = from_str(&left)
+        .map_err(|e| FenrisError::validation("json.invalid_left", &[("error", &e)]))?;
+    let right: Value = from_str(&right)
+        .map_err(|e| FenrisError::validation("json.invalid_right", &[("error", &e)]))?;
+
+    Ok(diff_values(&left, &right, &options.unwrap_or_default()))
+}
+
+#[command]
+pub async fn diff_json_with_saved(
+    app: AppHandle,
+    key: String,
+    json_string: String,
+    options: Option<DiffOptions>,
+) -> Result<JsonDiffResult, FenrisError> {
+    let pool = app_sqlite_pool(&app).await?;
+    let saved = load_json_document(&pool, &key, None).await?;
+    let current: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+
+    Ok(diff_values(&saved, &current, &options.unwrap_or_default()))
+}
+
+#[command]
+pub fn apply_json_patch(
+    json_string: String,
+    patch: String,
+    options: Option<FormatOptions>,
+) -> Result<String, FenrisError> {
+    let mut document: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+    let patch: json_patch::Patch = from_str(&patch)
+        .map_err(|e| FenrisError::validation("json.invalid_patch", &[("error", &e)]))?;
+
+    json_patch::patch(&mut document, &patch)
+        .map_err(|e| FenrisError::validation("json.patch_failed", &[("error", &e)]))?;
+
+    Ok(format_value(&document, &options.unwrap_or_default()))
+}
+
+pub fn diff_values(left: &Value, right: &Value, options: &DiffOptions) -> JsonDiffResult {
+    let mut differ = Differ {
+        options,
+        changes: Vec::new(),
+        patch: Vec::new(),
+    };
+    differ.diff(left, right, "");
+
+    let count = |kind: ChangeKind| differ.changes.iter().filter(|c| c.kind == kind).count();
+
+    JsonDiffResult {
+        identical: differ.changes.is_empty(),
+        added: count(ChangeKind::Added),
+        removed: count(ChangeKind::Removed),
+        changed: count(ChangeKind::Changed),
+        changes: differ.changes,
+        patch: differ.patch,
+    }
+}
+
+pub fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+struct Differ<'a> {
+    options: &'a DiffOptions,
+    changes: Vec<JsonChange>,
+    patch: Vec<Value>,
+}
+
+impl Differ<'_> {
+    fn diff(&mut self, left: &Value, right: &Value, path: &str) {
+        match (left, right) {
+            (Value::Object(l), Value::Object(r)) => self.diff_objects(l, r, path),
+            (Value::Array(l), Value::Array(r)) => {
+                if !self.diff_arrays_by_key(l, r, path) {
+                    self.diff_arrays_by_index(l, r, path);
+                }
+            }
+            _ if left == right => {}
+            _ => self.changed(path, left, right),
+        }
+    }
+
+    fn diff_objects(&mut self, left: &Map<String, Value>, right: &Map<String, Value>, path: &str) {
+        for (key, l) in left {
+            let child = format!("{}/{}", path, escape_pointer_segment(key));
+            match right.get(key) {
+                Some(r) => self.diff(l, r, &child),
+                None => self.removed(&child, l),
+            }
+        }
+
+        for (key, r) in right {
+            if !left.contains_key(key) {
+                let child = format!("{}/{}", path, escape_pointer_segment(key));
+                self.added(&child, r);
+            }
+        }
+    }
+
+    fn diff_arrays_by_index(&mut self, left: &[Value], right: &[Value], path: &str) {
+        let common = left.len().min(right.len());
+        for index in 0..common {
+            self.diff(&left[index], &right[index], &format!("{}/{}", path, index));
+        }
+
+        // Remove from the end so earlier indices stay valid while the patch is applied
+        for index in (common..left.len()).rev() {
+            self.removed(&format!("{}/{}", path, index), &left[index]);
+        }
+        for (index, value) in right.iter().enumerate().skip(common) {
+            self.added(&format!("{}/{}", path, index), value);
+        }
+    }
+
+    fn diff_arrays_by_key(&mut self, left: &[Value], right: &[Value], path: &str) -> bool {
+        let Some(key) = self.options.array_key.as_deref() else {
+            return false;
+        };
+        let (Some(left_index), Some(right_index)) =
+            (index_by_key(left, key), index_by_key(right, key))
+        else {
+            return false;
+        };
+
+        // Elements present on both sides must keep their relative order for an
+        // index-based patch, otherwise the whole array is replaced in the patch
+        let kept_left: Vec<String> = element_keys(left, key)
+            .into_iter()
+            .filter(|k| right_index.contains_key(k))
+            .collect();
+        let kept_right: Vec<String> = element_keys(right, key)
+            .into_iter()
+            .filter(|k| left_index.contains_key(k))
+            .collect();
+        let same_order = kept_left == kept_right;
+        let patch_start = self.patch.len();
+
+        for (index, element) in left.iter().enumerate() {
+            let element_key = key_of(element, key).unwrap_or_default();
+            if let Some(&right_position) = right_index.get(&element_key) {
+                self.diff(
+                    element,
+                    &right[right_position],
+                    &format!("{}/{}", path, index),
+                );
+            }
+        }
+
+        for (index, element) in left.iter().enumerate().rev() {
+            let element_key = key_of(element, key).unwrap_or_default();
+            if !right_index.contains_key(&element_key) {
+                self.removed(&format!("{}/{}", path, index), element);
+            }
+        }
+
+        for (index, element) in right.iter().enumerate() {
+            let element_key = key_of(element, key).unwrap_or_default();
+            if !left_index.contains_key(&element_key) {
+                self.added(&format!("{}/{}", path, index), element);
+            }
+        }
+
+        if !same_order {
+            self.patch.truncate(patch_start);
+            self.patch.push(json!({
+                "op": "replace",
+                "path": path,
+                "value": Value::Array(right.to_vec())
+            }));
+        }
+
+        true
+    }
+
+    fn added(&mut self, path: &str, value: &Value) {
+        self.patch
+            .push(json!({ "op": "add", "path": path, "value": value }));
+        self.changes.push(JsonChange {
+            kind: ChangeKind::Added,
+            path: path.to_string(),
+            old_value: None,
+            new_value: Some(value.clone()),
+        });
+    }
+
+    fn removed(&mut self, path: &str, value: &Value) {
+        self.patch.push(json!({ "op": "remove", "path": path }));
+        self.changes.push(JsonChange {
+            kind: ChangeKind::Removed,
+            path: path.to_string(),
+            old_value: Some(value.clone()),
+            new_value: None,
+        });
+    }
+
+    fn changed(&mut self, path: &str, old: &Value, new: &Value) {
+        self.patch
+            .push(json!({ "op": "replace", "path": path, "value": new }));
+        self.changes.push(JsonChange {
+            kind: ChangeKind::Changed,
+            path: path.to_string(),
+            old_value: Some(old.clone()),
+            new_value: Some(new.clone()),
+        });
+    }
+}
+
+fn key_of(element: &Value, key: &str) -> Option<String> {
+    match element.get(key)? {
+        Value::String(s) => Some(format!("s:{}", s)),
+        Value::Number(n) => Some(format!("n:{}", n)),
+        Value::Bool(b) => Some(format!("b:{}", b)),
+        _ => None,
+    }
+}
+
+fn element_keys(elements: &[Value], key: &str) -> Vec<String> {
+    elements
+        .iter()
+        .filter_map(|element| key_of(element, key))
+        .collect()
+}
+
+// Returns None when an element lacks the key or a key is duplicated
+fn index_by_key(elements: &[Value], key: &str) -> Option<HashMap<String, usize>> {
+    let mut index = HashMap::with_capacity(elements.len());
+    for (position, element) in elements.iter().enumerate() {
+        if index.insert(key_of(element, key)?, position).is_some() {
+            return None;
+        }
+    }
+    Some(index)
+}
@@ -0,0 +1,197 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+// The width comes from the frontend, wider indents are clamped so depth cannot blow up the output
+const MAX_INDENT_WIDTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IndentStyle {
+    Spaces,
+    Tabs,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FormatOptions {
+    pub indent_style: IndentStyle,
+    pub indent_width: usize,
+    pub sort_keys: bool,
+    pub compact: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_style: IndentStyle::Spaces,
+            indent_width: 2,
+            sort_keys: false,
+            compact: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    fn indent_unit(&self) -> String {
+        match self.indent_style {
+            IndentStyle::Spaces => " ".repeat(self.indent_width.min(MAX_INDENT_WIDTH)),
+            IndentStyle::Tabs => "\t".repeat(self.indent_width.clamp(1, MAX_INDENT_WIDTH)),
+        }
+    }
+}
+
+// Keys come out in source order because serde_json is built with `preserve_order`
+pub fn format_value(value: &Value, options: &FormatOptions) -> String {
+    let mut output = String::new();
+    let indent_unit = options.indent_unit();
+    write_value(&mut output, value, options, &indent_unit, 0);
+    output
+}
+
+fn write_value(
+    output: &mut String,
+    value: &Value,
+    options: &FormatOptions,
+    indent_unit: &str,
+    depth: usize,
+) {
+    match value {
+        Value::Object(map) if map.is_empty() => output.push_str("{}"),
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            if options.sort_keys {
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+            }
+
+            output.push('{');
+            for (index, (key, val)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_newline(output, options, indent_unit, depth + 1);
+                write_escaped_string(output, key);
+                output.push_str(if options.compact { ":" } else { ": " });
+                write_value(output, val, options, indent_unit, depth + 1);
+            }
+            write_newline(output, options, indent_unit, depth);
+            output.push('}');
+        }
+        Value::Array(arr) if arr.is_empty() => output.push_str("[]"),
+        Value::Array(arr) => {
+            output.push('[');
+            for (index, val) in arr.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_newline(output, options, indent_unit, depth + 1);
+                write_value(output, val, options, indent_unit, depth + 1);
+            }
+            write_newline(output, options, indent_unit, depth);
+            output.push(']');
+        }
+        Value::String(s) => write_escaped_string(output, s),
+        Value::Number(n) => output.push_str(&n.to_string()),
+        Value::Bool(b) => output.push_str(if *b { "true" } else { "false" }),
+        Value::Null => output.push_str("null"),
+    }
+}
+
+fn write_newline(output: &mut String, options: &FormatOptions, indent_unit: &str, depth: usize) {
+    if options.compact {
+        return;
+    }
+    output.push('\n');
+    for _ in 0..depth {
+        output.push_str(indent_unit);
+    }
+}
+
+pub fn write_escaped_string(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{08}' => output.push_str("\\b"),
+            '\u{0C}' => output.push_str("\\f"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+// Finds the lines (1-based, inclusive) that the nodes at `pointers` occupy in the
+// output of `format_value`, without building the formatted string
+pub fn line_spans(
+    value: &Value,
+    pointers: &HashSet<String>,
+    options: &FormatOptions,
+) -> HashMap<String, (usize, usize)> {
+    let mut spans = HashMap::new();
+    if pointers.is_empty() {
+        return spans;
+    }
+
+    let mut path = String::new();
+    let mut line = 1;
+    record_spans(value, pointers, options, &mut path, &mut line, &mut spans);
+    spans
+}
+
+fn record_spans(
+    value: &Value,
+    pointers: &HashSet<String>,
+    options: &FormatOptions,
+    path: &mut String,
+    line: &mut usize,
+    spans: &mut HashMap<String, (usize, usize)>,
+) {
+    let start = *line;
+
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            if options.sort_keys {
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+            }
+            for (key, val) in entries {
+                let parent_len = path.len();
+                path.push('/');
+                path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                advance_line(line, options);
+                record_spans(val, pointers, options, path, line, spans);
+                path.truncate(parent_len);
+            }
+            advance_line(line, options);
+        }
+        Value::Array(arr) if !arr.is_empty() => {
+            for (index, val) in arr.iter().enumerate() {
+                let parent_len = path.len();
+                let _ = write!(path, "/{}", index);
+                advance_line(line, options);
+                record_spans(val, pointers, options, path, line, spans);
+                path.truncate(parent_len);
+            }
+            advance_line(line, options);
+        }
+        _ => {}
+    }
+
+    if pointers.contains(path.as_str()) {
+        spans.insert(path.clone(), (start, *line));
+    }
+}
+
+fn advance_line(line: &mut usize, options: &FormatOptions) {
+    if !options.compact {
+        *line += 1;
+    }
+}
@@ -1,121 +1,1046 @@
+use crate::error::FenrisError;
+use crate::json::json_diff::escape_pointer_segment;
+use crate::json::json_formatter::{format_value, FormatOptions};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use reqwest::Client;
-use serde_json::{from_str, to_string_pretty, Value};
-use tauri::{command, AppHandle};
-use tauri_plugin_store::StoreExt;
-use std::collections::BTreeMap;
+use serde::Deserialize;
+use serde_json::{from_str, Map, Number, Value};
+use std::collections::HashMap;
+use tauri::command;
 
 #[command]
-pub async fn fetch_json(url: String) -> Result<String, String> {
+pub async fn fetch_json(url: String) -> Result<String, FenrisError> {
+    let parsed_json = fetch_json_value(&url).await?;
+
+    Ok(format_value(&parsed_json, &FormatOptions::default()))
+}
+
+pub async fn fetch_json_value(url: &str) -> Result<Value, FenrisError> {
     let client = Client::new();
 
     let response = client
-        .get(&url)
+        .get(url)
         .send()
         .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .map_err(|e| FenrisError::network("api.request_failed", &[("error", &e)]))?;
 
     if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(FenrisError::network(
+            "api.http_error",
+            &[("status", &response.status())],
+        ));
     }
 
     let response_text = response
         .text()
         .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
+        .map_err(|e| FenrisError::network("api.response_failed", &[("error", &e)]))?;
+
+    // Parsing keeps key order since serde_json is built with `preserve_order`
+    from_str(&response_text)
+        .map_err(|e| FenrisError::network("api.invalid_response", &[("error", &e)]))
+}
+
+#[command]
+pub fn format_json(
+    json_string: String,
+    options: Option<FormatOptions>,
+) -> Result<String, FenrisError> {
+    let parsed_json: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+
+    Ok(format_value(&parsed_json, &options.unwrap_or_default()))
+}
+
+#[command]
+pub fn minify_json(json_string: String) -> Result<String, FenrisError> {
+    let parsed_json: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+
+    let options = FormatOptions {
+        compact: true,
+        ..FormatOptions::default()
+    };
+    Ok(format_value(&parsed_json, &options))
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConvertFormat {
+    Csv,
+    Yaml,
+    Toml,
+    Xml,
+}
 
-    // Parse and pretty-print the JSON while preserving order
-    let parsed_json: Value =
-        from_str(&response_text).map_err(|e| format!("Response is not valid JSON: {}", e))?;
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConvertOptions {
+    /// JSON Pointer of the array to tabulate as CSV, defaults to the root array
+    pub array_pointer: Option<String>,
+    pub delimiter: char,
+    /// Turn CSV and XML text into numbers, booleans and nulls where possible
+    pub infer_types: bool,
+    /// Element name used when the document has no single top-level key to become the XML root
+    pub root_name: String,
+}
 
-    // Use custom formatter that preserves key order
-    format_json_preserving_order(&parsed_json)
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            array_pointer: None,
+            delimiter: ',',
+            infer_types: true,
+            root_name: String::from("root"),
+        }
+    }
 }
 
 #[command]
-pub fn format_json(json_string: String) -> Result<String, String> {
-    let parsed_json: Value = from_str(&json_string).map_err(|e| format!("Invalid JSON: {}", e))?;
+pub fn convert_from_json(
+    json_string: String,
+    target: ConvertFormat,
+    options: Option<ConvertOptions>,
+) -> Result<String, FenrisError> {
+    let document: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+    let options = options.unwrap_or_default();
 
-    // Use custom formatter that preserves key order
-    format_json_preserving_order(&parsed_json)
+    match target {
+        ConvertFormat::Csv => json_to_csv(&document, &options),
+        ConvertFormat::Yaml => serde_yaml::to_string(&document)
+            .map_err(|e| FenrisError::validation("yaml.write_failed", &[("error", &e)])),
+        ConvertFormat::Toml => json_to_toml(&document),
+        ConvertFormat::Xml => json_to_xml(&document, &options),
+    }
 }
 
-// Custom JSON formatter that preserves key order
-fn format_json_preserving_order(value: &Value) -> Result<String, String> {
+#[command]
+pub fn convert_to_json(
+    input: String,
+    source: ConvertFormat,
+    options: Option<ConvertOptions>,
+    format: Option<FormatOptions>,
+) -> Result<String, FenrisError> {
+    let options = options.unwrap_or_default();
+
+    let document = match source {
+        ConvertFormat::Csv => csv_to_json(&input, &options)?,
+        ConvertFormat::Yaml => {
+            let yaml: serde_yaml::Value = serde_yaml::from_str(&input)
+                .map_err(|e| FenrisError::validation("yaml.invalid", &[("error", &e)]))?;
+            yaml_to_json(&yaml, "")?
+        }
+        ConvertFormat::Toml => {
+            let toml: toml::Table = toml::from_str(&input)
+                .map_err(|e| FenrisError::validation("toml.invalid", &[("error", &e)]))?;
+            toml_to_json(&toml::Value::Table(toml), "")?
+        }
+        ConvertFormat::Xml => xml_to_json(&input, &options)?,
+    };
+
+    Ok(format_value(&document, &format.unwrap_or_default()))
+}
+
+// Lists the arrays of objects in a document, the candidates for a CSV export
+#[command]
+pub fn find_tabular_arrays(json_string: String) -> Result<Vec<String>, FenrisError> {
+    let document: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+    let mut pointers = Vec::new();
+    collect_tabular_arrays(&document, &mut String::new(), &mut pointers);
+    Ok(pointers)
+}
+
+fn collect_tabular_arrays(value: &Value, pointer: &mut String, pointers: &mut Vec<String>) {
+    let parent_len = pointer.len();
     match value {
+        Value::Array(items) => {
+            if items.iter().any(Value::is_object) {
+                pointers.push(pointer.clone());
+            }
+            for (index, item) in items.iter().enumerate() {
+                pointer.push_str(&format!("/{}", index));
+                collect_tabular_arrays(item, pointer, pointers);
+                pointer.truncate(parent_len);
+            }
+        }
         Value::Object(map) => {
-            // Use BTreeMap to preserve insertion order (or alphabetical if you prefer)
-            let mut ordered_map = BTreeMap::new();
-            for (key, val) in map {
-                ordered_map.insert(key.clone(), format_json_preserving_order(val)?);
+            for (key, item) in map {
+                pointer.push('/');
+                pointer.push_str(&escape_pointer_segment(key));
+                collect_tabular_arrays(item, pointer, pointers);
+                pointer.truncate(parent_len);
             }
+        }
+        _ => {}
+    }
+}
+
+fn json_to_csv(document: &Value, options: &ConvertOptions) -> Result<String, FenrisError> {
+    let target = match &options.array_pointer {
+        Some(pointer) => document
+            .pointer(pointer)
+            .ok_or_else(|| FenrisError::not_found("json.path_not_found", &[("path", &pointer)]))?,
+        None => document,
+    };
 
-            let entries: Vec<String> = ordered_map
-                .into_iter()
-                .map(|(k, v)| format!("\"{}\": {}", k, v))
-                .collect();
+    let Value::Array(rows) = target else {
+        let mut candidates = Vec::new();
+        collect_tabular_arrays(document, &mut String::new(), &mut candidates);
+        return Err(if candidates.is_empty() {
+            FenrisError::validation("csv.no_array", &[])
+        } else {
+            FenrisError::validation("csv.choose_array", &[("arrays", &candidates.join(", "))])
+        });
+    };
 
-            Ok(format!("{{\n{}\n}}", entries.join(",\n")))
+    let delimiter = delimiter_byte(options.delimiter)?;
+
+    // Columns appear in the order they are first seen across rows
+    let mut columns: Vec<String> = Vec::new();
+    let mut column_index: HashMap<String, usize> = HashMap::new();
+    let mut flat_rows: Vec<Vec<(usize, String)>> = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut cells = Vec::new();
+        flatten_cells(row, "", &mut cells);
+        let cells = cells
+            .into_iter()
+            .map(|(column, cell)| {
+                let index = *column_index.entry(column).or_insert_with_key(|column| {
+                    columns.push(column.clone());
+                    columns.len() - 1
+                });
+                (index, cell)
+            })
+            .collect();
+        flat_rows.push(cells);
+    }
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+    writer
+        .write_record(&columns)
+        .map_err(|e| FenrisError::validation("csv.write_failed", &[("error", &e)]))?;
+    for cells in flat_rows {
+        let mut record = vec![""; columns.len()];
+        // A key such as "a.b" can collide with a nested one, the first cell wins
+        for (index, cell) in cells.iter().rev() {
+            record[*index] = cell;
         }
-        Value::Array(arr) => {
-            let entries: Vec<String> = arr
-                .iter()
-                .map(|v| format_json_preserving_order(v))
-                .collect::<Result<Vec<_>, _>>()?;
+        writer
+            .write_record(&record)
+            .map_err(|e| FenrisError::validation("csv.write_failed", &[("error", &e)]))?;
+    }
 
-            Ok(format!("[\n{}\n]", entries.join(",\n")))
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| FenrisError::validation("csv.write_failed", &[("error", &e)]))?;
+    String::from_utf8(bytes)
+        .map_err(|e| FenrisError::validation("csv.write_failed", &[("error", &e)]))
+}
+
+// Nested objects become dotted columns, arrays are kept as JSON text in a single cell
+fn flatten_cells(value: &Value, prefix: &str, cells: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, item) in map {
+                let column = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_cells(item, &column, cells);
+            }
+        }
+        _ => {
+            let column = if prefix.is_empty() { "value" } else { prefix };
+            let cell = match value {
+                Value::Null => String::new(),
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            cells.push((column.to_string(), cell));
         }
-        Value::String(s) => Ok(format!("\"{}\"", s)),
-        Value::Number(n) => Ok(n.to_string()),
-        Value::Bool(b) => Ok(b.to_string()),
-        Value::Null => Ok("null".to_string()),
     }
 }
 
-// Alternative: Use serde_json with custom formatter for better performance
-#[command]
-pub fn format_json_fast(json_string: String) -> Result<String, String> {
-    let parsed_json: Value = from_str(&json_string).map_err(|e| format!("Invalid JSON: {}", e))?;
+fn csv_to_json(input: &str, options: &ConvertOptions) -> Result<Value, FenrisError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter_byte(options.delimiter)?)
+        .from_reader(input.as_bytes());
 
-    // Use serde_json's pretty printer but with custom indentation
-    to_string_pretty(&parsed_json).map_err(|e| format!("Failed to format JSON: {}", e))
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| FenrisError::validation("csv.invalid", &[("error", &e)]))?
+        .iter()
+        .enumerate()
+        .map(|(index, header)| match header.trim() {
+            "" => format!("column_{}", index + 1),
+            name => name.to_string(),
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record =
+            record.map_err(|e| FenrisError::validation("csv.invalid", &[("error", &e)]))?;
+        let mut row = Map::new();
+        for (header, field) in headers.iter().zip(record.iter()) {
+            let value = if options.infer_types {
+                infer_scalar(field)
+            } else {
+                Value::String(field.to_string())
+            };
+            insert_dotted(&mut row, header, value)?;
+        }
+        rows.push(Value::Object(row));
+    }
+
+    Ok(Value::Array(rows))
 }
 
-#[command]
-pub fn save_json_file(app: AppHandle, key: String, value: String) -> Result<(), String> {
-    let store = app.store("store.json").map_err(|e| e.to_string())?;
-    let parsed_value: Value = from_str(&value).map_err(|e| format!("Invalid JSON: {}", e))?;
+fn delimiter_byte(delimiter: char) -> Result<u8, FenrisError> {
+    if delimiter.is_ascii() {
+        Ok(delimiter as u8)
+    } else {
+        Err(FenrisError::validation(
+            "csv.invalid_delimiter",
+            &[("delimiter", &delimiter)],
+        ))
+    }
+}
+
+fn infer_scalar(text: &str) -> Value {
+    match text {
+        "" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        // Cells written from arrays or objects are read back as JSON
+        _ if text.starts_with(['[', '{']) => {
+            from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+        }
+        _ => match from_str::<Number>(text) {
+            Ok(number) => Value::Number(number),
+            Err(_) => Value::String(text.to_string()),
+        },
+    }
+}
 
-    store.set(key, parsed_value);
+fn insert_dotted(
+    row: &mut Map<String, Value>,
+    column: &str,
+    value: Value,
+) -> Result<(), FenrisError> {
+    let conflict = || FenrisError::conflict("csv.column_conflict", &[("column", &column)]);
+    let mut segments: Vec<&str> = column.split('.').collect();
+    let last = segments.pop().unwrap_or_default();
 
-    store
-        .save()
-        .map_err(|e| format!("Failed to save store: {}", e))?;
+    let mut target = row;
+    for segment in segments {
+        let entry = target
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        target = entry.as_object_mut().ok_or_else(conflict)?;
+    }
+
+    if target.contains_key(last) {
+        return Err(conflict());
+    }
+    target.insert(last.to_string(), value);
     Ok(())
 }
 
-#[command]
-pub fn list_json_files(app: AppHandle) -> Result<Vec<String>, String> {
-    let store = app.store("store.json").map_err(|e| e.to_string())?;
-    Ok(store.keys().into_iter().collect())
+fn yaml_to_json(yaml: &serde_yaml::Value, path: &str) -> Result<Value, FenrisError> {
+    let at = |path: &str| if path.is_empty() { "/" } else { path }.to_string();
+
+    Ok(match yaml {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(*b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                let f = n.as_f64().unwrap_or(f64::NAN);
+                Value::Number(Number::from_f64(f).ok_or_else(|| {
+                    FenrisError::validation(
+                        "json.unrepresentable_number",
+                        &[("value", &n), ("path", &at(path))],
+                    )
+                })?)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s.clone()),
+        serde_yaml::Value::Sequence(items) => Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| yaml_to_json(item, &format!("{}/{}", path, index)))
+                .collect::<Result<_, _>>()?,
+        ),
+        serde_yaml::Value::Mapping(mapping) => {
+            let mut map = Map::new();
+            for (key, item) in mapping {
+                let key = match key {
+                    serde_yaml::Value::String(s) => s.clone(),
+                    serde_yaml::Value::Number(n) => n.to_string(),
+                    serde_yaml::Value::Bool(b) => b.to_string(),
+                    _ => {
+                        return Err(FenrisError::validation(
+                            "yaml.complex_key",
+                            &[("path", &at(path))],
+                        ))
+                    }
+                };
+                let child_path = format!("{}/{}", path, escape_pointer_segment(&key));
+                map.insert(key, yaml_to_json(item, &child_path)?);
+            }
+            Value::Object(map)
+        }
+        serde_yaml::Value::Tagged(tagged) => {
+            return Err(FenrisError::validation(
+                "yaml.unsupported_tag",
+                &[("tag", &tagged.tag), ("path", &at(path))],
+            ))
+        }
+    })
 }
 
-#[command]
-pub fn get_json_file(app: AppHandle, key: String) -> Result<String, String> {
-    let store = app.store("store.json").map_err(|e| e.to_string())?;
-    match store.get(&key) {
-        Some(value) => {
-            to_string_pretty(&value).map_err(|e| format!("Failed to format JSON: {}", e))
+fn json_to_toml(document: &Value) -> Result<String, FenrisError> {
+    if !document.is_object() {
+        return Err(FenrisError::validation("toml.not_a_table", &[]));
+    }
+    let toml_value = json_value_to_toml(document, "")?;
+    toml::to_string(&toml_value)
+        .map_err(|e| FenrisError::validation("toml.write_failed", &[("error", &e)]))
+}
+
+fn json_value_to_toml(value: &Value, path: &str) -> Result<toml::Value, FenrisError> {
+    let at = |path: &str| if path.is_empty() { "/" } else { path }.to_string();
+
+    Ok(match value {
+        Value::Null => {
+            return Err(FenrisError::validation(
+                "toml.null_value",
+                &[("path", &at(path))],
+            ))
+        }
+        Value::Bool(b) => toml::Value::Boolean(*b),
+        Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => toml::Value::Integer(i),
+            (None, Some(f)) if !n.is_u64() => toml::Value::Float(f),
+            _ => {
+                return Err(FenrisError::validation(
+                    "toml.integer_too_large",
+                    &[("value", &n), ("path", &at(path))],
+                ))
+            }
+        },
+        Value::String(s) => toml::Value::String(s.clone()),
+        Value::Array(items) => toml::Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| json_value_to_toml(item, &format!("{}/{}", path, index)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => {
+            let mut table = toml::Table::new();
+            for (key, item) in map {
+                let child_path = format!("{}/{}", path, escape_pointer_segment(key));
+                table.insert(key.clone(), json_value_to_toml(item, &child_path)?);
+            }
+            toml::Value::Table(table)
         }
-        None => Err(String::from("File not found")),
+    })
+}
+
+fn toml_to_json(value: &toml::Value, path: &str) -> Result<Value, FenrisError> {
+    Ok(match value {
+        toml::Value::String(s) => Value::String(s.clone()),
+        toml::Value::Integer(i) => Value::from(*i),
+        toml::Value::Float(f) => Value::Number(Number::from_f64(*f).ok_or_else(|| {
+            FenrisError::validation(
+                "json.unrepresentable_number",
+                &[
+                    ("value", &f),
+                    ("path", &if path.is_empty() { "/" } else { path }),
+                ],
+            )
+        })?),
+        toml::Value::Boolean(b) => Value::Bool(*b),
+        // JSON has no date type, so datetimes are kept in their TOML text form
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| toml_to_json(item, &format!("{}/{}", path, index)))
+                .collect::<Result<_, _>>()?,
+        ),
+        toml::Value::Table(table) => {
+            let mut map = Map::new();
+            for (key, item) in table {
+                let child_path = format!("{}/{}", path, escape_pointer_segment(key));
+                map.insert(key.clone(), toml_to_json(item, &child_path)?);
+            }
+            Value::Object(map)
+        }
+    })
+}
+
+// Attributes are written from "@name" keys and text content from "#text",
+// the same convention `xml_to_json` reads back
+fn json_to_xml(document: &Value, options: &ConvertOptions) -> Result<String, FenrisError> {
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    let single_root = match document {
+        Value::Object(map) if map.len() == 1 => map
+            .iter()
+            .next()
+            .filter(|(key, _)| !key.starts_with(['@', '#'])),
+        _ => None,
+    };
+
+    match (single_root, document) {
+        (Some((name, value)), _) => {
+            if value.is_array() {
+                return Err(FenrisError::validation(
+                    "xml.array_root",
+                    &[("name", &name)],
+                ));
+            }
+            write_xml_element(&mut output, name, value, 0, &format!("/{}", name))?;
+        }
+        (None, Value::Array(_)) => {
+            let wrapped = Value::Object(Map::from_iter([(String::from("item"), document.clone())]));
+            write_xml_element(&mut output, &options.root_name, &wrapped, 0, "")?;
+        }
+        (None, _) => write_xml_element(&mut output, &options.root_name, document, 0, "")?,
     }
+
+    Ok(output)
 }
 
-#[command]
-pub fn delete_json_file(app: AppHandle, key: String) -> Result<(), String> {
-    let store = app.store("store.json").map_err(|e| e.to_string())?;
-    store.delete(&key);
-    store
-        .save()
-        .map_err(|e| format!("Failed to save store: {}", e))?;
+fn write_xml_element(
+    output: &mut String,
+    name: &str,
+    value: &Value,
+    depth: usize,
+    path: &str,
+) -> Result<(), FenrisError> {
+    let at = |path: &str| if path.is_empty() { "/" } else { path }.to_string();
+    if !is_xml_name(name) {
+        return Err(FenrisError::validation(
+            "xml.invalid_name",
+            &[("name", &name), ("path", &at(path))],
+        ));
+    }
+
+    let indent = "  ".repeat(depth);
+    match value {
+        Value::Array(items) => {
+            if items.is_empty() {
+                return Err(FenrisError::validation(
+                    "xml.empty_array",
+                    &[("path", &at(path))],
+                ));
+            }
+            // A lone element reads back as a single value, so the array would not survive
+            if items.len() == 1 {
+                return Err(FenrisError::validation(
+                    "xml.single_item_array",
+                    &[("path", &at(path))],
+                ));
+            }
+            for (index, item) in items.iter().enumerate() {
+                let item_path = format!("{}/{}", path, index);
+                if item.is_array() {
+                    return Err(FenrisError::validation(
+                        "xml.nested_array",
+                        &[("path", &item_path)],
+                    ));
+                }
+                write_xml_element(output, name, item, depth, &item_path)?;
+            }
+        }
+        Value::Object(map) => {
+            output.push_str(&format!("{}<{}", indent, name));
+
+            let mut text = None;
+            let mut children = Vec::new();
+            for (key, item) in map {
+                if let Some(attribute) = key.strip_prefix('@') {
+                    let item_path = format!("{}/{}", path, escape_pointer_segment(key));
+                    if !is_xml_name(attribute) || item.is_array() || item.is_object() {
+                        return Err(FenrisError::validation(
+                            "xml.invalid_attribute_value",
+                            &[("key", &key), ("path", &item_path)],
+                        ));
+                    }
+                    output.push_str(&format!(
+                        " {}=\"{}\"",
+                        attribute,
+                        quick_xml::escape::escape(scalar_text(item).as_str())
+                    ));
+                } else if key == "#text" {
+                    text = Some(scalar_text(item));
+                } else {
+                    children.push((key, item));
+                }
+            }
+
+            match (text, children.is_empty()) {
+                (None, true) => output.push_str("/>\n"),
+                (Some(text), true) => output.push_str(&format!(
+                    ">{}</{}>\n",
+                    quick_xml::escape::escape(text.as_str()),
+                    name
+                )),
+                (text, false) => {
+                    output.push_str(">\n");
+                    if let Some(text) = text {
+                        output.push_str(&format!(
+                            "{}  {}\n",
+                            indent,
+                            quick_xml::escape::escape(text.as_str())
+                        ));
+                    }
+                    for (key, item) in children {
+                        let child_path = format!("{}/{}", path, escape_pointer_segment(key));
+                        write_xml_element(output, key, item, depth + 1, &child_path)?;
+                    }
+                    output.push_str(&format!("{}</{}>\n", indent, name));
+                }
+            }
+        }
+        Value::Null => output.push_str(&format!("{}<{}/>\n", indent, name)),
+        scalar => output.push_str(&format!(
+            "{}<{}>{}</{}>\n",
+            indent,
+            name,
+            quick_xml::escape::escape(scalar_text(scalar).as_str()),
+            name
+        )),
+    }
+
     Ok(())
-}
\ No newline at end of file
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_alphabetic() || first == '_' => {}
+        _ => return false,
+    }
+    !name.to_lowercase().starts_with("xml")
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+}
+
+fn xml_to_json(input: &str, options: &ConvertOptions) -> Result<Value, FenrisError> {
+    struct OpenElement {
+        name: String,
+        map: Map<String, Value>,
+        text: String,
+    }
+
+    // Text is trimmed once per element, trimming each event would drop spaces around entities
+    let mut reader = Reader::from_str(input);
+
+    let xml_error = |reader: &Reader<&[u8]>, e: &dyn std::fmt::Display| {
+        FenrisError::validation(
+            "xml.invalid",
+            &[("offset", &reader.buffer_position()), ("error", &e)],
+        )
+    };
+    let open_element = |start: &BytesStart| -> Result<OpenElement, FenrisError> {
+        let mut map = Map::new();
+        for attribute in start.attributes() {
+            let attribute = attribute
+                .map_err(|e| FenrisError::validation("xml.invalid_attribute", &[("error", &e)]))?;
+            let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+            let value = attribute
+                .unescape_value()
+                .map_err(|e| FenrisError::validation("xml.invalid_attribute", &[("error", &e)]))?;
+            let value = if options.infer_types {
+                infer_scalar(&value)
+            } else {
+                Value::String(value.to_string())
+            };
+            map.insert(format!("@{}", key), value);
+        }
+        Ok(OpenElement {
+            name: String::from_utf8_lossy(start.name().as_ref()).to_string(),
+            map,
+            text: String::new(),
+        })
+    };
+    let close_element = |element: OpenElement| -> (String, Value) {
+        let text = element.text.trim();
+        let text_value = if options.infer_types {
+            infer_scalar(text)
+        } else {
+            Value::String(text.to_string())
+        };
+
+        let value = if element.map.is_empty() {
+            if text.is_empty() {
+                Value::Null
+            } else {
+                text_value
+            }
+        } else {
+            let mut map = element.map;
+            if !text.is_empty() {
+                map.insert(String::from("#text"), text_value);
+            }
+            Value::Object(map)
+        };
+        (element.name, value)
+    };
+
+    let mut stack: Vec<OpenElement> = Vec::new();
+    let mut root: Option<(String, Value)> = None;
+
+    loop {
+        let event = reader.read_event().map_err(|e| xml_error(&reader, &e))?;
+
+        let closed = match event {
+            Event::Start(start) => {
+                stack.push(open_element(&start)?);
+                None
+            }
+            Event::Empty(start) => Some(close_element(open_element(&start)?)),
+            Event::End(_) => stack.pop().map(close_element),
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    let decoded = text.decode().map_err(|e| xml_error(&reader, &e))?;
+                    let unescaped = quick_xml::escape::unescape(&decoded)
+                        .map_err(|e| xml_error(&reader, &e))?;
+                    element.text.push_str(&unescaped);
+                }
+                None
+            }
+            Event::GeneralRef(reference) => {
+                if let Some(element) = stack.last_mut() {
+                    let name = reference.decode().map_err(|e| xml_error(&reader, &e))?;
+                    match reference
+                        .resolve_char_ref()
+                        .map_err(|e| xml_error(&reader, &e))?
+                    {
+                        Some(c) => element.text.push(c),
+                        None => element.text.push_str(
+                            quick_xml::escape::resolve_predefined_entity(&name).ok_or_else(
+                                || {
+                                    FenrisError::validation(
+                                        "xml.unknown_entity",
+                                        &[("name", &name)],
+                                    )
+                                },
+                            )?,
+                        ),
+                    }
+                }
+                None
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element
+                        .text
+                        .push_str(&String::from_utf8_lossy(data.as_ref()));
+                }
+                None
+            }
+            Event::Eof => break,
+            _ => None,
+        };
+
+        // Repeated sibling elements are collected into an array
+        if let Some((name, value)) = closed {
+            match stack.last_mut() {
+                Some(parent) => match parent.map.get_mut(&name) {
+                    Some(Value::Array(items)) => items.push(value),
+                    Some(existing) => {
+                        let first = existing.take();
+                        *existing = Value::Array(vec![first, value]);
+                    }
+                    None => {
+                        parent.map.insert(name, value);
+                    }
+                },
+                None => root = Some((name, value)),
+            }
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(FenrisError::validation("xml.unexpected_end", &[]));
+    }
+    let (name, value) = root.ok_or_else(|| FenrisError::validation("xml.no_root", &[]))?;
+    Ok(Value::Object(Map::from_iter([(name, value)])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn csv_options(delimiter: char, infer_types: bool) -> ConvertOptions {
+        ConvertOptions {
+            delimiter,
+            infer_types,
+            ..ConvertOptions::default()
+        }
+    }
+
+    fn yaml(input: &str) -> Result<Value, FenrisError> {
+        yaml_to_json(&serde_yaml::from_str(input).unwrap(), "")
+    }
+
+    fn toml(input: &str) -> Result<Value, FenrisError> {
+        toml_to_json(&toml::Value::Table(toml::from_str(input).unwrap()), "")
+    }
+
+    #[test]
+    fn csv_flattens_rows_into_columns_in_first_seen_order() {
+        let document = json!([
+            {"id": 1, "user": {"name": "Ann"}, "tags": ["a", "b"]},
+            {"id": 2, "extra": null, "user": {"name": "Bob"}}
+        ]);
+        assert_eq!(
+            json_to_csv(&document, &ConvertOptions::default()).unwrap(),
+            "id,user.name,tags,extra\n1,Ann,\"[\"\"a\"\",\"\"b\"\"]\",\n2,Bob,,\n"
+        );
+    }
+
+    #[test]
+    fn csv_uses_the_array_pointer_and_lists_candidates() {
+        let document = json!({"meta": {"count": 1}, "rows": [{"x": 1}]});
+        let options = ConvertOptions {
+            array_pointer: Some(String::from("/rows")),
+            delimiter: ';',
+            ..ConvertOptions::default()
+        };
+        assert_eq!(json_to_csv(&document, &options).unwrap(), "x\n1\n");
+
+        let error = json_to_csv(&document, &ConvertOptions::default()).unwrap_err();
+        assert_eq!(error.code, "csv.choose_array");
+        assert_eq!(error.context["arrays"], "/rows");
+
+        assert_eq!(
+            json_to_csv(&json!({"a": 1}), &ConvertOptions::default())
+                .unwrap_err()
+                .code,
+            "csv.no_array"
+        );
+        let missing = ConvertOptions {
+            array_pointer: Some(String::from("/missing")),
+            ..ConvertOptions::default()
+        };
+        assert_eq!(
+            json_to_csv(&document, &missing).unwrap_err().code,
+            "json.path_not_found"
+        );
+    }
+
+    #[test]
+    fn csv_reads_back_nested_columns_and_inferred_types() {
+        let input = "id,user.name,tags,active,\n1,Ann,\"[\"\"a\"\"]\",true,\n2,,x,false,note\n";
+        assert_eq!(
+            csv_to_json(input, &ConvertOptions::default()).unwrap(),
+            json!([
+                {"id": 1, "user": {"name": "Ann"}, "tags": ["a"], "active": true, "column_5": null},
+                {"id": 2, "user": {"name": null}, "tags": "x", "active": false, "column_5": "note"}
+            ])
+        );
+        assert_eq!(
+            csv_to_json("a;b\n1;\n", &csv_options(';', false)).unwrap(),
+            json!([{"a": "1", "b": ""}])
+        );
+    }
+
+    #[test]
+    fn csv_rejects_conflicting_columns_and_bad_delimiters() {
+        let error = csv_to_json("a,a.b\n1,2\n", &ConvertOptions::default()).unwrap_err();
+        assert_eq!(error.code, "csv.column_conflict");
+        assert_eq!(error.context["column"], "a.b");
+
+        assert_eq!(
+            csv_to_json("a\n1\n", &csv_options('é', true))
+                .unwrap_err()
+                .code,
+            "csv.invalid_delimiter"
+        );
+    }
+
+    #[test]
+    fn xml_writes_attributes_text_and_repeated_elements() {
+        let document = json!({
+            "note": {
+                "@id": 7,
+                "title": "Hi & bye",
+                "tag": ["a", "b"],
+                "body": {"@lang": "en", "#text": "Hello"},
+                "empty": null
+            }
+        });
+        let xml = json_to_xml(&document, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <note id=\"7\">\n  \
+             <title>Hi &amp; bye</title>\n  \
+             <tag>a</tag>\n  \
+             <tag>b</tag>\n  \
+             <body lang=\"en\">Hello</body>\n  \
+             <empty/>\n\
+             </note>\n"
+        );
+        assert_eq!(
+            xml_to_json(&xml, &ConvertOptions::default()).unwrap(),
+            document
+        );
+    }
+
+    #[test]
+    fn xml_wraps_documents_without_a_single_root_key() {
+        let options = ConvertOptions {
+            root_name: String::from("items"),
+            ..ConvertOptions::default()
+        };
+        assert_eq!(
+            json_to_xml(&json!([1, 2]), &options).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <items>\n  <item>1</item>\n  <item>2</item>\n</items>\n"
+        );
+        assert_eq!(
+            json_to_xml(&json!({"a": 1, "b": 2}), &options).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <items>\n  <a>1</a>\n  <b>2</b>\n</items>\n"
+        );
+    }
+
+    #[test]
+    fn xml_rejects_arrays_that_would_not_read_back() {
+        let options = ConvertOptions::default();
+        let error = json_to_xml(&json!({"note": {"tag": ["a"]}}), &options).unwrap_err();
+        assert_eq!(error.code, "xml.single_item_array");
+        assert_eq!(error.context["path"], "/note/tag");
+
+        let cases = [
+            (json!({"note": {"tag": []}}), "xml.empty_array"),
+            (json!({"note": {"tag": [[1], [2]]}}), "xml.nested_array"),
+            (json!({"list": [1, 2]}), "xml.array_root"),
+            (json!({"note": {"1st": 1}}), "xml.invalid_name"),
+            (json!({"note": {"@id": [1]}}), "xml.invalid_attribute_value"),
+        ];
+        for (document, code) in cases {
+            assert_eq!(json_to_xml(&document, &options).unwrap_err().code, code);
+        }
+    }
+
+    #[test]
+    fn xml_reads_entities_cdata_and_keeps_strings_when_not_inferring() {
+        let options = csv_options(',', false);
+        assert_eq!(
+            xml_to_json(
+                "<a n=\"1\"><b> x &lt; y </b><c><![CDATA[<raw>]]></c></a>",
+                &options
+            )
+            .unwrap(),
+            json!({"a": {"@n": "1", "b": "x < y", "c": "<raw>"}})
+        );
+        assert_eq!(xml_to_json("", &options).unwrap_err().code, "xml.no_root");
+    }
+
+    #[test]
+    fn toml_round_trips_tables() {
+        let document = json!({"title": "x", "owner": {"age": 3, "ratio": 0.5, "tags": ["a"]}});
+        let written = json_to_toml(&document).unwrap();
+        assert_eq!(
+            written,
+            "title = \"x\"\n\n[owner]\nage = 3\nratio = 0.5\ntags = [\"a\"]\n"
+        );
+        assert_eq!(toml(&written).unwrap(), document);
+    }
+
+    #[test]
+    fn toml_keeps_keys_in_source_order() {
+        let document = json!({"zeta": 1, "alpha": 2, "mid": {"b": 1, "a": 2}});
+        let written = json_to_toml(&document).unwrap();
+        assert_eq!(written, "zeta = 1\nalpha = 2\n\n[mid]\nb = 1\na = 2\n");
+
+        let keys: Vec<String> = toml(&written)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(keys, ["zeta", "alpha", "mid"]);
+    }
+
+    #[test]
+    fn toml_rejects_values_it_cannot_hold() {
+        let error = json_to_toml(&json!({"owner": {"age": null}})).unwrap_err();
+        assert_eq!(error.code, "toml.null_value");
+        assert_eq!(error.context["path"], "/owner/age");
+
+        assert_eq!(
+            json_to_toml(&json!([1])).unwrap_err().code,
+            "toml.not_a_table"
+        );
+        assert_eq!(
+            json_to_toml(&json!({"big": u64::MAX})).unwrap_err().code,
+            "toml.integer_too_large"
+        );
+    }
+
+    #[test]
+    fn toml_keeps_datetimes_as_text_and_rejects_nan() {
+        assert_eq!(
+            toml("when = 1979-05-27T07:32:00Z\nvalues = [1, 2.5]").unwrap(),
+            json!({"when": "1979-05-27T07:32:00Z", "values": [1, 2.5]})
+        );
+        let error = toml("a = { b = nan }").unwrap_err();
+        assert_eq!(error.code, "json.unrepresentable_number");
+        assert_eq!(error.context["path"], "/a/b");
+    }
+
+    #[test]
+    fn yaml_converts_scalars_and_stringifies_keys() {
+        assert_eq!(
+            yaml("a: 1\nb: [x, true, null, 1.5]\n1: one\nfalse: no\n").unwrap(),
+            json!({"a": 1, "b": ["x", true, null, 1.5], "1": "one", "false": "no"})
+        );
+    }
+
+    #[test]
+    fn yaml_rejects_complex_keys_tags_and_nan() {
+        assert_eq!(
+            yaml("? [a, b]\n: 1\n").unwrap_err().code,
+            "yaml.complex_key"
+        );
+
+        let error = yaml("a:\n  - !custom 1\n").unwrap_err();
+        assert_eq!(error.code, "yaml.unsupported_tag");
+        assert_eq!(error.context["path"], "/a/0");
+
+        assert_eq!(
+            yaml("a: .nan\n").unwrap_err().code,
+            "json.unrepresentable_number"
+        );
+    }
+}
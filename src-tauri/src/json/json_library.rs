@@ -0,0 +1,652 @@
+use crate::error::FenrisError;
+use crate::sqlite::app_sqlite_pool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string, to_string_pretty, Value};
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use tauri::{command, AppHandle};
+use tauri_plugin_store::StoreExt;
+
+// Older versions are pruned once a document has this many
+const MAX_VERSIONS: i64 = 50;
+const LEGACY_STORE: &str = "store.json";
+// Tag names are joined with the ASCII unit separator so commas in names survive
+const TAG_SEPARATOR: char = '\u{1f}';
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonDocument {
+    pub id: i64,
+    pub name: String,
+    pub source_url: Option<String>,
+    pub size_bytes: i64,
+    pub current_version: i64,
+    pub version_count: i64,
+    pub tags: Vec<String>,
+    pub date_created: String,
+    pub date_modified: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonDocumentVersion {
+    pub version: i64,
+    pub size_bytes: i64,
+    pub date_created: String,
+    pub is_current: bool,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonDocumentSort {
+    #[default]
+    Name,
+    Created,
+    Updated,
+    Size,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct JsonDocumentQuery {
+    pub sort: JsonDocumentSort,
+    pub descending: bool,
+    /// Matches anywhere in the document name
+    pub search: Option<String>,
+    pub tag: Option<String>,
+    pub source_url: Option<String>,
+}
+
+type DocumentRow = (
+    i64,
+    String,
+    Option<String>,
+    i64,
+    i64,
+    i64,
+    Option<String>,
+    String,
+    String,
+);
+
+const DOCUMENT_COLUMNS: &str = "d.id, d.name, d.source_url, d.size_bytes, d.current_version,
+    (SELECT COUNT(*) FROM json_document_versions v WHERE v.document_id = d.id),
+    (SELECT GROUP_CONCAT(t.name, char(31)) FROM json_document_tags dt
+        JOIN tags t ON t.id = dt.tag_id WHERE dt.document_id = d.id),
+    CAST(d.date_created AS TEXT), CAST(d.date_modified AS TEXT)";
+
+// Saving identical content only updates metadata, otherwise a new version is recorded
+#[command]
+pub async fn save_json_file(
+    app: AppHandle,
+    key: String,
+    value: String,
+    source_url: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<JsonDocument, FenrisError> {
+    let name = key.trim().to_string();
+    if name.is_empty() {
+        return Err(FenrisError::validation("document.name_empty", &[]));
+    }
+    let parsed_value: Value =
+        from_str(&value).map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+    let content = to_string(&parsed_value)
+        .map_err(|e| FenrisError::internal("json.serialize_failed", &[("error", &e)]))?;
+
+    let pool = app_sqlite_pool(&app).await?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+
+    let document_id = match find_document_id(&mut tx, &name).await? {
+        Some(id) => {
+            let current = current_content(&mut tx, id).await?;
+            if current != content {
+                add_version(&mut tx, id, &content).await?;
+            }
+            if let Some(url) = &source_url {
+                sqlx::query("UPDATE json_documents SET source_url = ? WHERE id = ?")
+                    .bind(url)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| FenrisError::sqlx("document.update_failed", &e, &[]))?;
+            }
+            id
+        }
+        None => create_document(&mut tx, &name, source_url.as_deref(), &content).await?,
+    };
+
+    if let Some(tags) = &tags {
+        replace_tags(&mut tx, document_id, tags).await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("document.save_failed", &e, &[]))?;
+    get_document(&pool, &name).await
+}
+
+#[command]
+pub async fn list_json_files(app: AppHandle) -> Result<Vec<String>, FenrisError> {
+    let documents = list_json_documents(app, None).await?;
+    Ok(documents
+        .into_iter()
+        .map(|document| document.name)
+        .collect())
+}
+
+#[command]
+pub async fn list_json_documents(
+    app: AppHandle,
+    query: Option<JsonDocumentQuery>,
+) -> Result<Vec<JsonDocument>, FenrisError> {
+    let query = query.unwrap_or_default();
+    let pool = app_sqlite_pool(&app).await?;
+
+    let order_column = match query.sort {
+        JsonDocumentSort::Name => "d.name COLLATE NOCASE",
+        JsonDocumentSort::Created => "d.date_created",
+        JsonDocumentSort::Updated => "d.date_modified",
+        JsonDocumentSort::Size => "d.size_bytes",
+    };
+    let direction = if query.descending { "DESC" } else { "ASC" };
+    let sql = format!(
+        "SELECT {} FROM json_documents d
+        WHERE (?1 IS NULL OR d.name LIKE '%' || ?1 || '%' ESCAPE '\\')
+            AND (?2 IS NULL OR EXISTS (SELECT 1 FROM json_document_tags dt
+                JOIN tags t ON t.id = dt.tag_id WHERE dt.document_id = d.id AND t.name = ?2))
+            AND (?3 IS NULL OR d.source_url LIKE '%' || ?3 || '%' ESCAPE '\\')
+        ORDER BY {} {}, d.id",
+        DOCUMENT_COLUMNS, order_column, direction
+    );
+
+    let rows: Vec<DocumentRow> = sqlx::query_as(&sql)
+        .bind(non_empty(query.search).map(|s| escape_like(&s)))
+        .bind(non_empty(query.tag))
+        .bind(non_empty(query.source_url).map(|s| escape_like(&s)))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| FenrisError::sqlx("document.list_failed", &e, &[]))?;
+
+    Ok(rows.into_iter().map(document_from_row).collect())
+}
+
+#[command]
+pub async fn get_json_file(app: AppHandle, key: String) -> Result<String, FenrisError> {
+    let pool = app_sqlite_pool(&app).await?;
+    let value = load_json_document(&pool, &key, None).await?;
+    to_string_pretty(&value)
+        .map_err(|e| FenrisError::validation("json.format_failed", &[("error", &e)]))
+}
+
+#[command]
+pub async fn delete_json_file(app: AppHandle, key: String) -> Result<(), FenrisError> {
+    let pool = app_sqlite_pool(&app).await?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+
+    let id = find_document_id(&mut tx, &key)
+        .await?
+        .ok_or_else(|| FenrisError::not_found("document.not_found", &[]))?;
+    for sql in [
+        "DELETE FROM json_document_versions WHERE document_id = ?",
+        "DELETE FROM json_document_tags WHERE document_id = ?",
+        "DELETE FROM json_documents WHERE id = ?",
+    ] {
+        sqlx::query(sql)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FenrisError::sqlx("document.delete_failed", &e, &[]))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("document.delete_failed", &e, &[]))
+}
+
+#[command]
+pub async fn rename_json_document(
+    app: AppHandle,
+    name: String,
+    new_name: String,
+) -> Result<JsonDocument, FenrisError> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err(FenrisError::validation("document.name_empty", &[]));
+    }
+
+    // The UNIQUE index on the name decides, a check before the update could race another rename
+    let pool = app_sqlite_pool(&app).await?;
+    let result =
+        sqlx::query("UPDATE json_documents SET name = ?, date_modified = ? WHERE name = ?")
+            .bind(&new_name)
+            .bind(timestamp())
+            .bind(&name)
+            .execute(&pool)
+            .await
+            .map_err(|e| match e.as_database_error() {
+                Some(error) if error.is_unique_violation() => {
+                    FenrisError::conflict("document.exists", &[("name", &new_name)])
+                }
+                _ => FenrisError::sqlx("document.rename_failed", &e, &[]),
+            })?;
+    if result.rows_affected() == 0 {
+        return Err(FenrisError::not_found("document.not_found", &[]));
+    }
+
+    get_document(&pool, &new_name).await
+}
+
+// The copy starts a fresh history from the current version and keeps the source URL and tags
+#[command]
+pub async fn duplicate_json_document(
+    app: AppHandle,
+    name: String,
+    new_name: String,
+) -> Result<JsonDocument, FenrisError> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err(FenrisError::validation("document.name_empty", &[]));
+    }
+
+    let pool = app_sqlite_pool(&app).await?;
+    if document_exists(&pool, &new_name).await? {
+        return Err(FenrisError::conflict(
+            "document.exists",
+            &[("name", &new_name)],
+        ));
+    }
+    let source = get_document(&pool, &name).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+    let content = current_content(&mut tx, source.id).await?;
+    let id = create_document(&mut tx, &new_name, source.source_url.as_deref(), &content).await?;
+    replace_tags(&mut tx, id, &source.tags).await?;
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("document.duplicate_failed", &e, &[]))?;
+
+    get_document(&pool, &new_name).await
+}
+
+#[command]
+pub async fn list_json_document_versions(
+    app: AppHandle,
+    name: String,
+) -> Result<Vec<JsonDocumentVersion>, FenrisError> {
+    let pool = app_sqlite_pool(&app).await?;
+    let rows: Vec<(i64, i64, String, bool)> = sqlx::query_as(
+        "SELECT v.version, v.size_bytes, CAST(v.date_created AS TEXT), v.version = d.current_version
+        FROM json_document_versions v JOIN json_documents d ON d.id = v.document_id
+        WHERE d.name = ? ORDER BY v.version DESC",
+    )
+    .bind(&name)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("document.versions_list_failed", &e, &[]))?;
+
+    if rows.is_empty() {
+        return Err(FenrisError::not_found("document.not_found", &[]));
+    }
+    Ok(rows
+        .into_iter()
+        .map(
+            |(version, size_bytes, date_created, is_current)| JsonDocumentVersion {
+                version,
+                size_bytes,
+                date_created,
+                is_current,
+            },
+        )
+        .collect())
+}
+
+#[command]
+pub async fn get_json_document_version(
+    app: AppHandle,
+    name: String,
+    version: i64,
+) -> Result<String, FenrisError> {
+    let pool = app_sqlite_pool(&app).await?;
+    let value = load_json_document(&pool, &name, Some(version)).await?;
+    to_string_pretty(&value)
+        .map_err(|e| FenrisError::validation("json.format_failed", &[("error", &e)]))
+}
+
+// Rolling back records the old content as a new version, so the rollback itself can be undone
+#[command]
+pub async fn rollback_json_document(
+    app: AppHandle,
+    name: String,
+    version: i64,
+) -> Result<JsonDocument, FenrisError> {
+    let pool = app_sqlite_pool(&app).await?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+
+    let id = find_document_id(&mut tx, &name)
+        .await?
+        .ok_or_else(|| FenrisError::not_found("document.not_found", &[]))?;
+    let (content,): (String,) = sqlx::query_as(
+        "SELECT content FROM json_document_versions WHERE document_id = ? AND version = ?",
+    )
+    .bind(id)
+    .bind(version)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| FenrisError::sqlx("document.version_read_failed", &e, &[]))?
+    .ok_or_else(|| {
+        FenrisError::not_found("document.version_not_found", &[("version", &version)])
+    })?;
+
+    if current_content(&mut tx, id).await? != content {
+        add_version(&mut tx, id, &content).await?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("document.rollback_failed", &e, &[]))?;
+
+    get_document(&pool, &name).await
+}
+
+#[command]
+pub async fn set_json_document_tags(
+    app: AppHandle,
+    name: String,
+    tags: Vec<String>,
+) -> Result<JsonDocument, FenrisError> {
+    let pool = app_sqlite_pool(&app).await?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+    let id = find_document_id(&mut tx, &name)
+        .await?
+        .ok_or_else(|| FenrisError::not_found("document.not_found", &[]))?;
+    replace_tags(&mut tx, id, &tags).await?;
+    tx.commit()
+        .await
+        .map_err(|e| FenrisError::sqlx("document.tags_failed", &e, &[]))?;
+
+    get_document(&pool, &name).await
+}
+
+// Loads the current version, or the given one, as a parsed value
+pub async fn load_json_document(
+    pool: &Pool<Sqlite>,
+    name: &str,
+    version: Option<i64>,
+) -> Result<Value, FenrisError> {
+    let row: Option<(String,)> = sqlx::query_as(
+        "SELECT v.content FROM json_document_versions v
+        JOIN json_documents d ON d.id = v.document_id
+        WHERE d.name = ? AND v.version = COALESCE(?, d.current_version)",
+    )
+    .bind(name)
+    .bind(version)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("document.read_failed", &e, &[]))?;
+
+    let (content,) = row.ok_or_else(|| match version {
+        Some(version) => {
+            FenrisError::not_found("document.version_not_found", &[("version", &version)])
+        }
+        None => FenrisError::not_found("document.not_found", &[]),
+    })?;
+    from_str(&content)
+        .map_err(|e| FenrisError::database("document.invalid_stored", &[("error", &e)]))
+}
+
+// Moves documents saved by older versions from store.json into the database, once
+pub async fn import_legacy_json_store(app: AppHandle) -> Result<(), FenrisError> {
+    let store = app
+        .store(LEGACY_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    if store.is_empty() {
+        return Ok(());
+    }
+
+    let pool = app_sqlite_pool(&app).await?;
+    for (name, value) in store.entries() {
+        if !document_exists(&pool, &name).await? {
+            let content = to_string(&value)
+                .map_err(|e| FenrisError::internal("json.serialize_failed", &[("error", &e)]))?;
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| FenrisError::sqlx("database.transaction_failed", &e, &[]))?;
+            create_document(&mut tx, &name, None, &content).await?;
+            tx.commit()
+                .await
+                .map_err(|e| FenrisError::sqlx("document.import_failed", &e, &[("name", &name)]))?;
+        }
+        store.delete(&name);
+    }
+
+    store
+        .save()
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))
+}
+
+async fn get_document(pool: &Pool<Sqlite>, name: &str) -> Result<JsonDocument, FenrisError> {
+    let sql = format!(
+        "SELECT {} FROM json_documents d WHERE d.name = ?",
+        DOCUMENT_COLUMNS
+    );
+    let row: Option<DocumentRow> = sqlx::query_as(&sql)
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| FenrisError::sqlx("document.read_failed", &e, &[]))?;
+
+    row.map(document_from_row)
+        .ok_or_else(|| FenrisError::not_found("document.not_found", &[]))
+}
+
+async fn document_exists(pool: &Pool<Sqlite>, name: &str) -> Result<bool, FenrisError> {
+    let row: Option<(i64,)> = sqlx::query_as("SELECT id FROM json_documents WHERE name = ?")
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| FenrisError::sqlx("document.read_failed", &e, &[]))?;
+    Ok(row.is_some())
+}
+
+async fn find_document_id(
+    conn: &mut SqliteConnection,
+    name: &str,
+) -> Result<Option<i64>, FenrisError> {
+    let row: Option<(i64,)> = sqlx::query_as("SELECT id FROM json_documents WHERE name = ?")
+        .bind(name)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| FenrisError::sqlx("document.read_failed", &e, &[]))?;
+    Ok(row.map(|(id,)| id))
+}
+
+async fn current_content(conn: &mut SqliteConnection, id: i64) -> Result<String, FenrisError> {
+    let (content,): (String,) = sqlx::query_as(
+        "SELECT v.content FROM json_document_versions v
+        JOIN json_documents d ON d.id = v.document_id AND v.version = d.current_version
+        WHERE d.id = ?",
+    )
+    .bind(id)
+    .fetch_one(conn)
+    .await
+    .map_err(|e| FenrisError::sqlx("document.read_failed", &e, &[]))?;
+    Ok(content)
+}
+
+async fn create_document(
+    conn: &mut SqliteConnection,
+    name: &str,
+    source_url: Option<&str>,
+    content: &str,
+) -> Result<i64, FenrisError> {
+    let now = timestamp();
+    let result = sqlx::query(
+        "INSERT INTO json_documents (name, source_url, current_version, size_bytes, date_created, date_modified)
+        VALUES (?, ?, 1, ?, ?, ?)",
+    )
+    .bind(name)
+    .bind(source_url)
+    .bind(content.len() as i64)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| FenrisError::sqlx("document.create_failed", &e, &[]))?;
+
+    let id = result.last_insert_rowid();
+    insert_version(conn, id, 1, content, &now).await?;
+    Ok(id)
+}
+
+async fn add_version(
+    conn: &mut SqliteConnection,
+    id: i64,
+    content: &str,
+) -> Result<(), FenrisError> {
+    let now = timestamp();
+    let (latest,): (i64,) = sqlx::query_as(
+        "SELECT COALESCE(MAX(version), 0) FROM json_document_versions WHERE document_id = ?",
+    )
+    .bind(id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| FenrisError::sqlx("document.versions_read_failed", &e, &[]))?;
+    let version = latest + 1;
+
+    insert_version(conn, id, version, content, &now).await?;
+    sqlx::query(
+        "UPDATE json_documents SET current_version = ?, size_bytes = ?, date_modified = ? WHERE id = ?",
+    )
+    .bind(version)
+    .bind(content.len() as i64)
+    .bind(&now)
+    .bind(id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| FenrisError::sqlx("document.update_failed", &e, &[]))?;
+
+    sqlx::query("DELETE FROM json_document_versions WHERE document_id = ? AND version <= ?")
+        .bind(id)
+        .bind(version - MAX_VERSIONS)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| FenrisError::sqlx("document.prune_failed", &e, &[]))?;
+    Ok(())
+}
+
+async fn insert_version(
+    conn: &mut SqliteConnection,
+    id: i64,
+    version: i64,
+    content: &str,
+    now: &str,
+) -> Result<(), FenrisError> {
+    sqlx::query(
+        "INSERT INTO json_document_versions (document_id, version, content, size_bytes, date_created)
+        VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(id)
+    .bind(version)
+    .bind(content)
+    .bind(content.len() as i64)
+    .bind(now)
+    .execute(conn)
+    .await
+    .map_err(|e| FenrisError::sqlx("document.version_save_failed", &e, &[]))?;
+    Ok(())
+}
+
+// Tags are shared with notes, so unknown names are added to the tags table
+async fn replace_tags(
+    conn: &mut SqliteConnection,
+    id: i64,
+    tags: &[String],
+) -> Result<(), FenrisError> {
+    sqlx::query("DELETE FROM json_document_tags WHERE document_id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| FenrisError::sqlx("document.tags_failed", &e, &[]))?;
+
+    for tag in tags
+        .iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+    {
+        sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+            .bind(tag)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| FenrisError::sqlx("document.tags_failed", &e, &[]))?;
+        sqlx::query(
+            "INSERT OR IGNORE INTO json_document_tags (document_id, tag_id)
+            SELECT ?, id FROM tags WHERE name = ?",
+        )
+        .bind(id)
+        .bind(tag)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| FenrisError::sqlx("document.tags_failed", &e, &[]))?;
+    }
+    Ok(())
+}
+
+fn document_from_row(row: DocumentRow) -> JsonDocument {
+    let (
+        id,
+        name,
+        source_url,
+        size_bytes,
+        current_version,
+        version_count,
+        tags,
+        date_created,
+        date_modified,
+    ) = row;
+
+    let mut tags: Vec<String> = tags
+        .map(|tags| tags.split(TAG_SEPARATOR).map(String::from).collect())
+        .unwrap_or_default();
+    tags.sort();
+
+    JsonDocument {
+        id,
+        name,
+        source_url,
+        size_bytes,
+        current_version,
+        version_count,
+        tags,
+        date_created,
+        date_modified,
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+// Makes `%` and `_` match literally in a LIKE pattern that uses `ESCAPE '\'`
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn timestamp() -> String {
+    let now: DateTime<Utc> = Utc::now();
+    now.format("%Y-%m-%d %H:%M:%S").to_string()
+}
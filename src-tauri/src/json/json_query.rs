@@ -0,0 +1,136 @@
+use crate::error::FenrisError;
+use crate::json::json_formatter::{line_spans, FormatOptions};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_value, Value};
+use serde_json_path::JsonPath;
+use std::collections::HashSet;
+use std::time::Instant;
+use tauri::command;
+
+const MAX_QUERY_MATCHES: usize = 10000;
+
+#[derive(Debug, Clone, Copy, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryLanguage {
+    #[default]
+    JsonPath,
+    JmesPath,
+}
+
+#[derive(Serialize)]
+pub struct QueryMatch {
+    pub path: Option<String>,
+    pub pointer: Option<String>,
+    pub value: Value,
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct QueryResult {
+    pub count: usize,
+    pub matches: Vec<QueryMatch>,
+    /// Set when the expression selected more than MAX_QUERY_MATCHES nodes
+    pub truncated: bool,
+    pub duration_ms: u128,
+}
+
+#[command]
+pub fn query_json(
+    json_string: String,
+    expression: String,
+    language: Option<QueryLanguage>,
+    options: Option<FormatOptions>,
+) -> Result<QueryResult, FenrisError> {
+    let start_time = Instant::now();
+    let document: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+
+    if expression.trim().is_empty() {
+        return Ok(QueryResult {
+            count: 0,
+            matches: Vec::new(),
+            truncated: false,
+            duration_ms: 0,
+        });
+    }
+
+    let (matches, truncated) = match language.unwrap_or_default() {
+        QueryLanguage::JsonPath => {
+            query_json_path(&document, &expression, &options.unwrap_or_default())?
+        }
+        QueryLanguage::JmesPath => (query_jmes_path(&document, &expression)?, false),
+    };
+
+    Ok(QueryResult {
+        count: matches.len(),
+        matches,
+        truncated,
+        duration_ms: start_time.elapsed().as_millis(),
+    })
+}
+
+fn query_json_path(
+    document: &Value,
+    expression: &str,
+    options: &FormatOptions,
+) -> Result<(Vec<QueryMatch>, bool), FenrisError> {
+    let path = JsonPath::parse(expression)
+        .map_err(|e| FenrisError::validation("query.invalid_jsonpath", &[("error", &e)]))?;
+    let nodes = path.query_located(document);
+    let truncated = nodes.len() > MAX_QUERY_MATCHES;
+
+    let located: Vec<(String, String, &Value)> = nodes
+        .iter()
+        .take(MAX_QUERY_MATCHES)
+        .map(|node| {
+            (
+                node.location().to_string(),
+                node.location().to_json_pointer(),
+                node.node(),
+            )
+        })
+        .collect();
+
+    // Line ranges follow the layout of `format_json` so the editor can highlight them
+    let pointers: HashSet<String> = located.iter().map(|(_, p, _)| p.clone()).collect();
+    let spans = line_spans(document, &pointers, options);
+
+    let matches = located
+        .into_iter()
+        .map(|(path, pointer, value)| {
+            let span = spans.get(&pointer).copied();
+            QueryMatch {
+                path: Some(path),
+                pointer: Some(pointer),
+                value: value.clone(),
+                start_line: span.map(|(start, _)| start),
+                end_line: span.map(|(_, end)| end),
+            }
+        })
+        .collect();
+    Ok((matches, truncated))
+}
+
+// JMESPath projections build new values, so its results have no location in the document
+fn query_jmes_path(document: &Value, expression: &str) -> Result<Vec<QueryMatch>, FenrisError> {
+    let compiled = jmespath::compile(expression)
+        .map_err(|e| FenrisError::validation("query.invalid_jmespath", &[("error", &e)]))?;
+    let result = compiled
+        .search(document)
+        .map_err(|e| FenrisError::validation("query.jmespath_failed", &[("error", &e)]))?;
+    let value = to_value(&*result)
+        .map_err(|e| FenrisError::internal("json.serialize_failed", &[("error", &e)]))?;
+
+    if value.is_null() {
+        return Ok(Vec::new());
+    }
+
+    Ok(vec![QueryMatch {
+        path: None,
+        pointer: None,
+        value,
+        start_line: None,
+        end_line: None,
+    }])
+}
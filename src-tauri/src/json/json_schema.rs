@@ -0,0 +1,247 @@
+use crate::error::FenrisError;
+use serde::Serialize;
+use serde_json::{from_str, json, to_string_pretty, Map, Value};
+use tauri::{command, AppHandle};
+use tauri_plugin_store::StoreExt;
+
+const SCHEMA_STORE: &str = "schemas.json";
+const SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+#[derive(Serialize)]
+pub struct SchemaViolation {
+    pub instance_path: String,
+    pub schema_path: String,
+    pub keyword: String,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct SchemaValidationResult {
+    pub valid: bool,
+    pub violations: Vec<SchemaViolation>,
+}
+
+#[command]
+pub fn register_json_schema(
+    app: AppHandle,
+    name: String,
+    schema: String,
+) -> Result<(), FenrisError> {
+    if name.trim().is_empty() {
+        return Err(FenrisError::validation("schema.name_empty", &[]));
+    }
+
+    let parsed_schema: Value =
+        from_str(&schema).map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+    jsonschema::draft202012::new(&parsed_schema)
+        .map_err(|e| FenrisError::validation("schema.invalid", &[("error", &e)]))?;
+
+    let store = app
+        .store(SCHEMA_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    store.set(name, parsed_schema);
+    store
+        .save()
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))?;
+    Ok(())
+}
+
+#[command]
+pub fn list_json_schemas(app: AppHandle) -> Result<Vec<String>, FenrisError> {
+    let store = app
+        .store(SCHEMA_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    let mut names: Vec<String> = store.keys().into_iter().collect();
+    names.sort();
+    Ok(names)
+}
+
+#[command]
+pub fn get_json_schema(app: AppHandle, name: String) -> Result<String, FenrisError> {
+    let store = app
+        .store(SCHEMA_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    match store.get(&name) {
+        Some(schema) => to_string_pretty(&schema)
+            .map_err(|e| FenrisError::validation("json.format_failed", &[("error", &e)])),
+        None => Err(FenrisError::not_found(
+            "schema.not_found",
+            &[("name", &name)],
+        )),
+    }
+}
+
+#[command]
+pub fn delete_json_schema(app: AppHandle, name: String) -> Result<(), FenrisError> {
+    let store = app
+        .store(SCHEMA_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    store.delete(&name);
+    store
+        .save()
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))?;
+    Ok(())
+}
+
+#[command]
+pub fn validate_json_with_schema(
+    app: AppHandle,
+    schema_name: String,
+    json_string: String,
+) -> Result<SchemaValidationResult, FenrisError> {
+    let store = app
+        .store(SCHEMA_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    let schema = store
+        .get(&schema_name)
+        .ok_or_else(|| FenrisError::not_found("schema.not_found", &[("name", &schema_name)]))?;
+
+    let document: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+
+    validate_against_schema(&schema, &document)
+}
+
+pub fn validate_against_schema(
+    schema: &Value,
+    document: &Value,
+) -> Result<SchemaValidationResult, FenrisError> {
+    let validator = jsonschema::draft202012::new(schema)
+        .map_err(|e| FenrisError::validation("schema.invalid", &[("error", &e)]))?;
+
+    let violations: Vec<SchemaViolation> = validator
+        .iter_errors(document)
+        .map(|error| {
+            let schema_path = error.schema_path.to_string();
+            SchemaViolation {
+                instance_path: error.instance_path.to_string(),
+                keyword: schema_path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                schema_path,
+                message: error.to_string(),
+            }
+        })
+        .collect();
+
+    Ok(SchemaValidationResult {
+        valid: violations.is_empty(),
+        violations,
+    })
+}
+
+#[command]
+pub fn infer_json_schema(json_string: String) -> Result<String, FenrisError> {
+    let document: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+
+    let mut schema = infer_schema(&document);
+    if let Value::Object(map) = &mut schema {
+        map.shift_insert(
+            0,
+            String::from("$schema"),
+            Value::String(SCHEMA_DRAFT.to_string()),
+        );
+    }
+
+    to_string_pretty(&schema)
+        .map_err(|e| FenrisError::validation("json.format_failed", &[("error", &e)]))
+}
+
+pub fn infer_schema(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({ "type": "null" }),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Number(n) if n.is_i64() || n.is_u64() => json!({ "type": "integer" }),
+        Value::Number(_) => json!({ "type": "number" }),
+        Value::String(_) => json!({ "type": "string" }),
+        Value::Array(items) => {
+            let mut schema = Map::new();
+            schema.insert(String::from("type"), Value::String(String::from("array")));
+            if let Some(merged) = items.iter().map(infer_schema).reduce(merge_schemas) {
+                schema.insert(String::from("items"), merged);
+            }
+            Value::Object(schema)
+        }
+        Value::Object(map) => {
+            let properties: Map<String, Value> = map
+                .iter()
+                .map(|(key, val)| (key.clone(), infer_schema(val)))
+                .collect();
+            let required: Vec<Value> = map.keys().cloned().map(Value::String).collect();
+
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required
+            })
+        }
+    }
+}
+
+// Merges two inferred schemas so array items describe every element they saw
+fn merge_schemas(left: Value, right: Value) -> Value {
+    if left == right {
+        return left;
+    }
+
+    let left_type = left["type"].as_str().unwrap_or_default();
+    let right_type = right["type"].as_str().unwrap_or_default();
+
+    match (left_type, right_type) {
+        ("object", "object") => {
+            let mut properties = left["properties"].as_object().cloned().unwrap_or_default();
+            for (key, schema) in right["properties"].as_object().cloned().unwrap_or_default() {
+                match properties.get_mut(&key) {
+                    Some(existing) => *existing = merge_schemas(existing.take(), schema),
+                    None => {
+                        properties.insert(key, schema);
+                    }
+                }
+            }
+
+            // Only keys present in every sample stay required
+            let right_required = right["required"].as_array().cloned().unwrap_or_default();
+            let required: Vec<Value> = left["required"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|key| right_required.contains(key))
+                .collect();
+
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required
+            })
+        }
+        ("array", "array") => match (left.get("items"), right.get("items")) {
+            (Some(l), Some(r)) => {
+                json!({ "type": "array", "items": merge_schemas(l.clone(), r.clone()) })
+            }
+            (Some(items), None) | (None, Some(items)) => {
+                json!({ "type": "array", "items": items.clone() })
+            }
+            (None, None) => json!({ "type": "array" }),
+        },
+        ("integer", "number") | ("number", "integer") => json!({ "type": "number" }),
+        _ => {
+            let mut variants: Vec<Value> = Vec::new();
+            for schema in [left, right] {
+                let candidates = match schema.get("anyOf").and_then(|v| v.as_array()) {
+                    Some(nested) => nested.clone(),
+                    None => vec![schema],
+                };
+                for candidate in candidates {
+                    if !variants.contains(&candidate) {
+                        variants.push(candidate);
+                    }
+                }
+            }
+            json!({ "anyOf": variants })
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use crate::error::FenrisError;
+use crate::json::json_diff::escape_pointer_segment;
+use serde::Serialize;
+use serde_json::{from_str, Value};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::time::Instant;
+use tauri::command;
+
+const LARGEST_ARRAYS: usize = 10;
+const MAX_KEY_FREQUENCY: usize = 200;
+const MAX_DISTINCT_VALUES: usize = 10000;
+
+#[derive(Serialize, Default)]
+pub struct NodeCounts {
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    pub numbers: usize,
+    pub booleans: usize,
+    pub nulls: usize,
+}
+
+#[derive(Serialize)]
+pub struct ArraySize {
+    pub pointer: String,
+    pub length: usize,
+}
+
+#[derive(Serialize)]
+pub struct KeyCount {
+    pub key: String,
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct PathStats {
+    /// Array indices are replaced by `*`, e.g. "/items/*/name", and a key named `*` is written `~*`
+    pub path: String,
+    pub types: Vec<String>,
+    pub inferred_type: String,
+    pub occurrences: usize,
+    pub missing: usize,
+    pub sometimes_missing: bool,
+    pub null_count: usize,
+    pub distinct_values: Option<usize>,
+    pub distinct_capped: bool,
+}
+
+#[derive(Serialize)]
+pub struct JsonStats {
+    pub max_depth: usize,
+    pub total_nodes: usize,
+    pub counts: NodeCounts,
+    pub empty_strings: usize,
+    pub empty_arrays: usize,
+    pub empty_objects: usize,
+    pub largest_arrays: Vec<ArraySize>,
+    pub key_frequency: Vec<KeyCount>,
+    pub paths: Vec<PathStats>,
+    pub duration_ms: u128,
+}
+
+#[command]
+pub fn analyze_json(json_string: String) -> Result<JsonStats, FenrisError> {
+    let start_time = Instant::now();
+    let document: Value = from_str(&json_string)
+        .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+
+    let mut analyzer = Analyzer::default();
+    let mut pointer = String::new();
+    analyzer.visit(&document, "", &mut pointer, 1);
+
+    Ok(analyzer.finish(start_time.elapsed().as_millis()))
+}
+
+#[derive(Default)]
+struct PathAccumulator {
+    types: BTreeSet<&'static str>,
+    occurrences: usize,
+    objects_seen: usize,
+    null_count: usize,
+    distinct: HashSet<String>,
+    has_primitives: bool,
+    distinct_capped: bool,
+}
+
+#[derive(Default)]
+struct Analyzer {
+    max_depth: usize,
+    total_nodes: usize,
+    counts: NodeCounts,
+    empty_strings: usize,
+    empty_arrays: usize,
+    empty_objects: usize,
+    largest_arrays: BinaryHeap<Reverse<(usize, String)>>,
+    key_frequency: HashMap<String, usize>,
+    paths: BTreeMap<String, PathAccumulator>,
+}
+
+impl Analyzer {
+    // `path` is the generalized path used for shape statistics, `pointer` the concrete location
+    fn visit(&mut self, value: &Value, path: &str, pointer: &mut String, depth: usize) {
+        self.total_nodes += 1;
+        self.max_depth = self.max_depth.max(depth);
+
+        let entry = self.paths.entry(path.to_string()).or_default();
+        entry.occurrences += 1;
+        entry.types.insert(type_name(value));
+
+        match value {
+            Value::Object(map) => {
+                self.counts.objects += 1;
+                entry.objects_seen += 1;
+                if map.is_empty() {
+                    self.empty_objects += 1;
+                }
+
+                for (key, child) in map {
+                    *self.key_frequency.entry(key.clone()).or_insert(0) += 1;
+
+                    let segment = escape_pointer_segment(key);
+                    // Escaped keys only use `~0` and `~1`, so `~*` cannot clash with another key
+                    let path_segment = if segment == "*" { "~*" } else { &segment };
+                    let child_path = format!("{}/{}", path, path_segment);
+                    let parent_len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&segment);
+                    self.visit(child, &child_path, pointer, depth + 1);
+                    pointer.truncate(parent_len);
+                }
+            }
+            Value::Array(items) => {
+                self.counts.arrays += 1;
+                if items.is_empty() {
+                    self.empty_arrays += 1;
+                }
+
+                self.largest_arrays
+                    .push(Reverse((items.len(), pointer.clone())));
+                if self.largest_arrays.len() > LARGEST_ARRAYS {
+                    self.largest_arrays.pop();
+                }
+
+                let child_path = format!("{}/*", path);
+                for (index, child) in items.iter().enumerate() {
+                    let parent_len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&index.to_string());
+                    self.visit(child, &child_path, pointer, depth + 1);
+                    pointer.truncate(parent_len);
+                }
+            }
+            primitive => {
+                match primitive {
+                    Value::String(s) => {
+                        self.counts.strings += 1;
+                        if s.is_empty() {
+                            self.empty_strings += 1;
+                        }
+                    }
+                    Value::Number(_) => self.counts.numbers += 1,
+                    Value::Bool(_) => self.counts.booleans += 1,
+                    _ => {
+                        self.counts.nulls += 1;
+                        entry.null_count += 1;
+                    }
+                }
+
+                entry.has_primitives = true;
+                if entry.distinct.len() < MAX_DISTINCT_VALUES {
+                    entry.distinct.insert(primitive.to_string());
+                } else if !entry.distinct.contains(&primitive.to_string()) {
+                    entry.distinct_capped = true;
+                }
+            }
+        }
+    }
+
+    fn finish(self, duration_ms: u128) -> JsonStats {
+        let mut largest_arrays: Vec<ArraySize> = self
+            .largest_arrays
+            .into_iter()
+            .map(|Reverse((length, pointer))| ArraySize { pointer, length })
+            .collect();
+        largest_arrays.sort_by(|a, b| b.length.cmp(&a.length).then(a.pointer.cmp(&b.pointer)));
+
+        let mut key_frequency: Vec<KeyCount> = self
+            .key_frequency
+            .into_iter()
+            .map(|(key, count)| KeyCount { key, count })
+            .collect();
+        key_frequency.sort_by(|a, b| b.count.cmp(&a.count).then(a.key.cmp(&b.key)));
+        key_frequency.truncate(MAX_KEY_FREQUENCY);
+
+        // A key is missing wherever its parent object was seen without it
+        let paths = self
+            .paths
+            .iter()
+            .map(|(path, acc)| {
+                let parent_objects = parent_path(path)
+                    .and_then(|parent| self.paths.get(parent))
+                    .map(|parent| parent.objects_seen)
+                    .unwrap_or(0);
+                let missing = parent_objects.saturating_sub(acc.occurrences);
+
+                PathStats {
+                    path: path.clone(),
+                    types: acc.types.iter().map(|t| t.to_string()).collect(),
+                    inferred_type: infer_type(&acc.types),
+                    occurrences: acc.occurrences,
+                    missing,
+                    sometimes_missing: missing > 0,
+                    null_count: acc.null_count,
+                    distinct_values: acc.has_primitives.then_some(acc.distinct.len()),
+                    distinct_capped: acc.distinct_capped,
+                }
+            })
+            .collect();
+
+        JsonStats {
+            max_depth: self.max_depth,
+            total_nodes: self.total_nodes,
+            counts: self.counts,
+            empty_strings: self.empty_strings,
+            empty_arrays: self.empty_arrays,
+            empty_objects: self.empty_objects,
+            largest_arrays,
+            key_frequency,
+            paths,
+            duration_ms,
+        }
+    }
+}
+
+// Only object members can be missing, array elements have no fixed parent shape
+fn parent_path(path: &str) -> Option<&str> {
+    let (parent, last) = path.rsplit_once('/')?;
+    (last != "*").then_some(parent)
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+fn infer_type(types: &BTreeSet<&'static str>) -> String {
+    let mut non_null: Vec<&str> = types.iter().copied().filter(|t| *t != "null").collect();
+    if non_null.contains(&"integer") && non_null.contains(&"number") {
+        non_null.retain(|t| *t != "integer");
+    }
+
+    let base = match non_null.as_slice() {
+        [] => return String::from("null"),
+        [single] => single.to_string(),
+        _ => String::from("mixed"),
+    };
+
+    if types.contains("null") {
+        format!("{} | null", base)
+    } else {
+        base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path_stats(json: &str) -> Vec<(String, usize)> {
+        analyze_json(json.to_string())
+            .unwrap()
+            .paths
+            .into_iter()
+            .map(|stats| (stats.path, stats.occurrences))
+            .collect()
+    }
+
+    #[test]
+    fn star_keys_do_not_merge_with_array_elements() {
+        assert_eq!(
+            path_stats(r#"[{"a": {"*": "x"}}, {"a": [1, 2]}]"#),
+            [
+                (String::from(""), 1),
+                (String::from("/*"), 2),
+                (String::from("/*/a"), 2),
+                (String::from("/*/a/*"), 2),
+                (String::from("/*/a/~*"), 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1321 @@
+use crate::error::{lock, FenrisError};
+use crate::json::json_diff::escape_pointer_segment;
+use crate::json::json_formatter::{format_value, FormatOptions};
+use serde::Serialize;
+use serde_json::{from_slice, Number, Value};
+use std::collections::HashMap;
+use std::fs::{metadata, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+use tauri::async_runtime::spawn_blocking;
+use tauri::{command, State};
+
+const READ_BUFFER_SIZE: usize = 256 * 1024;
+// Containers smaller than this are cheap to rescan, so only larger ones are kept in the index
+const INDEX_MIN_BYTES: u64 = 4096;
+const CHECKPOINT_EVERY: u64 = 1000;
+const MAX_PAGE_SIZE: u64 = 1000;
+const DEFAULT_PAGE_SIZE: u64 = 100;
+const MAX_NODE_BYTES: u64 = 16 * 1024 * 1024;
+const PREVIEW_CHARS: usize = 120;
+const DEFAULT_SEARCH_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Boolean,
+    Null,
+}
+
+struct ContainerEntry {
+    end: u64,
+    children: u64,
+    // (child index, byte offset) pairs so deep pages do not rescan from the start
+    checkpoints: Vec<(u64, u64)>,
+}
+
+pub struct JsonFileIndex {
+    path: String,
+    size: u64,
+    modified: Option<SystemTime>,
+    root_offset: u64,
+    root_end: u64,
+    root_kind: NodeKind,
+    containers: HashMap<u64, ContainerEntry>,
+}
+
+// Indexed files, keyed by path
+#[derive(Default)]
+pub struct JsonIndexManager(pub Mutex<HashMap<String, Arc<JsonFileIndex>>>);
+
+#[derive(Serialize)]
+pub struct JsonIndexSummary {
+    pub path: String,
+    pub size_bytes: u64,
+    pub root_kind: NodeKind,
+    pub root_children: Option<u64>,
+    pub indexed_containers: usize,
+    pub duration_ms: u128,
+}
+
+#[derive(Serialize)]
+pub struct JsonNodeSummary {
+    pub key: Option<String>,
+    pub index: u64,
+    pub pointer: String,
+    pub kind: NodeKind,
+    pub child_count: Option<u64>,
+    pub byte_len: u64,
+    pub preview: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct JsonChildrenPage {
+    pub pointer: String,
+    pub kind: NodeKind,
+    pub total: u64,
+    pub offset: u64,
+    pub children: Vec<JsonNodeSummary>,
+}
+
+#[derive(Serialize)]
+pub struct JsonSearchHit {
+    pub pointer: String,
+    pub in_key: bool,
+    pub preview: String,
+}
+
+#[derive(Serialize)]
+pub struct JsonSearchResult {
+    pub hits: Vec<JsonSearchHit>,
+    pub truncated: bool,
+    pub duration_ms: u128,
+}
+
+#[command]
+pub async fn index_json_file(
+    manager: State<'_, JsonIndexManager>,
+    path: String,
+) -> Result<JsonIndexSummary, FenrisError> {
+    let start_time = Instant::now();
+    let build_path = path.clone();
+    let index = spawn_blocking(move || JsonFileIndex::build(&build_path))
+        .await
+        .map_err(|e| FenrisError::internal("task.spawn_failed", &[("error", &e)]))??;
+
+    let summary = JsonIndexSummary {
+        path: path.clone(),
+        size_bytes: index.size,
+        root_kind: index.root_kind,
+        root_children: index.containers.get(&index.root_offset).map(|e| e.children),
+        indexed_containers: index.containers.len(),
+        duration_ms: start_time.elapsed().as_millis(),
+    };
+
+    lock(&manager.0)?.insert(path, Arc::new(index));
+    Ok(summary)
+}
+
+#[command]
+pub fn close_json_index(manager: State<JsonIndexManager>, path: String) -> Result<(), FenrisError> {
+    lock(&manager.0)?.remove(&path);
+    Ok(())
+}
+
+#[command]
+pub async fn get_json_children(
+    manager: State<'_, JsonIndexManager>,
+    path: String,
+    pointer: String,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<JsonChildrenPage, FenrisError> {
+    let index = indexed_file(&manager, &path)?;
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    spawn_blocking(move || index.children_page(&pointer, offset, limit))
+        .await
+        .map_err(|e| FenrisError::internal("task.spawn_failed", &[("error", &e)]))?
+}
+
+#[command]
+pub async fn get_json_node(
+    manager: State<'_, JsonIndexManager>,
+    path: String,
+    pointer: String,
+    options: Option<FormatOptions>,
+) -> Result<String, FenrisError> {
+    let index = indexed_file(&manager, &path)?;
+
+    spawn_blocking(move || {
+        let node = index.locate(&pointer)?;
+        let byte_len = node.end - node.offset;
+        if byte_len > MAX_NODE_BYTES {
+            let size = format!("{:.1}", byte_len as f64 / (1024.0 * 1024.0));
+            return Err(FenrisError::validation(
+                "stream.node_too_large",
+                &[("size", &size)],
+            ));
+        }
+
+        let bytes = index.open_reader()?.read_range(node.offset, node.end)?;
+        let value: Value = from_slice(&bytes)
+            .map_err(|e| FenrisError::validation("json.invalid", &[("error", &e)]))?;
+        Ok(format_value(&value, &options.unwrap_or_default()))
+    })
+    .await
+    .map_err(|e| FenrisError::internal("task.spawn_failed", &[("error", &e)]))?
+}
+
+#[command]
+pub async fn search_json_file(
+    manager: State<'_, JsonIndexManager>,
+    path: String,
+    query: String,
+    case_sensitive: Option<bool>,
+    limit: Option<usize>,
+) -> Result<JsonSearchResult, FenrisError> {
+    let index = indexed_file(&manager, &path)?;
+    let start_time = Instant::now();
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).max(1);
+
+    let (hits, truncated) =
+        spawn_blocking(move || index.search(&query, case_sensitive.unwrap_or(false), limit))
+            .await
+            .map_err(|e| FenrisError::internal("task.spawn_failed", &[("error", &e)]))??;
+
+    Ok(JsonSearchResult {
+        hits,
+        truncated,
+        duration_ms: start_time.elapsed().as_millis(),
+    })
+}
+
+fn indexed_file(manager: &JsonIndexManager, path: &str) -> Result<Arc<JsonFileIndex>, FenrisError> {
+    let index = lock(&manager.0)?
+        .get(path)
+        .cloned()
+        .ok_or_else(|| FenrisError::not_found("stream.not_indexed", &[("path", &path)]))?;
+
+    let modified = metadata(path).and_then(|m| m.modified()).ok();
+    if modified != index.modified {
+        return Err(FenrisError::conflict(
+            "stream.index_outdated",
+            &[("path", &path)],
+        ));
+    }
+    Ok(index)
+}
+
+struct NodeLocation {
+    offset: u64,
+    end: u64,
+    kind: NodeKind,
+}
+
+struct ChildInfo {
+    index: u64,
+    key: Option<String>,
+    kind: NodeKind,
+    offset: u64,
+    end: u64,
+    children: Option<u64>,
+    preview: Option<String>,
+}
+
+impl JsonFileIndex {
+    fn build(path: &str) -> Result<Self, FenrisError> {
+        struct OpenContainer {
+            offset: u64,
+            kind: NodeKind,
+            children: u64,
+            checkpoints: Vec<(u64, u64)>,
+        }
+
+        let file_metadata =
+            metadata(path).map_err(|e| FenrisError::io("file.read_failed", &[("error", &e)]))?;
+        let mut tokenizer = Tokenizer::new(ByteReader::open(path)?, false);
+        let mut open: Vec<OpenContainer> = Vec::new();
+        let mut containers = HashMap::new();
+        let mut root: Option<(u64, u64, NodeKind)> = None;
+
+        while let Some(event) = tokenizer.next_event()? {
+            let child_offset = match &event {
+                Event::Key { offset, .. } => Some(*offset),
+                Event::Begin { offset, .. } | Event::Value { offset, .. } => open
+                    .last()
+                    .filter(|parent| parent.kind == NodeKind::Array)
+                    .map(|_| *offset),
+                Event::End { .. } => None,
+            };
+            if let (Some(child_offset), Some(parent)) = (child_offset, open.last_mut()) {
+                if parent.children > 0 && parent.children % CHECKPOINT_EVERY == 0 {
+                    parent.checkpoints.push((parent.children, child_offset));
+                }
+                parent.children += 1;
+            }
+
+            match event {
+                Event::Begin { kind, offset } => open.push(OpenContainer {
+                    offset,
+                    kind,
+                    children: 0,
+                    checkpoints: Vec::new(),
+                }),
+                Event::End { end } => {
+                    let Some(container) = open.pop() else {
+                        continue;
+                    };
+                    let is_root = open.is_empty();
+                    if is_root || end - container.offset >= INDEX_MIN_BYTES {
+                        containers.insert(
+                            container.offset,
+                            ContainerEntry {
+                                end,
+                                children: container.children,
+                                checkpoints: container.checkpoints,
+                            },
+                        );
+                    }
+                    if is_root {
+                        root = Some((container.offset, end, container.kind));
+                    }
+                }
+                Event::Value {
+                    kind, offset, end, ..
+                } if open.is_empty() => root = Some((offset, end, kind)),
+                _ => {}
+            }
+        }
+
+        let (root_offset, root_end, root_kind) =
+            root.ok_or_else(|| FenrisError::validation("stream.empty_file", &[]))?;
+
+        Ok(Self {
+            path: path.to_string(),
+            size: file_metadata.len(),
+            modified: file_metadata.modified().ok(),
+            root_offset,
+            root_end,
+            root_kind,
+            containers,
+        })
+    }
+
+    fn open_reader(&self) -> Result<ByteReader, FenrisError> {
+        ByteReader::open(&self.path)
+    }
+
+    fn locate(&self, pointer: &str) -> Result<NodeLocation, FenrisError> {
+        let mut node = NodeLocation {
+            offset: self.root_offset,
+            end: self.root_end,
+            kind: self.root_kind,
+        };
+
+        for segment in parse_pointer(pointer)? {
+            let target_index = match node.kind {
+                NodeKind::Array => Some(segment.parse::<u64>().map_err(|_| {
+                    FenrisError::validation("stream.invalid_index", &[("index", &segment)])
+                })?),
+                NodeKind::Object => None,
+                _ => {
+                    return Err(FenrisError::not_found(
+                        "json.path_not_found",
+                        &[("path", &pointer)],
+                    ))
+                }
+            };
+
+            let mut found = None;
+            self.scan_children(&node, target_index.unwrap_or(0), |child| {
+                let matches = match target_index {
+                    Some(index) => child.index == index,
+                    None => child.key.as_deref() == Some(segment.as_str()),
+                };
+                if matches {
+                    found = Some(NodeLocation {
+                        offset: child.offset,
+                        end: child.end,
+                        kind: child.kind,
+                    });
+                }
+                !matches
+            })?;
+
+            node = found.ok_or_else(|| {
+                FenrisError::not_found("json.path_not_found", &[("path", &pointer)])
+            })?;
+        }
+
+        Ok(node)
+    }
+
+    fn child_count(&self, node: &NodeLocation) -> Result<u64, FenrisError> {
+        if let Some(entry) = self.containers.get(&node.offset) {
+            return Ok(entry.children);
+        }
+        let mut count = 0;
+        self.scan_children(node, 0, |_| {
+            count += 1;
+            true
+        })?;
+        Ok(count)
+    }
+
+    fn children_page(
+        &self,
+        pointer: &str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<JsonChildrenPage, FenrisError> {
+        let node = self.locate(pointer)?;
+        if !matches!(node.kind, NodeKind::Object | NodeKind::Array) {
+            return Err(FenrisError::validation(
+                "stream.no_children",
+                &[("path", &pointer)],
+            ));
+        }
+
+        let total = self.child_count(&node)?;
+        let mut children = Vec::new();
+        self.scan_children(&node, offset, |child| {
+            let segment = match &child.key {
+                Some(key) => escape_pointer_segment(key),
+                None => child.index.to_string(),
+            };
+            children.push(JsonNodeSummary {
+                pointer: format!("{}/{}", pointer, segment),
+                key: child.key,
+                index: child.index,
+                kind: child.kind,
+                child_count: child.children,
+                byte_len: child.end - child.offset,
+                preview: child.preview,
+            });
+            (children.len() as u64) < limit
+        })?;
+
+        Ok(JsonChildrenPage {
+            pointer: pointer.to_string(),
+            kind: node.kind,
+            total,
+            offset,
+            children,
+        })
+    }
+
+    // Visits the direct children of a container starting at child index `from`,
+    // until the container ends or `visit` returns false
+    fn scan_children(
+        &self,
+        node: &NodeLocation,
+        from: u64,
+        mut visit: impl FnMut(ChildInfo) -> bool,
+    ) -> Result<(), FenrisError> {
+        let mut tokenizer = Tokenizer::new(self.open_reader()?, true);
+        let checkpoint = self
+            .containers
+            .get(&node.offset)
+            .and_then(|entry| entry.checkpoints.iter().rev().find(|(i, _)| *i <= from))
+            .copied();
+
+        let mut index = match checkpoint {
+            Some((child_index, child_offset)) => {
+                tokenizer.resume_inside(node.kind, child_offset)?;
+                child_index
+            }
+            None => {
+                tokenizer.reader.seek(node.offset)?;
+                tokenizer.next_event()?;
+                0
+            }
+        };
+
+        let mut key: Option<String> = None;
+        let mut current: Option<ChildInfo> = None;
+
+        while let Some(event) = tokenizer.next_event()? {
+            let depth = tokenizer.stack.len();
+            let mut completed = None;
+
+            match event {
+                Event::End { .. } if depth == 0 => break,
+                Event::End { end } if depth == 1 => {
+                    completed = current.take().map(|child| ChildInfo { end, ..child });
+                }
+                Event::Key { key: name, .. } if depth == 1 => key = Some(name),
+                Event::Key { .. } if depth == 2 => {
+                    if let Some(child) = current.as_mut() {
+                        child.children = child.children.map(|c| c + 1);
+                    }
+                }
+                Event::Value {
+                    kind,
+                    text,
+                    offset,
+                    end,
+                } if depth == 1 => {
+                    completed = Some(ChildInfo {
+                        index,
+                        key: key.take(),
+                        kind,
+                        offset,
+                        end,
+                        children: None,
+                        preview: text.map(|t| preview_text(kind, &t)),
+                    });
+                }
+                Event::Begin { kind, offset } if depth == 2 => {
+                    let child = ChildInfo {
+                        index,
+                        key: key.take(),
+                        kind,
+                        offset,
+                        end: offset,
+                        children: Some(0),
+                        preview: None,
+                    };
+                    match self.containers.get(&offset) {
+                        Some(entry) => {
+                            tokenizer.skip_container(entry.end)?;
+                            completed = Some(ChildInfo {
+                                end: entry.end,
+                                children: Some(entry.children),
+                                ..child
+                            });
+                        }
+                        None => current = Some(child),
+                    }
+                }
+                // Elements of a child array, counted while walking past it
+                Event::Value { .. } if depth == 2 => count_array_element(&mut current),
+                Event::Begin { .. } if depth == 3 => count_array_element(&mut current),
+                _ => {}
+            }
+
+            if let Some(child) = completed {
+                index += 1;
+                if child.index >= from && !visit(child) {
+                    return Ok(());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn search(
+        &self,
+        query: &str,
+        case_sensitive: bool,
+        limit: usize,
+    ) -> Result<(Vec<JsonSearchHit>, bool), FenrisError> {
+        struct PathFrame {
+            kind: NodeKind,
+            segment: Option<String>,
+            next_index: u64,
+            key: Option<String>,
+        }
+
+        if query.is_empty() {
+            return Ok((Vec::new(), false));
+        }
+
+        let needle = if case_sensitive {
+            query.to_string()
+        } else {
+            query.to_lowercase()
+        };
+        let matches = |text: &str| {
+            if case_sensitive {
+                text.contains(&needle)
+            } else {
+                text.to_lowercase().contains(&needle)
+            }
+        };
+
+        let mut tokenizer = Tokenizer::new(self.open_reader()?, true);
+        let mut path: Vec<PathFrame> = Vec::new();
+        let mut hits = Vec::new();
+
+        let pointer_of = |path: &[PathFrame], last: Option<&str>| {
+            let mut pointer = String::new();
+            for segment in path
+                .iter()
+                .filter_map(|frame| frame.segment.as_deref())
+                .chain(last)
+            {
+                pointer.push('/');
+                pointer.push_str(&escape_pointer_segment(segment));
+            }
+            pointer
+        };
+
+        // Returns the segment of the next child of the innermost container
+        let next_segment = |path: &mut [PathFrame]| -> Option<String> {
+            let frame = path.last_mut()?;
+            match frame.kind {
+                NodeKind::Array => {
+                    frame.next_index += 1;
+                    Some((frame.next_index - 1).to_string())
+                }
+                _ => frame.key.take(),
+            }
+        };
+
+        while let Some(event) = tokenizer.next_event()? {
+            match event {
+                Event::Key { key, .. } => {
+                    if matches(&key) {
+                        hits.push(JsonSearchHit {
+                            pointer: pointer_of(&path, Some(&key)),
+                            in_key: true,
+                            preview: truncate_chars(&key),
+                        });
+                    }
+                    if let Some(frame) = path.last_mut() {
+                        frame.key = Some(key);
+                    }
+                }
+                Event::Value { kind, text, .. } => {
+                    let segment = next_segment(&mut path);
+                    if let Some(text) = text.filter(|t| matches(t)) {
+                        hits.push(JsonSearchHit {
+                            pointer: pointer_of(&path, segment.as_deref()),
+                            in_key: false,
+                            preview: preview_text(kind, &text),
+                        });
+                    }
+                }
+                Event::Begin { kind, .. } => {
+                    let segment = next_segment(&mut path);
+                    path.push(PathFrame {
+                        kind,
+                        segment,
+                        next_index: 0,
+                        key: None,
+                    });
+                }
+                Event::End { .. } => {
+                    path.pop();
+                }
+            }
+
+            // One hit past the limit proves there are more, stopping at the limit would not
+            if hits.len() > limit {
+                hits.truncate(limit);
+                return Ok((hits, true));
+            }
+        }
+
+        Ok((hits, false))
+    }
+}
+
+fn count_array_element(current: &mut Option<ChildInfo>) {
+    if let Some(child) = current.as_mut().filter(|c| c.kind == NodeKind::Array) {
+        child.children = child.children.map(|c| c + 1);
+    }
+}
+
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, FenrisError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(FenrisError::validation(
+            "stream.invalid_pointer",
+            &[("path", &pointer)],
+        ));
+    }
+    Ok(pointer[1..]
+        .split('/')
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn truncate_chars(text: &str) -> String {
+    match text.char_indices().nth(PREVIEW_CHARS) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+fn preview_text(kind: NodeKind, text: &str) -> String {
+    match kind {
+        NodeKind::String => format!("\"{}\"", truncate_chars(text)),
+        _ => truncate_chars(text),
+    }
+}
+
+struct ByteReader {
+    file: File,
+    buffer: Vec<u8>,
+    start: usize,
+    end: usize,
+    pos: u64,
+}
+
+impl ByteReader {
+    fn open(path: &str) -> Result<Self, FenrisError> {
+        let file =
+            File::open(path).map_err(|e| FenrisError::io("file.open_failed", &[("error", &e)]))?;
+        Ok(Self {
+            file,
+            buffer: vec![0; READ_BUFFER_SIZE],
+            start: 0,
+            end: 0,
+            pos: 0,
+        })
+    }
+
+    fn seek(&mut self, pos: u64) -> Result<(), FenrisError> {
+        self.file
+            .seek(SeekFrom::Start(pos))
+            .map_err(|e| FenrisError::io("file.read_failed", &[("error", &e)]))?;
+        self.start = 0;
+        self.end = 0;
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, FenrisError> {
+        if self.start == self.end {
+            self.end = self
+                .file
+                .read(&mut self.buffer)
+                .map_err(|e| FenrisError::io("file.read_failed", &[("error", &e)]))?;
+            self.start = 0;
+            if self.end == 0 {
+                return Ok(None);
+            }
+        }
+        Ok(Some(self.buffer[self.start]))
+    }
+
+    fn bump(&mut self) {
+        self.start += 1;
+        self.pos += 1;
+    }
+
+    fn read_range(&mut self, start: u64, end: u64) -> Result<Vec<u8>, FenrisError> {
+        self.seek(start)?;
+        let mut bytes = vec![0; (end - start) as usize];
+        self.file
+            .read_exact(&mut bytes)
+            .map_err(|e| FenrisError::io("file.read_failed", &[("error", &e)]))?;
+        Ok(bytes)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    FirstOrClose,
+    Key,
+    Colon,
+    Value,
+    CommaOrClose,
+}
+
+struct Frame {
+    kind: NodeKind,
+    expect: Expect,
+}
+
+enum Event {
+    Begin {
+        kind: NodeKind,
+        offset: u64,
+    },
+    End {
+        end: u64,
+    },
+    Key {
+        key: String,
+        offset: u64,
+    },
+    Value {
+        kind: NodeKind,
+        text: Option<String>,
+        offset: u64,
+        end: u64,
+    },
+}
+
+// Pull parser over the file that never holds more than one token in memory
+struct Tokenizer {
+    reader: ByteReader,
+    stack: Vec<Frame>,
+    root_done: bool,
+    capture_values: bool,
+}
+
+impl Tokenizer {
+    fn new(reader: ByteReader, capture_values: bool) -> Self {
+        Self {
+            reader,
+            stack: Vec::new(),
+            root_done: false,
+            capture_values,
+        }
+    }
+
+    // Continues parsing inside a container at the start of one of its children
+    fn resume_inside(&mut self, kind: NodeKind, offset: u64) -> Result<(), FenrisError> {
+        self.reader.seek(offset)?;
+        self.root_done = true;
+        self.stack = vec![Frame {
+            kind,
+            expect: if kind == NodeKind::Object {
+                Expect::Key
+            } else {
+                Expect::Value
+            },
+        }];
+        Ok(())
+    }
+
+    // Jumps past the container that was just opened
+    fn skip_container(&mut self, end: u64) -> Result<(), FenrisError> {
+        self.stack.pop();
+        self.reader.seek(end)
+    }
+
+    fn next_event(&mut self) -> Result<Option<Event>, FenrisError> {
+        loop {
+            self.skip_whitespace()?;
+            let offset = self.reader.pos;
+            let byte = self.reader.peek()?;
+
+            let Some(frame) = self.stack.last_mut() else {
+                return match byte {
+                    None if self.root_done => Ok(None),
+                    None => Err(FenrisError::validation("stream.unexpected_end", &[])),
+                    Some(b) if self.root_done => Err(unexpected(b, offset)),
+                    Some(_) => self.value_start().map(Some),
+                };
+            };
+            let Some(byte) = byte else {
+                return Err(FenrisError::validation("stream.unexpected_end", &[]));
+            };
+
+            match (frame.kind, frame.expect, byte) {
+                (NodeKind::Object, Expect::FirstOrClose | Expect::CommaOrClose, b'}')
+                | (NodeKind::Array, Expect::FirstOrClose | Expect::CommaOrClose, b']') => {
+                    self.reader.bump();
+                    self.stack.pop();
+                    return Ok(Some(Event::End {
+                        end: self.reader.pos,
+                    }));
+                }
+                (NodeKind::Object, Expect::FirstOrClose | Expect::Key, b'"') => {
+                    frame.expect = Expect::Colon;
+                    let key = self.read_string(true)?.unwrap_or_default();
+                    return Ok(Some(Event::Key { key, offset }));
+                }
+                (_, Expect::Colon, b':') => {
+                    frame.expect = Expect::Value;
+                    self.reader.bump();
+                }
+                (NodeKind::Object, Expect::CommaOrClose, b',') => {
+                    frame.expect = Expect::Key;
+                    self.reader.bump();
+                }
+                (NodeKind::Array, Expect::CommaOrClose, b',') => {
+                    frame.expect = Expect::Value;
+                    self.reader.bump();
+                }
+                (NodeKind::Array, Expect::FirstOrClose, _) | (_, Expect::Value, _) => {
+                    return self.value_start().map(Some);
+                }
+                _ => return Err(unexpected(byte, offset)),
+            }
+        }
+    }
+
+    fn value_start(&mut self) -> Result<Event, FenrisError> {
+        match self.stack.last_mut() {
+            Some(frame) => frame.expect = Expect::CommaOrClose,
+            None => self.root_done = true,
+        }
+
+        let offset = self.reader.pos;
+        match self.reader.peek()? {
+            Some(b'{') => {
+                self.reader.bump();
+                self.stack.push(Frame {
+                    kind: NodeKind::Object,
+                    expect: Expect::FirstOrClose,
+                });
+                Ok(Event::Begin {
+                    kind: NodeKind::Object,
+                    offset,
+                })
+            }
+            Some(b'[') => {
+                self.reader.bump();
+                self.stack.push(Frame {
+                    kind: NodeKind::Array,
+                    expect: Expect::FirstOrClose,
+                });
+                Ok(Event::Begin {
+                    kind: NodeKind::Array,
+                    offset,
+                })
+            }
+            Some(b'"') => {
+                let text = self.read_string(self.capture_values)?;
+                Ok(Event::Value {
+                    kind: NodeKind::String,
+                    text,
+                    offset,
+                    end: self.reader.pos,
+                })
+            }
+            _ => self.read_literal(offset),
+        }
+    }
+
+    fn read_literal(&mut self, offset: u64) -> Result<Event, FenrisError> {
+        let mut raw = Vec::new();
+        while let Some(byte) = self.reader.peek()? {
+            if matches!(byte, b',' | b']' | b'}' | b' ' | b'\t' | b'\r' | b'\n') {
+                break;
+            }
+            raw.push(byte);
+            self.reader.bump();
+        }
+
+        if raw.is_empty() {
+            return match self.reader.peek()? {
+                Some(byte) => Err(unexpected(byte, offset)),
+                None => Err(FenrisError::validation("stream.unexpected_end", &[])),
+            };
+        }
+
+        let text = String::from_utf8(raw).unwrap_or_default();
+        let kind = match text.as_str() {
+            "true" | "false" => NodeKind::Boolean,
+            "null" => NodeKind::Null,
+            _ if serde_json::from_str::<Number>(&text).is_ok() => NodeKind::Number,
+            _ => {
+                return Err(FenrisError::validation(
+                    "stream.invalid_value",
+                    &[("value", &text), ("offset", &offset)],
+                ))
+            }
+        };
+
+        Ok(Event::Value {
+            kind,
+            text: Some(text),
+            offset,
+            end: self.reader.pos,
+        })
+    }
+
+    fn read_string(&mut self, capture: bool) -> Result<Option<String>, FenrisError> {
+        let offset = self.reader.pos;
+        self.reader.bump();
+
+        let mut raw = Vec::new();
+        let mut escaped = false;
+        let mut has_escape = false;
+        loop {
+            let byte = self.reader.peek()?.ok_or_else(|| {
+                FenrisError::validation("stream.unterminated_string", &[("offset", &offset)])
+            })?;
+            self.reader.bump();
+
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+                has_escape = true;
+            } else if byte == b'"' {
+                break;
+            }
+            if capture {
+                raw.push(byte);
+            }
+        }
+
+        if !capture {
+            return Ok(None);
+        }
+
+        let decoded = if has_escape {
+            let mut quoted = Vec::with_capacity(raw.len() + 2);
+            quoted.push(b'"');
+            quoted.extend_from_slice(&raw);
+            quoted.push(b'"');
+            from_slice::<String>(&quoted).ok()
+        } else {
+            String::from_utf8(raw).ok()
+        };
+        decoded
+            .map(Some)
+            .ok_or_else(|| FenrisError::validation("stream.invalid_string", &[("offset", &offset)]))
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), FenrisError> {
+        while let Some(b' ' | b'\t' | b'\r' | b'\n') = self.reader.peek()? {
+            self.reader.bump();
+        }
+        Ok(())
+    }
+}
+
+fn unexpected(byte: u8, offset: u64) -> FenrisError {
+    FenrisError::validation(
+        "stream.unexpected_character",
+        &[("character", &byte.escape_ascii()), ("offset", &offset)],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{remove_file, write};
+
+    // Removes the temporary file once the test is done with it
+    struct TempJson(String);
+
+    impl TempJson {
+        fn new(name: &str, contents: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "fenris-stream-{}-{}.json",
+                std::process::id(),
+                name
+            ));
+            write(&path, contents).unwrap();
+            Self(path.to_string_lossy().into_owned())
+        }
+
+        fn index(&self) -> JsonFileIndex {
+            JsonFileIndex::build(&self.0).unwrap()
+        }
+    }
+
+    impl Drop for TempJson {
+        fn drop(&mut self) {
+            let _ = remove_file(&self.0);
+        }
+    }
+
+    fn describe(event: Event) -> String {
+        match event {
+            Event::Begin { kind, offset } => format!("begin {:?} @{}", kind, offset),
+            Event::End { end } => format!("end @{}", end),
+            Event::Key { key, offset } => format!("key {} @{}", key, offset),
+            Event::Value {
+                kind,
+                text,
+                offset,
+                end,
+            } => format!(
+                "{:?} {} @{}..{}",
+                kind,
+                text.unwrap_or_default(),
+                offset,
+                end
+            ),
+        }
+    }
+
+    fn events(name: &str, contents: &str, capture_values: bool) -> Vec<String> {
+        let file = TempJson::new(name, contents);
+        let mut tokenizer = Tokenizer::new(ByteReader::open(&file.0).unwrap(), capture_values);
+        let mut events = Vec::new();
+        while let Some(event) = tokenizer.next_event().unwrap() {
+            events.push(describe(event));
+        }
+        events
+    }
+
+    fn tokenize_error(name: &str, contents: &str) -> FenrisError {
+        let file = TempJson::new(name, contents);
+        let mut tokenizer = Tokenizer::new(ByteReader::open(&file.0).unwrap(), true);
+        loop {
+            match tokenizer.next_event() {
+                Ok(Some(_)) => continue,
+                Ok(None) => panic!("{} tokenized without error", contents),
+                Err(error) => return error,
+            }
+        }
+    }
+
+    fn number_array(count: usize) -> String {
+        let items: Vec<String> = (0..count).map(|i| i.to_string()).collect();
+        format!("[{}]", items.join(","))
+    }
+
+    #[test]
+    fn tokenizer_emits_events_with_offsets() {
+        assert_eq!(
+            events("events", r#"{"a": [1, true, null, "x\"y"], "b": {}}"#, true),
+            [
+                "begin Object @0",
+                "key a @1",
+                "begin Array @6",
+                "Number 1 @7..8",
+                "Boolean true @10..14",
+                "Null null @16..20",
+                "String x\"y @22..28",
+                "end @29",
+                "key b @31",
+                "begin Object @36",
+                "end @38",
+                "end @39",
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenizer_skips_string_values_unless_captured() {
+        assert_eq!(
+            events("uncaptured", r#"{"key": "value", "n": -1.5e3}"#, false),
+            [
+                "begin Object @0",
+                "key key @1",
+                "String  @8..15",
+                "key n @17",
+                "Number -1.5e3 @22..28",
+                "end @29",
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenizer_accepts_a_scalar_root() {
+        assert_eq!(
+            events("scalar", "  \"\\u00e9t\\u00e9\"\n", true),
+            ["String été @2..17"]
+        );
+    }
+
+    #[test]
+    fn tokenizer_reports_unexpected_characters_with_offset() {
+        let error = tokenize_error("missing_comma", "[1 2]");
+        assert_eq!(error.code, "stream.unexpected_character");
+        assert_eq!(error.context["character"], "2");
+        assert_eq!(error.context["offset"], "3");
+
+        assert_eq!(
+            tokenize_error("missing_colon", r#"{"a" 1}"#).code,
+            "stream.unexpected_character"
+        );
+        assert_eq!(
+            tokenize_error("trailing_value", "[1] 2").context["offset"],
+            "4"
+        );
+    }
+
+    #[test]
+    fn tokenizer_rejects_truncated_and_invalid_input() {
+        assert_eq!(
+            tokenize_error("open_array", "[1,").code,
+            "stream.unexpected_end"
+        );
+        assert_eq!(
+            tokenize_error("open_string", "[\"abc").code,
+            "stream.unterminated_string"
+        );
+
+        let error = tokenize_error("bad_literal", "[tru]");
+        assert_eq!(error.code, "stream.invalid_value");
+        assert_eq!(error.context["value"], "tru");
+    }
+
+    #[test]
+    fn build_rejects_empty_files() {
+        let file = TempJson::new("empty", "  \n");
+        assert_eq!(
+            JsonFileIndex::build(&file.0).err().unwrap().code,
+            "stream.unexpected_end"
+        );
+    }
+
+    #[test]
+    fn build_indexes_root_and_large_containers_only() {
+        let large = number_array(2000);
+        let contents = format!(r#"{{"small": [1, 2], "large": {}, "name": "x"}}"#, large);
+        let file = TempJson::new("containers", &contents);
+        let index = file.index();
+
+        assert_eq!(index.root_kind, NodeKind::Object);
+        assert_eq!(index.root_end, contents.len() as u64);
+        assert_eq!(index.containers.len(), 2);
+        assert_eq!(index.containers[&index.root_offset].children, 3);
+
+        let large_offset = contents.find("[0,").unwrap() as u64;
+        let entry = &index.containers[&large_offset];
+        assert_eq!(entry.children, 2000);
+        assert_eq!(entry.checkpoints.len(), 1);
+    }
+
+    #[test]
+    fn children_page_lists_children_with_counts_and_previews() {
+        let large = number_array(1500);
+        let contents = format!(
+            r#"{{"a/b": {{"x": 1, "y": [1, 2]}}, "list": [[1, 2, 3], {{}}], "large": {}, "text": "hi"}}"#,
+            large
+        );
+        let file = TempJson::new("children", &contents);
+        let page = file.index().children_page("", 0, 10).unwrap();
+
+        assert_eq!(page.kind, NodeKind::Object);
+        assert_eq!(page.total, 4);
+        let summary: Vec<_> = page
+            .children
+            .iter()
+            .map(|c| {
+                (
+                    c.pointer.as_str(),
+                    c.kind,
+                    c.child_count,
+                    c.preview.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("/a~1b", NodeKind::Object, Some(2), None),
+                ("/list", NodeKind::Array, Some(2), None),
+                ("/large", NodeKind::Array, Some(1500), None),
+                ("/text", NodeKind::String, None, Some("\"hi\"")),
+            ]
+        );
+
+        let nested = file.index().children_page("/list/0", 0, 10).unwrap();
+        assert_eq!(nested.total, 3);
+        assert_eq!(nested.children[2].pointer, "/list/0/2");
+    }
+
+    #[test]
+    fn children_page_resumes_from_checkpoints() {
+        let file = TempJson::new("paging", &number_array(2500));
+        let index = file.index();
+        assert_eq!(index.containers[&index.root_offset].checkpoints.len(), 2);
+
+        let page = index.children_page("", 2100, 3).unwrap();
+        assert_eq!(page.total, 2500);
+        assert_eq!(page.offset, 2100);
+        let previews: Vec<_> = page
+            .children
+            .iter()
+            .map(|c| (c.index, c.preview.clone().unwrap()))
+            .collect();
+        assert_eq!(
+            previews,
+            [
+                (2100, String::from("2100")),
+                (2101, String::from("2101")),
+                (2102, String::from("2102")),
+            ]
+        );
+
+        let last = index.children_page("", 2498, 10).unwrap();
+        assert_eq!(last.children.len(), 2);
+    }
+
+    #[test]
+    fn locate_follows_pointers_and_reports_missing_paths() {
+        let contents = r#"{"users": [{"name": "Ann"}, {"name": "Bob"}], "~tilde": true}"#;
+        let file = TempJson::new("locate", contents);
+        let index = file.index();
+
+        let node = index.locate("/users/1/name").unwrap();
+        assert_eq!(node.kind, NodeKind::String);
+        assert_eq!(
+            &contents[node.offset as usize..node.end as usize],
+            "\"Bob\""
+        );
+        assert_eq!(index.locate("/~0tilde").unwrap().kind, NodeKind::Boolean);
+
+        assert_eq!(
+            index.locate("/users/2").err().unwrap().code,
+            "json.path_not_found"
+        );
+        assert_eq!(
+            index.locate("/users/x").err().unwrap().code,
+            "stream.invalid_index"
+        );
+        assert_eq!(
+            index.locate("/users/0/name/x").err().unwrap().code,
+            "json.path_not_found"
+        );
+        assert_eq!(
+            index.children_page("/~0tilde", 0, 10).err().unwrap().code,
+            "stream.no_children"
+        );
+    }
+
+    #[test]
+    fn parse_pointer_unescapes_segments() {
+        assert!(parse_pointer("").unwrap().is_empty());
+        assert_eq!(parse_pointer("/a~1b/~01/").unwrap(), ["a/b", "~1", ""]);
+        assert_eq!(
+            parse_pointer("a/b").unwrap_err().code,
+            "stream.invalid_pointer"
+        );
+    }
+
+    #[test]
+    fn search_matches_keys_and_values() {
+        let contents = r#"{"name": "Alice", "tags": ["alpha", "beta"], "nested": {"alias": "x"}}"#;
+        let file = TempJson::new("search", contents);
+        let index = file.index();
+
+        let (hits, truncated) = index.search("al", false, 10).unwrap();
+        let found: Vec<_> = hits
+            .iter()
+            .map(|h| (h.pointer.as_str(), h.in_key, h.preview.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("/name", false, "\"Alice\""),
+                ("/tags/0", false, "\"alpha\""),
+                ("/nested/alias", true, "alias"),
+            ]
+        );
+        assert!(!truncated);
+
+        let (hits, _) = index.search("Al", true, 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(index.search("", false, 10).unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn search_is_truncated_only_when_more_hits_exist() {
+        let contents = r#"["a1", "a2", "a3"]"#;
+        let file = TempJson::new("search_limit", contents);
+        let index = file.index();
+
+        let (hits, truncated) = index.search("a", false, 2).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(truncated);
+
+        let (hits, truncated) = index.search("a", false, 3).unwrap();
+        assert_eq!(hits.len(), 3);
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn previews_are_cut_at_the_char_limit() {
+        let long = "é".repeat(PREVIEW_CHARS + 5);
+        let preview = preview_text(NodeKind::String, &long);
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS + 3);
+        assert!(preview.ends_with("…\""));
+        assert_eq!(preview_text(NodeKind::Number, "42"), "42");
+    }
+}
@@ -0,0 +1,853 @@
+use crate::error::FenrisError;
+use crate::json::json_formatter::{format_value, write_escaped_string, FormatOptions};
+use serde::Serialize;
+use serde_json::{from_str, Value};
+use tauri::command;
+
+const MAX_DIAGNOSTICS: usize = 200;
+
+#[derive(Serialize, Clone)]
+pub struct JsonDiagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub message: String,
+    pub expected: Option<String>,
+    pub found: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct JsonFix {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub description: String,
+}
+
+#[derive(Serialize)]
+pub struct JsonValidationReport {
+    pub valid: bool,
+    pub errors: Vec<JsonDiagnostic>,
+    pub fixes: Vec<JsonFix>,
+    pub repaired: Option<String>,
+}
+
+#[command]
+pub fn validate_json(
+    json_string: String,
+    repair: Option<bool>,
+    options: Option<FormatOptions>,
+) -> Result<JsonValidationReport, FenrisError> {
+    let repair = repair.unwrap_or(false);
+    let mut parser = LenientParser::new(&json_string, repair);
+    parser.parse_document();
+
+    let LenientParser {
+        output,
+        errors,
+        fixes,
+        ..
+    } = parser;
+
+    if !repair || !errors.is_empty() {
+        return Ok(JsonValidationReport {
+            valid: errors.is_empty(),
+            errors,
+            fixes,
+            repaired: None,
+        });
+    }
+
+    // The repaired text is re-parsed so the frontend only ever receives valid JSON
+    let parsed: Value = from_str(&output)
+        .map_err(|e| FenrisError::validation("json.repair_failed", &[("error", &e)]))?;
+
+    Ok(JsonValidationReport {
+        valid: fixes.is_empty(),
+        errors,
+        fixes,
+        repaired: Some(format_value(&parsed, &options.unwrap_or_default())),
+    })
+}
+
+struct LenientParser<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    line_starts: Vec<usize>,
+    repair: bool,
+    output: String,
+    errors: Vec<JsonDiagnostic>,
+    fixes: Vec<JsonFix>,
+}
+
+impl<'a> LenientParser<'a> {
+    fn new(text: &'a str, repair: bool) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+
+        Self {
+            text,
+            bytes: text.as_bytes(),
+            pos: 0,
+            line_starts,
+            repair,
+            output: String::with_capacity(text.len()),
+            errors: Vec::new(),
+            fixes: Vec::new(),
+        }
+    }
+
+    fn location(&self, offset: usize) -> (usize, usize) {
+        let line_index = match self.line_starts.binary_search(&offset) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+        let line_start = self.line_starts[line_index];
+        let column = self.text[line_start..offset].chars().count() + 1;
+        (line_index + 1, column)
+    }
+
+    fn found_at(&self, offset: usize) -> Option<String> {
+        match self.text[offset..].chars().next() {
+            Some(c) => Some(c.to_string()),
+            None => Some(String::from("end of input")),
+        }
+    }
+
+    fn error(&mut self, offset: usize, message: &str, expected: Option<&str>) {
+        // Nested containers failing at the same spot would otherwise repeat the same error
+        let repeated = self.errors.last().is_some_and(|e| e.offset == offset);
+        if repeated || self.errors.len() >= MAX_DIAGNOSTICS {
+            return;
+        }
+        let (line, column) = self.location(offset);
+        self.errors.push(JsonDiagnostic {
+            line,
+            column,
+            offset,
+            message: message.to_string(),
+            expected: expected.map(|e| e.to_string()),
+            found: self.found_at(offset),
+        });
+    }
+
+    // Repairable problems become fixes in repair mode and errors otherwise
+    fn fixable(&mut self, offset: usize, message: &str, fix: &str, expected: Option<&str>) {
+        if self.repair {
+            let (line, column) = self.location(offset);
+            self.fixes.push(JsonFix {
+                line,
+                column,
+                offset,
+                description: fix.to_string(),
+            });
+        } else {
+            self.error(offset, message, expected);
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            match b {
+                b' ' | b'\t' | b'\n' | b'\r' => self.pos += 1,
+                b'/' if self.bytes.get(self.pos + 1) == Some(&b'/') => {
+                    let start = self.pos;
+                    while let Some(b) = self.peek() {
+                        if b == b'\n' {
+                            break;
+                        }
+                        self.pos += 1;
+                    }
+                    self.fixable(
+                        start,
+                        "Comments are not allowed in JSON",
+                        "Removed comment",
+                        None,
+                    );
+                }
+                b'/' if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+                    let start = self.pos;
+                    match self.text[self.pos + 2..].find("*/") {
+                        Some(end) => {
+                            self.pos += end + 4;
+                            self.fixable(
+                                start,
+                                "Comments are not allowed in JSON",
+                                "Removed comment",
+                                None,
+                            );
+                        }
+                        None => {
+                            self.error(start, "Unterminated block comment", Some("*/"));
+                            self.pos = self.bytes.len();
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    // Skips to the next delimiter so one bad token doesn't cascade into many errors
+    fn recover(&mut self) {
+        while let Some(b) = self.peek() {
+            match b {
+                b',' | b'}' | b']' => break,
+                b'"' | b'\'' => {
+                    self.pos += 1;
+                    while let Some(c) = self.peek() {
+                        self.pos += 1;
+                        if c == b'\\' {
+                            self.pos += 1;
+                        } else if c == b {
+                            break;
+                        }
+                    }
+                }
+                _ => self.pos += 1,
+            }
+        }
+        self.pos = self.pos.min(self.bytes.len());
+    }
+
+    fn parse_document(&mut self) {
+        self.skip_whitespace();
+        if self.peek().is_none() {
+            self.error(self.pos, "Empty document", Some("value"));
+            return;
+        }
+
+        self.parse_value();
+        self.skip_whitespace();
+
+        if self.pos < self.bytes.len() {
+            self.error(
+                self.pos,
+                "Unexpected characters after the end of the document",
+                Some("end of input"),
+            );
+        }
+    }
+
+    fn parse_value(&mut self) {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => self.parse_string(),
+            Some(b'\'') => self.parse_single_quoted_string(),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(b) if b.is_ascii_alphabetic() || b == b'+' || b == b'.' => self.parse_literal(),
+            Some(_) => {
+                self.error(self.pos, "Unexpected character", Some("value"));
+                self.output.push_str("null");
+                self.recover();
+            }
+            None => {
+                self.error(self.pos, "Unexpected end of input", Some("value"));
+            }
+        }
+    }
+
+    fn parse_object(&mut self) {
+        self.pos += 1;
+        self.output.push('{');
+        let mut first = true;
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    break;
+                }
+                None => {
+                    self.error(self.pos, "Unterminated object", Some("}"));
+                    break;
+                }
+                _ => {}
+            }
+
+            if !first {
+                self.output.push(',');
+            }
+            first = false;
+            let member_start = self.pos;
+
+            if self.parse_key() {
+                self.skip_whitespace();
+                if self.peek() == Some(b':') {
+                    self.pos += 1;
+                } else {
+                    self.error(self.pos, "Missing ':' after object key", Some(":"));
+                }
+                self.output.push(':');
+                self.parse_value();
+            } else {
+                self.output.push_str(":null");
+            }
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    let comma = self.pos;
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b'}') {
+                        self.fixable(
+                            comma,
+                            "Trailing comma before '}'",
+                            "Removed trailing comma",
+                            Some("string"),
+                        );
+                    }
+                }
+                Some(b'}') | None => {}
+                Some(b']') => {
+                    self.error(self.pos, "Mismatched ']' inside an object", Some("}"));
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => {
+                    self.error(
+                        self.pos,
+                        "Missing ',' between object members",
+                        Some(", or }"),
+                    );
+                    if self.pos == member_start {
+                        self.pos += 1;
+                    }
+                }
+            }
+        }
+
+        self.output.push('}');
+    }
+
+    fn parse_key(&mut self) -> bool {
+        let text = self.text;
+        match self.peek() {
+            Some(b'"') => self.parse_string(),
+            Some(b'\'') => self.parse_single_quoted_string(),
+            Some(b) if b.is_ascii_alphabetic() || b == b'_' || b == b'$' => {
+                let start = self.pos;
+                while let Some(b) = self.peek() {
+                    if b.is_ascii_alphanumeric() || b == b'_' || b == b'$' {
+                        self.pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+                let key = &text[start..self.pos];
+                self.fixable(
+                    start,
+                    "Object keys must be double-quoted strings",
+                    &format!("Quoted key {}", key),
+                    Some("string"),
+                );
+                write_escaped_string(&mut self.output, key);
+            }
+            _ => {
+                self.error(self.pos, "Expected an object key", Some("string"));
+                self.output.push_str("\"\"");
+                self.recover();
+                return false;
+            }
+        }
+        true
+    }
+
+    fn parse_array(&mut self) {
+        self.pos += 1;
+        self.output.push('[');
+        let mut first = true;
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b']') => {
+                    self.pos += 1;
+                    break;
+                }
+                None => {
+                    self.error(self.pos, "Unterminated array", Some("]"));
+                    break;
+                }
+                _ => {}
+            }
+
+            if !first {
+                self.output.push(',');
+            }
+            first = false;
+            let element_start = self.pos;
+
+            self.parse_value();
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => {
+                    let comma = self.pos;
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b']') {
+                        self.fixable(
+                            comma,
+                            "Trailing comma before ']'",
+                            "Removed trailing comma",
+                            Some("value"),
+                        );
+                    }
+                }
+                Some(b']') | None => {}
+                Some(b'}') => {
+                    self.error(self.pos, "Mismatched '}' inside an array", Some("]"));
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => {
+                    self.error(
+                        self.pos,
+                        "Missing ',' between array elements",
+                        Some(", or ]"),
+                    );
+                    if self.pos == element_start {
+                        self.pos += 1;
+                    }
+                }
+            }
+        }
+
+        self.output.push(']');
+    }
+
+    fn parse_string(&mut self) {
+        let start = self.pos;
+        self.pos += 1;
+
+        while let Some(b) = self.peek() {
+            match b {
+                b'"' => {
+                    self.pos += 1;
+                    self.output.push_str(&self.text[start..self.pos]);
+                    return;
+                }
+                b'\\' => {
+                    match self.bytes.get(self.pos + 1) {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {}
+                        Some(b'u') => {
+                            let hex = self.text.get(self.pos + 2..self.pos + 6).unwrap_or("");
+                            if hex.len() != 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                                self.error(
+                                    self.pos,
+                                    "Invalid unicode escape",
+                                    Some("four hex digits"),
+                                );
+                            }
+                        }
+                        _ => self.error(self.pos, "Invalid escape sequence", None),
+                    }
+                    self.pos += 2;
+                }
+                b if b < 0x20 => {
+                    self.error(
+                        self.pos,
+                        "Control characters must be escaped inside strings",
+                        None,
+                    );
+                    self.pos += 1;
+                }
+                _ => self.pos += 1,
+            }
+        }
+
+        self.pos = self.bytes.len();
+        self.error(start, "Unterminated string", Some("\""));
+        self.output.push_str("\"\"");
+    }
+
+    fn parse_single_quoted_string(&mut self) {
+        let start = self.pos;
+        self.pos += 1;
+        let mut decoded = String::new();
+
+        while self.pos < self.bytes.len() {
+            let c = self.text[self.pos..].chars().next().unwrap_or_default();
+            match c {
+                '\'' => {
+                    self.pos += 1;
+                    self.fixable(
+                        start,
+                        "Strings must use double quotes",
+                        "Replaced single quotes with double quotes",
+                        Some("\""),
+                    );
+                    write_escaped_string(&mut self.output, &decoded);
+                    return;
+                }
+                '\\' => {
+                    let escaped = self.text[self.pos + 1..].chars().next().unwrap_or_default();
+                    let simple = match escaped {
+                        '\'' | '"' | '\\' | '/' => Some(escaped),
+                        'b' => Some('\u{8}'),
+                        'f' => Some('\u{c}'),
+                        'n' => Some('\n'),
+                        'r' => Some('\r'),
+                        't' => Some('\t'),
+                        _ => None,
+                    };
+                    match (simple, escaped) {
+                        (Some(c), _) => {
+                            decoded.push(c);
+                            self.pos += 2;
+                        }
+                        (None, 'u') => match self.unicode_escape(self.pos) {
+                            Some((c, len)) => {
+                                decoded.push(c);
+                                self.pos += len;
+                            }
+                            None => {
+                                self.error(
+                                    self.pos,
+                                    "Invalid unicode escape",
+                                    Some("four hex digits"),
+                                );
+                                self.pos += 2;
+                            }
+                        },
+                        _ => {
+                            self.error(self.pos, "Invalid escape sequence", None);
+                            self.pos += 1 + escaped.len_utf8();
+                        }
+                    }
+                }
+                c => {
+                    decoded.push(c);
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+
+        self.pos = self.bytes.len();
+        self.error(start, "Unterminated string", Some("'"));
+        self.output.push_str("\"\"");
+    }
+
+    // Decodes `\uXXXX` at `at`, joining a surrogate pair into one character.
+    // Returns the character and the length of the escape text, None for bad hex or a lone surrogate
+    fn unicode_escape(&self, at: usize) -> Option<(char, usize)> {
+        let hex_at = |offset: usize| {
+            let hex = self.text.get(offset + 2..offset + 6)?;
+            if self.text[offset..].starts_with("\\u") && hex.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                u32::from_str_radix(hex, 16).ok()
+            } else {
+                None
+            }
+        };
+
+        let high = hex_at(at)?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).map(|c| (c, 6));
+        }
+        let low = hex_at(at + 6).filter(|low| (0xDC00..0xE000).contains(low))?;
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).map(|c| (c, 12))
+    }
+
+    fn parse_number(&mut self) {
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+
+        let raw = &self.text[start..self.pos];
+        if raw == "-" && self.text[self.pos..].starts_with("Infinity") {
+            self.pos += "Infinity".len();
+            self.fixable(
+                start,
+                "-Infinity is not a valid JSON value",
+                "Replaced -Infinity with null",
+                Some("value"),
+            );
+            self.output.push_str("null");
+            return;
+        }
+
+        if is_json_number(raw) {
+            self.output.push_str(raw);
+        } else {
+            self.error(start, &format!("Invalid number '{}'", raw), Some("number"));
+            self.output.push('0');
+        }
+    }
+
+    fn parse_literal(&mut self) {
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if b.is_ascii_alphanumeric() || matches!(b, b'_' | b'+' | b'-' | b'.') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+
+        let word = &self.text[start..self.pos];
+        match word {
+            "true" | "false" | "null" => self.output.push_str(word),
+            "NaN" | "Infinity" | "+Infinity" | "undefined" => {
+                self.fixable(
+                    start,
+                    &format!("{} is not a valid JSON value", word),
+                    &format!("Replaced {} with null", word),
+                    Some("value"),
+                );
+                self.output.push_str("null");
+            }
+            _ => {
+                self.error(
+                    start,
+                    &format!("Unexpected token '{}'", word),
+                    Some("value"),
+                );
+                self.output.push_str("null");
+            }
+        }
+    }
+}
+
+fn is_json_number(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    let mut i = 0;
+
+    if bytes.get(i) == Some(&b'-') {
+        i += 1;
+    }
+
+    match bytes.get(i) {
+        Some(b'0') => i += 1,
+        Some(b'1'..=b'9') => {
+            while bytes.get(i).is_some_and(|b| b.is_ascii_digit()) {
+                i += 1;
+            }
+        }
+        _ => return false,
+    }
+
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        if !bytes.get(i).is_some_and(|b| b.is_ascii_digit()) {
+            return false;
+        }
+        while bytes.get(i).is_some_and(|b| b.is_ascii_digit()) {
+            i += 1;
+        }
+    }
+
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        if !bytes.get(i).is_some_and(|b| b.is_ascii_digit()) {
+            return false;
+        }
+        while bytes.get(i).is_some_and(|b| b.is_ascii_digit()) {
+            i += 1;
+        }
+    }
+
+    i == bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn check(text: &str) -> JsonValidationReport {
+        validate_json(text.to_string(), None, None).unwrap()
+    }
+
+    fn repair(text: &str) -> JsonValidationReport {
+        validate_json(text.to_string(), Some(true), None).unwrap()
+    }
+
+    fn messages(report: &JsonValidationReport) -> Vec<&str> {
+        report.errors.iter().map(|e| e.message.as_str()).collect()
+    }
+
+    #[test]
+    fn accepts_valid_json() {
+        let report = check(r#"{"a": [1, -2.5e3, true, null], "b": {"c": "é\n"}}"#);
+        assert!(report.valid);
+        assert!(report.errors.is_empty());
+        assert!(report.fixes.is_empty());
+        assert!(report.repaired.is_none());
+    }
+
+    #[test]
+    fn reports_line_column_expected_and_found() {
+        let report = check("{\n  \"a\": 1,\n  \"b\" 2\n}");
+        assert!(!report.valid);
+        let error = &report.errors[0];
+        assert_eq!(error.message, "Missing ':' after object key");
+        assert_eq!((error.line, error.column), (3, 7));
+        assert_eq!(error.expected.as_deref(), Some(":"));
+        assert_eq!(error.found.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        let error = &check(r#"{"é": x}"#).errors[0];
+        assert_eq!(error.message, "Unexpected token 'x'");
+        // é takes two bytes, so the byte offset is one past the column
+        assert_eq!((error.line, error.column, error.offset), (1, 7, 7));
+    }
+
+    #[test]
+    fn reports_end_of_input() {
+        assert_eq!(messages(&check("  ")), ["Empty document"]);
+        let report = check(r#"{"a": "open"#);
+        assert_eq!(
+            messages(&report),
+            ["Unterminated string", "Unterminated object"]
+        );
+        assert_eq!(report.errors[0].offset, 6);
+        assert_eq!(report.errors[1].offset, 11);
+        assert_eq!(
+            check("[1, 2").errors[0].found.as_deref(),
+            Some("end of input")
+        );
+    }
+
+    #[test]
+    fn reports_repairable_problems_as_errors_without_repair() {
+        let report = check("{a: 'x', /* c */ \"b\": [1, 2,],}");
+        assert_eq!(
+            messages(&report),
+            [
+                "Object keys must be double-quoted strings",
+                "Strings must use double quotes",
+                "Comments are not allowed in JSON",
+                "Trailing comma before ']'",
+                "Trailing comma before '}'",
+            ]
+        );
+        assert!(report.fixes.is_empty());
+    }
+
+    #[test]
+    fn repairs_common_mistakes() {
+        let report = repair("// config\n{a: 'it\\'s', b: [1, 2,], c: NaN, d: -Infinity,}");
+        assert!(!report.valid);
+        assert!(report.errors.is_empty());
+        let fixes: Vec<&str> = report
+            .fixes
+            .iter()
+            .map(|f| f.description.as_str())
+            .collect();
+        assert_eq!(
+            fixes,
+            [
+                "Removed comment",
+                "Quoted key a",
+                "Replaced single quotes with double quotes",
+                "Quoted key b",
+                "Removed trailing comma",
+                "Quoted key c",
+                "Replaced NaN with null",
+                "Quoted key d",
+                "Replaced -Infinity with null",
+                "Removed trailing comma",
+            ]
+        );
+
+        let repaired: Value = from_str(&report.repaired.unwrap()).unwrap();
+        assert_eq!(
+            repaired,
+            json!({"a": "it's", "b": [1, 2], "c": null, "d": null})
+        );
+    }
+
+    #[test]
+    fn repair_decodes_escapes_in_single_quoted_strings() {
+        let report = repair(r"['\u00e9', '\b', '\f', '\ud83d\ude00', 'a\/b\x27']");
+        assert_eq!(messages(&report), ["Invalid escape sequence"]);
+
+        let report = repair(r"['\u00e9', '\b', '\f', '\ud83d\ude00', 'a\/b']");
+        let repaired = report.repaired.unwrap();
+        assert!(repaired.contains(r#""\b""#) && repaired.contains(r#""\f""#));
+        let value: Value = from_str(&repaired).unwrap();
+        assert_eq!(value, json!(["é", "\u{8}", "\u{c}", "😀", "a/b"]));
+
+        assert_eq!(
+            messages(&repair(r"['\ud83d', '\u12']")),
+            ["Invalid unicode escape", "Invalid unicode escape"]
+        );
+    }
+
+    #[test]
+    fn valid_json_needs_no_repair() {
+        let report = repair(r#"{"a":[1,2]}"#);
+        assert!(report.valid);
+        let repaired: Value = from_str(&report.repaired.unwrap()).unwrap();
+        assert_eq!(repaired, json!({"a": [1, 2]}));
+    }
+
+    #[test]
+    fn does_not_repair_real_errors() {
+        let report = repair(r#"{"a": , "b": 'x'}"#);
+        assert_eq!(messages(&report), ["Unexpected character"]);
+        assert_eq!(report.fixes.len(), 1);
+        assert!(report.repaired.is_none());
+    }
+
+    #[test]
+    fn recovers_after_errors() {
+        let report = check(r#"[1, @, 3 4, 01, "a\q", {"k" "v"}]"#);
+        assert_eq!(
+            messages(&report),
+            [
+                "Unexpected character",
+                "Missing ',' between array elements",
+                "Invalid number '01'",
+                "Invalid escape sequence",
+                "Missing ':' after object key",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_mismatched_brackets_once() {
+        assert_eq!(
+            messages(&check(r#"{"a": 1]"#)),
+            ["Mismatched ']' inside an object"]
+        );
+        assert_eq!(messages(&check("[[[")), ["Unterminated array"]);
+    }
+
+    #[test]
+    fn caps_the_number_of_diagnostics() {
+        let text = format!("[{}]", "@,".repeat(MAX_DIAGNOSTICS * 2));
+        assert_eq!(check(&text).errors.len(), MAX_DIAGNOSTICS);
+    }
+
+    #[test]
+    fn recognizes_json_numbers() {
+        for valid in ["0", "-0", "12", "-0.5", "1e10", "1E+2", "2.5e-3"] {
+            assert!(is_json_number(valid), "{}", valid);
+        }
+        for invalid in ["", "-", "01", "1.", ".5", "+1", "1e", "1e+", "1.2.3", "--1"] {
+            assert!(!is_json_number(invalid), "{}", invalid);
+        }
+    }
+}
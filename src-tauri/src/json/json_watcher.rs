@@ -0,0 +1,357 @@
+use crate::error::{lock, FenrisError};
+use crate::json::json_diff::{diff_values, DiffOptions};
+use crate::json::json_helpers::fetch_json_value;
+use crate::sqlite::app_sqlite_pool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_value, json, to_value, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::{spawn, JoinHandle};
+use tauri::{command, AppHandle, Emitter, Manager, State};
+use tauri_plugin_store::StoreExt;
+
+const WATCH_STORE: &str = "json-watches.json";
+const SNAPSHOT_STORE: &str = "json-watch-snapshots.json";
+const MAX_SNAPSHOTS: usize = 20;
+const MIN_INTERVAL_SECS: u64 = 5;
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+const MAX_BACKOFF_SECS: u64 = 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWatch {
+    pub name: String,
+    pub url: String,
+    pub interval_secs: u64,
+    pub paused: bool,
+    pub create_note: bool,
+    pub last_checked: Option<String>,
+    pub last_changed: Option<String>,
+    pub last_error: Option<String>,
+    pub consecutive_errors: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSnapshot {
+    pub taken_at: String,
+    pub content: Value,
+}
+
+#[derive(Serialize, Clone)]
+pub struct JsonWatchChange {
+    pub name: String,
+    pub url: String,
+    pub checked_at: String,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+// Running poll tasks, keyed by watch name
+#[derive(Default)]
+pub struct JsonWatchManager(pub Mutex<HashMap<String, JoinHandle<()>>>);
+
+#[command]
+pub fn add_json_watch(
+    app: AppHandle,
+    manager: State<JsonWatchManager>,
+    name: String,
+    url: String,
+    interval_secs: Option<u64>,
+    create_note: Option<bool>,
+) -> Result<JsonWatch, FenrisError> {
+    if name.trim().is_empty() || url.trim().is_empty() {
+        return Err(FenrisError::validation("watch.fields_empty", &[]));
+    }
+
+    let store = app
+        .store(WATCH_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    if store.has(&name) {
+        return Err(FenrisError::conflict("watch.exists", &[("name", &name)]));
+    }
+
+    let watch = JsonWatch {
+        name: name.clone(),
+        url: url.trim().to_string(),
+        interval_secs: interval_secs
+            .unwrap_or(DEFAULT_INTERVAL_SECS)
+            .max(MIN_INTERVAL_SECS),
+        paused: false,
+        create_note: create_note.unwrap_or(false),
+        last_checked: None,
+        last_changed: None,
+        last_error: None,
+        consecutive_errors: 0,
+    };
+
+    store.set(
+        name.clone(),
+        to_value(&watch)
+            .map_err(|e| FenrisError::internal("json.serialize_failed", &[("error", &e)]))?,
+    );
+    store
+        .save()
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))?;
+
+    start_watch(&app, &manager, name)?;
+    Ok(watch)
+}
+
+#[command]
+pub fn pause_json_watch(
+    app: AppHandle,
+    manager: State<JsonWatchManager>,
+    name: String,
+    paused: bool,
+) -> Result<JsonWatch, FenrisError> {
+    let store = app
+        .store(WATCH_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    let mut watch: JsonWatch = store
+        .get(&name)
+        .and_then(|value| from_value(value).ok())
+        .ok_or_else(|| FenrisError::not_found("watch.not_found", &[("name", &name)]))?;
+
+    watch.paused = paused;
+    watch.consecutive_errors = 0;
+    store.set(
+        name.clone(),
+        to_value(&watch)
+            .map_err(|e| FenrisError::internal("json.serialize_failed", &[("error", &e)]))?,
+    );
+    store
+        .save()
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))?;
+
+    if paused {
+        stop_watch(&manager, &name)?;
+    } else {
+        start_watch(&app, &manager, name)?;
+    }
+    Ok(watch)
+}
+
+#[command]
+pub fn list_json_watches(app: AppHandle) -> Result<Vec<JsonWatch>, FenrisError> {
+    let store = app
+        .store(WATCH_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    let mut watches: Vec<JsonWatch> = store
+        .values()
+        .into_iter()
+        .filter_map(|value| from_value(value).ok())
+        .collect();
+    watches.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(watches)
+}
+
+#[command]
+pub fn remove_json_watch(
+    app: AppHandle,
+    manager: State<JsonWatchManager>,
+    name: String,
+) -> Result<(), FenrisError> {
+    stop_watch(&manager, &name)?;
+
+    for store_name in [WATCH_STORE, SNAPSHOT_STORE] {
+        let store = app
+            .store(store_name)
+            .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+        store.delete(&name);
+        store
+            .save()
+            .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))?;
+    }
+    Ok(())
+}
+
+#[command]
+pub fn get_json_watch_snapshots(
+    app: AppHandle,
+    name: String,
+) -> Result<Vec<JsonSnapshot>, FenrisError> {
+    let store = app
+        .store(SNAPSHOT_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    Ok(store
+        .get(&name)
+        .and_then(|value| from_value(value).ok())
+        .unwrap_or_default())
+}
+
+// Called once at startup so active watches keep polling across restarts
+pub fn restore_json_watches(app: &AppHandle) -> Result<(), FenrisError> {
+    let manager = app.state::<JsonWatchManager>();
+    for watch in list_json_watches(app.clone())? {
+        if !watch.paused {
+            start_watch(app, &manager, watch.name)?;
+        }
+    }
+    Ok(())
+}
+
+fn start_watch(
+    app: &AppHandle,
+    manager: &JsonWatchManager,
+    name: String,
+) -> Result<(), FenrisError> {
+    let handle = spawn(run_watch(app.clone(), name.clone()));
+    let mut tasks = lock(&manager.0)?;
+    if let Some(previous) = tasks.insert(name, handle) {
+        previous.abort();
+    }
+    Ok(())
+}
+
+fn stop_watch(manager: &JsonWatchManager, name: &str) -> Result<(), FenrisError> {
+    let mut tasks = lock(&manager.0)?;
+    if let Some(handle) = tasks.remove(name) {
+        handle.abort();
+    }
+    Ok(())
+}
+
+async fn run_watch(app: AppHandle, name: String) {
+    while let Some(delay) = poll_watch(&app, &name).await {
+        tokio::time::sleep(delay).await;
+    }
+}
+
+// Polls once and returns the delay before the next poll, or None when the watch is gone or paused
+async fn poll_watch(app: &AppHandle, name: &str) -> Option<Duration> {
+    let store = app.store(WATCH_STORE).ok()?;
+    let mut watch: JsonWatch = from_value(store.get(name)?).ok()?;
+    if watch.paused {
+        return None;
+    }
+
+    let now: DateTime<Utc> = Utc::now();
+    let checked_at = now.format("%Y-%m-%d %H:%M:%S").to_string();
+    watch.last_checked = Some(checked_at.clone());
+
+    match fetch_json_value(&watch.url).await {
+        Ok(content) => {
+            watch.consecutive_errors = 0;
+            watch.last_error = None;
+
+            if let Some(change) = record_snapshot(app, &watch, content, &checked_at) {
+                watch.last_changed = Some(checked_at);
+                let _ = app.emit("json-watch-changed", &change);
+
+                if watch.create_note {
+                    if let Err(e) = append_watch_note(app, &change).await {
+                        watch.last_error = Some(e.message);
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            watch.consecutive_errors += 1;
+            watch.last_error = Some(e.message.clone());
+            let _ = app.emit("json-watch-error", json!({ "name": name, "error": e }));
+        }
+    }
+
+    // The watch may have been paused or removed while the request was in flight
+    let current: JsonWatch = from_value(store.get(name)?).ok()?;
+    watch.paused = current.paused;
+    store.set(name, to_value(&watch).ok()?);
+    let _ = store.save();
+
+    if watch.paused {
+        return None;
+    }
+    Some(next_delay(&watch))
+}
+
+// Doubles the interval for every consecutive error, capped at an hour
+fn next_delay(watch: &JsonWatch) -> Duration {
+    let factor = 2u64.saturating_pow(watch.consecutive_errors.min(16));
+    let secs = watch
+        .interval_secs
+        .saturating_mul(factor)
+        .min(MAX_BACKOFF_SECS.max(watch.interval_secs));
+    Duration::from_secs(secs)
+}
+
+// Stores the content when it differs from the latest snapshot and describes the change.
+// The first snapshot of a watch is a baseline and is not reported as a change
+fn record_snapshot(
+    app: &AppHandle,
+    watch: &JsonWatch,
+    content: Value,
+    checked_at: &str,
+) -> Option<JsonWatchChange> {
+    let store = app.store(SNAPSHOT_STORE).ok()?;
+    let mut snapshots: Vec<JsonSnapshot> = store
+        .get(&watch.name)
+        .and_then(|value| from_value(value).ok())
+        .unwrap_or_default();
+
+    let change = match snapshots.last() {
+        Some(previous) if previous.content == content => return None,
+        Some(previous) => {
+            let diff = diff_values(&previous.content, &content, &DiffOptions::default());
+            Some(JsonWatchChange {
+                name: watch.name.clone(),
+                url: watch.url.clone(),
+                checked_at: checked_at.to_string(),
+                added: diff.added,
+                removed: diff.removed,
+                changed: diff.changed,
+            })
+        }
+        None => None,
+    };
+
+    snapshots.push(JsonSnapshot {
+        taken_at: checked_at.to_string(),
+        content,
+    });
+    if snapshots.len() > MAX_SNAPSHOTS {
+        snapshots.drain(..snapshots.len() - MAX_SNAPSHOTS);
+    }
+
+    store.set(watch.name.clone(), to_value(&snapshots).ok()?);
+    let _ = store.save();
+    change
+}
+
+// Appends the change to a single note named after the watch, creating it on first use
+async fn append_watch_note(app: &AppHandle, change: &JsonWatchChange) -> Result<(), FenrisError> {
+    let pool = app_sqlite_pool(app).await?;
+    let title = format!("Watch: {}", change.name);
+    let entry = format!(
+        "## {}\n\n{} changed: {} added, {} removed, {} changed\n\n",
+        change.checked_at, change.url, change.added, change.removed, change.changed
+    );
+
+    let existing: Option<(i64,)> = sqlx::query_as("SELECT id FROM single_notes WHERE title = ?")
+        .bind(&title)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| FenrisError::sqlx("watch.note_read_failed", &e, &[]))?;
+
+    match existing {
+        Some((id,)) => sqlx::query(
+            "UPDATE single_notes SET content = content || ?, date_modified = ? WHERE id = ?",
+        )
+        .bind(&entry)
+        .bind(&change.checked_at)
+        .bind(id),
+        None => sqlx::query(
+            "INSERT INTO single_notes (title, content, date_created, date_modified) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&title)
+        .bind(&entry)
+        .bind(&change.checked_at)
+        .bind(&change.checked_at),
+    }
+    .execute(&pool)
+    .await
+    .map_err(|e| FenrisError::sqlx("watch.note_save_failed", &e, &[]))?;
+
+    Ok(())
+}
@@ -1,5 +1,28 @@
+mod api_collections;
+mod api_request;
+mod json_codegen;
+mod json_diff;
+mod json_formatter;
 mod json_helpers;
+mod json_library;
+mod json_query;
+mod json_schema;
+mod json_stats;
+mod json_stream;
+mod json_validator;
+mod json_watcher;
 mod search_helpers;
 
+pub use api_collections::*;
+pub use api_request::*;
+pub use json_codegen::*;
+pub use json_diff::*;
 pub use json_helpers::*;
+pub use json_library::*;
+pub use json_query::*;
+pub use json_schema::*;
+pub use json_stats::*;
+pub use json_stream::*;
+pub use json_validator::*;
+pub use json_watcher::*;
 pub use search_helpers::*;
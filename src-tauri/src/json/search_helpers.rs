@@ -1,7 +1,10 @@
-use regex::Regex;
-use serde::Serialize;
-use tauri::command;
+use crate::error::FenrisError;
+use regex::{Match, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
+use tauri::command;
+
+const DEFAULT_SEARCH_LIMIT: usize = 10000;
 
 #[derive(Serialize)]
 pub struct SearchMatch {
@@ -20,118 +23,278 @@ pub struct MatchRange {
 pub struct SearchResult {
     pub count: usize,
     pub matches: Vec<SearchMatch>,
+    pub truncated: bool,
     pub duration_ms: u128,
 }
 
+#[derive(Serialize)]
+pub struct ReplaceResult {
+    pub text: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct SearchOptions {
+    /// Treats the search term as a regular expression instead of literal text
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Lets matches span lines, `.` then also matches line breaks
+    pub multiline: bool,
+    /// Maximum number of matches, defaults to 10000 for searches and unlimited for replacements
+    pub limit: Option<usize>,
+}
+
+// Whole-word matches are checked against their neighbouring characters rather than with `\b`,
+// which never matches next to a term that starts or ends with punctuation
+struct SearchPattern {
+    regex: Regex,
+    whole_word: bool,
+}
+
+impl SearchPattern {
+    fn find_iter<'t>(&'t self, text: &'t str) -> impl Iterator<Item = Match<'t>> + 't {
+        self.regex
+            .find_iter(text)
+            .filter(move |m| !m.is_empty() && (!self.whole_word || is_whole_word(text, m)))
+    }
+}
+
 #[command]
-pub fn perform_search(json_response: String, search_term: String) -> Result<SearchResult, String> {
+pub fn search_text(
+    text: String,
+    search_term: String,
+    options: Option<SearchOptions>,
+) -> Result<SearchResult, FenrisError> {
     let start_time = Instant::now();
+    let options = options.unwrap_or_default();
 
-    if search_term.trim().is_empty() {
+    if search_term.is_empty() || (!options.regex && search_term.trim().is_empty()) {
         return Ok(SearchResult {
             count: 0,
             matches: Vec::new(),
+            truncated: false,
             duration_ms: 0,
         });
     }
 
-    let regex = Regex::new(&search_term)
-        .map_err(|e| format!("Invalid regex pattern: {}", e))?;
+    let pattern = build_search_pattern(&search_term, &options)?;
+    let limit = options.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
 
-    let mut matches = Vec::new();
-    let mut total_count = 0;
+    let (matches, count, truncated) = if options.multiline {
+        search_multiline(&text, &pattern, limit)
+    } else {
+        search_lines(&text, &pattern, limit)
+    };
 
-    let estimated_lines = json_response.len() / 80;
-    matches.reserve(estimated_lines.min(1000));
+    Ok(SearchResult {
+        count,
+        matches,
+        truncated,
+        duration_ms: start_time.elapsed().as_millis(),
+    })
+}
 
-    for (line_number, line) in json_response.lines().enumerate() {
-        let line_matches: Vec<MatchRange> = regex
-            .find_iter(line)
-            .map(|m| MatchRange {
-                start: m.start(),
-                end: m.end(),
-            })
-            .collect();
-
-        if !line_matches.is_empty() {
-            total_count += line_matches.len();
-            matches.push(SearchMatch {
-                line_number: line_number + 1,
-                line: line.to_string(),
-                matches: line_matches,
-            });
+// Replacement text may refer to capture groups as $1 or ${name} in regex mode
+#[command]
+pub fn replace_text(
+    text: String,
+    search_term: String,
+    replacement: String,
+    options: Option<SearchOptions>,
+) -> Result<ReplaceResult, FenrisError> {
+    let options = options.unwrap_or_default();
+    if search_term.is_empty() {
+        return Ok(ReplaceResult { text, count: 0 });
+    }
 
-            if total_count > 10000 {
-                break;
-            }
-        }
+    let pattern = build_search_pattern(&search_term, &options)?;
+    let mut remaining = options.limit.unwrap_or(usize::MAX);
+
+    if options.multiline {
+        let (text, count) =
+            replace_limited(&text, &pattern, &replacement, options.regex, remaining);
+        return Ok(ReplaceResult { text, count });
     }
 
-    let duration = start_time.elapsed();
+    // Line by line so a pattern never swallows the line breaks between lines
+    let mut output = String::with_capacity(text.len());
+    let mut count = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let ending = &line[content.len()..];
 
-    Ok(SearchResult {
-        count: total_count,
-        matches,
-        duration_ms: duration.as_millis(),
+        let (replaced, replaced_count) =
+            replace_limited(content, &pattern, &replacement, options.regex, remaining);
+        output.push_str(&replaced);
+        output.push_str(ending);
+        count += replaced_count;
+        remaining -= replaced_count;
+    }
+
+    Ok(ReplaceResult {
+        text: output,
+        count,
     })
 }
 
-#[command]
-pub fn perform_fast_search(json_response: String, search_term: String) -> Result<SearchResult, String> {
-    let start_time = Instant::now();
+fn build_search_pattern(
+    search_term: &str,
+    options: &SearchOptions,
+) -> Result<SearchPattern, FenrisError> {
+    let pattern = if options.regex {
+        search_term.to_string()
+    } else {
+        regex::escape(search_term)
+    };
 
-    if search_term.trim().is_empty() {
-        return Ok(SearchResult {
-            count: 0,
-            matches: Vec::new(),
-            duration_ms: 0,
-        });
-    }
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .dot_matches_new_line(options.multiline)
+        .build()
+        .map_err(|e| FenrisError::validation("search.invalid_regex", &[("error", &e)]))?;
+    Ok(SearchPattern {
+        regex,
+        whole_word: options.whole_word,
+    })
+}
+
+fn is_whole_word(text: &str, m: &Match) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    !text[..m.start()].chars().next_back().is_some_and(is_word)
+        && !text[m.end()..].chars().next().is_some_and(is_word)
+}
 
+fn search_lines(
+    text: &str,
+    pattern: &SearchPattern,
+    limit: usize,
+) -> (Vec<SearchMatch>, usize, bool) {
     let mut matches = Vec::new();
     let mut total_count = 0;
-    let search_term_lower = search_term.to_lowercase();
 
-    for (line_number, line) in json_response.lines().enumerate() {
-        let line_lower = line.to_lowercase();
-        let mut start = 0;
+    let estimated_lines = text.len() / 80;
+    matches.reserve(estimated_lines.min(1000));
+
+    for (line_number, line) in text.lines().enumerate() {
         let mut line_matches = Vec::new();
+        for m in pattern.find_iter(line) {
+            if total_count == limit {
+                push_line_match(&mut matches, line_number, line, line_matches);
+                return (matches, total_count, true);
+            }
+            total_count += 1;
+            line_matches.push(MatchRange {
+                start: m.start(),
+                end: m.end(),
+            });
+        }
+        push_line_match(&mut matches, line_number, line, line_matches);
+    }
 
-        while let Some(pos) = line_lower[start..].find(&search_term_lower) {
-            let absolute_pos = start + pos;
-            let match_range = MatchRange {
-                start: absolute_pos,
-                end: absolute_pos + search_term.len(),
-            };
+    (matches, total_count, false)
+}
 
-            line_matches.push(match_range);
-            total_count += 1;
-            start = absolute_pos + search_term.len();
+// Matches that span lines are reported on their first line, with `line` holding every line they
+// touch so the ranges stay valid offsets into it
+fn search_multiline(
+    text: &str,
+    pattern: &SearchPattern,
+    limit: usize,
+) -> (Vec<SearchMatch>, usize, bool) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+        .collect();
+    let line_end = |index: usize| {
+        line_starts
+            .get(index + 1)
+            .map(|next| next - 1)
+            .unwrap_or(text.len())
+    };
 
-            // Performance optimization for large files
-            if total_count > 10000 {
-                break;
-            }
+    let mut matches: Vec<SearchMatch> = Vec::new();
+    let mut total_count = 0;
+    for m in pattern.find_iter(text) {
+        if total_count == limit {
+            return (matches, total_count, true);
         }
+        total_count += 1;
 
-        if !line_matches.is_empty() {
-            matches.push(SearchMatch {
-                line_number: line_number + 1,
-                line: line.to_string(),
-                matches: line_matches,
-            });
+        let first = line_starts.partition_point(|start| *start <= m.start()) - 1;
+        let last = line_starts.partition_point(|start| *start < m.end()) - 1;
+        let offset = line_starts[first];
+        let range = MatchRange {
+            start: m.start() - offset,
+            end: m.end() - offset,
+        };
+
+        match matches.last_mut() {
+            Some(previous) if previous.line_number == first + 1 => {
+                let end = line_end(last).max(offset + previous.line.len());
+                previous.line = text[offset..end].trim_end_matches('\r').to_string();
+                previous.matches.push(range);
+            }
+            _ => matches.push(SearchMatch {
+                line_number: first + 1,
+                line: text[offset..line_end(last)]
+                    .trim_end_matches('\r')
+                    .to_string(),
+                matches: vec![range],
+            }),
         }
+    }
+
+    (matches, total_count, false)
+}
+
+fn push_line_match(
+    matches: &mut Vec<SearchMatch>,
+    line_number: usize,
+    line: &str,
+    line_matches: Vec<MatchRange>,
+) {
+    if !line_matches.is_empty() {
+        matches.push(SearchMatch {
+            line_number: line_number + 1,
+            line: line.to_string(),
+            matches: line_matches,
+        });
+    }
+}
 
-        if total_count > 10000 {
+// Replaces the same matches a search reports, so empty and partial-word matches are left alone
+fn replace_limited(
+    text: &str,
+    pattern: &SearchPattern,
+    replacement: &str,
+    expand: bool,
+    limit: usize,
+) -> (String, usize) {
+    let mut output = String::with_capacity(text.len());
+    let mut last_end = 0;
+    let mut count = 0;
+
+    for captures in pattern.regex.captures_iter(text) {
+        if count == limit {
             break;
         }
-    }
+        let m = captures.get(0).expect("group 0 is always set");
+        if m.is_empty() || (pattern.whole_word && !is_whole_word(text, &m)) {
+            continue;
+        }
 
-    let duration = start_time.elapsed();
+        output.push_str(&text[last_end..m.start()]);
+        if expand {
+            captures.expand(replacement, &mut output);
+        } else {
+            output.push_str(replacement);
+        }
+        last_end = m.end();
+        count += 1;
+    }
 
-    Ok(SearchResult {
-        count: total_count,
-        matches,
-        duration_ms: duration.as_millis(),
-    })
-}
\ No newline at end of file
+    output.push_str(&text[last_end..]);
+    (output, count)
+}
@@ -1,37 +1,66 @@
 use crate::cli::{
-    cli_date_now, cli_date_without_hours, cli_design, cli_help_command, cli_show_dir,
+    cli_date_now, cli_date_without_hours, cli_design, complete_shell_input, get_shell_cwd,
+    run_shell_command, run_startup_script,
 };
+use crate::i18n::{get_locale, set_locale};
 use crate::json::{
-    delete_json_file, fetch_json, format_json, get_json_file, list_json_files, perform_search,
-    save_json_file,
+    add_json_watch, analyze_json, apply_json_patch, clear_api_history, close_json_index,
+    convert_from_json, convert_to_json, delete_api_collection, delete_api_environment,
+    delete_api_request, delete_json_file, delete_json_schema, diff_json, diff_json_with_saved,
+    duplicate_json_document, export_api_collection, fetch_json, find_tabular_arrays, format_json,
+    generate_types_from_json, get_json_children, get_json_document_version, get_json_file,
+    get_json_node, get_json_schema, get_json_watch_snapshots, import_api_collection,
+    import_legacy_json_store, index_json_file, infer_json_schema, list_api_collections,
+    list_api_environments, list_api_history, list_json_document_versions, list_json_documents,
+    list_json_files, list_json_schemas, list_json_watches, minify_json, pause_json_watch,
+    query_json, register_json_schema, remove_json_watch, rename_json_document, replace_text,
+    restore_json_watches, rollback_json_document, run_saved_api_request, save_api_environment,
+    save_api_request, save_json_file, search_json_file, search_text, send_api_request,
+    set_json_document_tags, validate_json, validate_json_with_schema, ApiStoreLock,
+    JsonIndexManager, JsonWatchManager,
+};
+use crate::ollama::{
+    handle_shell_ai_status_command, list_of_models, ollama_api_call, ollama_health_check, read_file,
+};
+use crate::sqlite::{
+    create_single_note, create_tag, delete_folder_and_note_sqlite, delete_folder_by_name_sqlite,
+    delete_folder_sqlite, delete_note_by_name_sqlite, delete_single_note, get_all_folders,
+    get_all_single_note, get_note_by_id_sqlite, get_notes_by_folder_sqlite,
+    get_single_note_by_id_sqlite, insert_or_ignore_note_tag, save_folder_sqlite,
+    save_note_to_folder_sqlite, select_all_tags_by_name, select_all_tags_where_name,
+    sqlite_migrations, update_folder_by_id_sqlite, update_folder_by_name_sqlite,
+    update_folder_sqlite, update_note_content_sqlite, update_single_note,
+    update_single_note_content_sqlite, vacuum_sqlite, APP_DATABASE,
 };
-use crate::ollama::{list_of_models, ollama_api_call, read_file};
-use crate::sqlite::{create_single_note, create_tag, delete_folder_and_note_sqlite, delete_folder_by_name_sqlite, delete_folder_sqlite, delete_note_by_name_sqlite, delete_single_note, get_all_folders, get_all_single_note, get_note_by_id_sqlite, get_notes_by_folder_sqlite, get_single_note_by_id_sqlite, save_folder_sqlite, save_note_to_folder_sqlite, select_all_tags_by_name, select_all_tags_where_name, sqlite_migrations, update_folder_by_id_sqlite, update_folder_by_name_sqlite, update_folder_sqlite, update_note_content_sqlite, update_single_note, update_single_note_content_sqlite, vacuum_sqlite};
 use crate::state::{
-    auto_save_folder_note, auto_save_single_note, calculate, editor_state, get_add_note_state,
-    get_folder_items_state, get_folder_note_by_id, get_folder_state, get_note_state,
-    get_single_note_by_id, get_vec_history, is_markdown_full_screen, reset_add_note_state,
-    reset_folder_items_state, reset_folder_state, reset_note_state, set_add_note_state,
-    set_char_count_for_markdown, set_editor_state, set_folder_items_state, set_folder_name,
-    set_markdown_content, set_note_name, set_vec_history, set_word_count_for_markdown,
-    toggle_view_mode, update_add_note_field, update_folder_items_field, validate_folder_name,
-    validate_note_name, AddNoteManager, AddNoteState, Counter, FolderItemsManager,
-    FolderItemsState, FolderManager, FolderState, MarkdownPreviewManager, MarkdownState,
-    NoteManager, NoteState, ShellManager, ShellState,
+    auto_save_folder_note, auto_save_single_note, calculate, delete_shell_history, editor_state,
+    get_add_note_state, get_folder_items_state, get_folder_note_by_id, get_folder_state,
+    get_note_state, get_single_note_by_id, get_vec_history, is_markdown_full_screen,
+    load_shell_history, reset_add_note_state, reset_folder_items_state, reset_folder_state,
+    reset_note_state, search_shell_history, set_add_note_state, set_char_count_for_markdown,
+    set_editor_state, set_folder_items_state, set_folder_name, set_markdown_content, set_note_name,
+    set_word_count_for_markdown, toggle_view_mode, update_add_note_field,
+    update_folder_items_field, validate_folder_name, validate_note_name, AddNoteManager,
+    AddNoteState, Counter, FolderItemsManager, FolderItemsState, FolderManager, FolderState,
+    MarkdownPreviewManager, MarkdownState, NoteManager, NoteState, ShellManager, ShellState,
 };
 use crate::store::{
-    get_available_models_with_custom, handle_shell_ai_model_command, handle_shell_theme_command,
+    format_stored_date, get_available_models_with_custom, get_date_settings,
+    handle_shell_ai_model_command, handle_shell_theme_command, set_date_locale, set_date_timezone,
     store_and_get_ai_model, store_and_get_theme, store_and_set_ai_model, store_and_set_theme,
 };
 use crate::theme::{get_theme, list_of_themes, set_theme};
 use crate::ui_helpers::{
-    delete_folder_dialog, delete_single_note_dialog, pick_json_file, save_json_as_file,
+    delete_folder_dialog, delete_single_note_dialog, pick_json_file, pick_json_file_path,
+    save_json_as_file,
 };
 use std::sync::Mutex;
 use tauri::{generate_context, Builder};
 use tauri_plugin_dialog::init;
 
 mod cli;
+mod error;
+mod i18n;
 mod json;
 mod ollama;
 mod sqlite;
@@ -40,6 +69,8 @@ mod store;
 mod theme;
 mod ui_helpers;
 
+pub use cli::run_headless;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     Builder::default()
@@ -58,19 +89,40 @@ pub fn run() {
         .manage(ShellManager {
             state: Mutex::new(ShellState::default()),
         })
+        .manage(JsonWatchManager::default())
+        .manage(JsonIndexManager::default())
+        .manage(ApiStoreLock::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(
             tauri_plugin_sql::Builder::default()
-                .add_migrations("sqlite:fenris_app_notes.db", sqlite_migrations())
+                .add_migrations(APP_DATABASE, sqlite_migrations())
                 .build(),
         )
         .plugin(init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        .setup(|app| {
+            if let Err(error) = restore_json_watches(app.handle()) {
+                eprintln!("fenris: JSON watches could not be restored: {}", error);
+            }
+            if let Err(error) = load_shell_history(app.handle()) {
+                eprintln!("fenris: shell history could not be loaded: {}", error);
+            }
+            // Finishes before any command runs, so listing never misses documents still in the old store.
+            // A failed import keeps store.json and is retried on the next launch
+            if let Err(error) =
+                tauri::async_runtime::block_on(import_legacy_json_store(app.handle().clone()))
+            {
+                eprintln!("fenris: legacy JSON store import failed: {}", error);
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
-            cli_help_command,
             cli_date_now,
-            cli_show_dir,
             cli_design,
+            run_shell_command,
+            complete_shell_input,
+            run_startup_script,
+            get_shell_cwd,
             fetch_json,
             format_json,
             ollama_api_call,
@@ -82,7 +134,8 @@ pub fn run() {
             get_json_file,
             delete_json_file,
             delete_single_note_dialog,
-            perform_search,
+            search_text,
+            replace_text,
             cli_date_without_hours,
             delete_folder_dialog,
             delete_single_note,
@@ -129,8 +182,9 @@ pub fn run() {
             auto_save_single_note,
             get_folder_note_by_id,
             get_single_note_by_id,
-            set_vec_history,
             get_vec_history,
+            search_shell_history,
+            delete_shell_history,
             set_theme,
             get_theme,
             list_of_themes,
@@ -149,7 +203,63 @@ pub fn run() {
             select_all_tags_by_name,
             create_tag,
             select_all_tags_where_name,
-            insert_or_ignore_note_tag
+            insert_or_ignore_note_tag,
+            ollama_health_check,
+            handle_shell_ai_status_command,
+            minify_json,
+            validate_json,
+            register_json_schema,
+            list_json_schemas,
+            get_json_schema,
+            delete_json_schema,
+            validate_json_with_schema,
+            infer_json_schema,
+            query_json,
+            diff_json,
+            diff_json_with_saved,
+            apply_json_patch,
+            send_api_request,
+            list_api_collections,
+            save_api_request,
+            delete_api_request,
+            delete_api_collection,
+            export_api_collection,
+            import_api_collection,
+            list_api_environments,
+            save_api_environment,
+            delete_api_environment,
+            run_saved_api_request,
+            list_api_history,
+            clear_api_history,
+            add_json_watch,
+            pause_json_watch,
+            list_json_watches,
+            remove_json_watch,
+            get_json_watch_snapshots,
+            index_json_file,
+            close_json_index,
+            get_json_children,
+            get_json_node,
+            search_json_file,
+            pick_json_file_path,
+            analyze_json,
+            convert_from_json,
+            convert_to_json,
+            find_tabular_arrays,
+            generate_types_from_json,
+            list_json_documents,
+            rename_json_document,
+            duplicate_json_document,
+            list_json_document_versions,
+            get_json_document_version,
+            rollback_json_document,
+            set_json_document_tags,
+            get_date_settings,
+            set_date_timezone,
+            set_date_locale,
+            format_stored_date,
+            get_locale,
+            set_locale
         ])
         .run(generate_context!())
         .expect("error while running Fenris application");
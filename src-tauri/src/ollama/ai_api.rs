@@ -1,3 +1,4 @@
+use crate::error::FenrisError;
 use fs::read_to_string;
 use html::push_html;
 use pulldown_cmark::{html, Options, Parser};
@@ -13,13 +14,13 @@ pub async fn ollama_api_call(
     prompt: String,
     model: String,
     file_content: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, FenrisError> {
     let ollama_base_url = "http://localhost:11434/api/chat";
 
     let client = Client::builder()
         .timeout(Duration::from_secs(300))
         .build()
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| FenrisError::network("http.client_failed", &[("error", &e)]))?;
 
     // Combine file content with prompt if provided
     let final_prompt = if let Some(content) = file_content {
@@ -44,16 +45,25 @@ pub async fn ollama_api_call(
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(|e| {
+            if e.is_connect() {
+                FenrisError::network("ai.connect_failed", &[])
+            } else {
+                FenrisError::network("ai.network_error", &[("error", &e)])
+            }
+        })?;
 
     if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(FenrisError::network(
+            "ai.http_error",
+            &[("status", &response.status())],
+        ));
     }
 
     let json_res: Value = response
         .json()
         .await
-        .map_err(|e| format!("JSON parse error: {}", e))?;
+        .map_err(|e| FenrisError::network("ai.invalid_response", &[("error", &e)]))?;
 
     if let Some(content) = json_res["message"]["content"].as_str() {
         let markdown_content = content.trim();
@@ -62,30 +72,30 @@ pub async fn ollama_api_call(
     } else {
         if let Some(done) = json_res["done"].as_bool() {
             if done {
-                Err("Request completed but no content received".to_string())
+                Err(FenrisError::network("ai.empty_response", &[]))
             } else {
-                Err("Request still processing".to_string())
+                Err(FenrisError::network("ai.still_processing", &[]))
             }
         } else {
-            Err("Unexpected response format from Ollama".to_string())
+            Err(FenrisError::network("ai.unexpected_format", &[]))
         }
     }
 }
 
 #[command]
-pub async fn read_file(file_path: String) -> Result<String, String> {
+pub async fn read_file(file_path: String) -> Result<String, FenrisError> {
     let path = Path::new(&file_path);
 
     if !path.exists() {
-        return Err("File does not exist".to_string());
+        return Err(FenrisError::not_found("file.not_found", &[]));
     }
 
     // Check if it's a file (not a directory)
     if !path.is_file() {
-        return Err("Path is not a file".to_string());
+        return Err(FenrisError::io("file.not_a_file", &[]));
     }
 
-    read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))
+    read_to_string(&file_path).map_err(|e| FenrisError::io("file.read_failed", &[("error", &e)]))
 }
 
 fn markdown_to_html(markdown: &str) -> String {
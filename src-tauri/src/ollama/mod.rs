@@ -1,5 +1,7 @@
 mod ai_api;
 mod list_of_ai_models;
+mod ollama_health_check;
 
 pub use ai_api::*;
-pub use list_of_ai_models::*;
\ No newline at end of file
+pub use list_of_ai_models::*;
+pub use ollama_health_check::*;
@@ -0,0 +1,375 @@
+use crate::error::FenrisError;
+use crate::i18n::t;
+use crate::store::store_and_get_ai_model;
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle};
+
+pub const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+    Skipped,
+}
+
+#[derive(Serialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LoadedModel {
+    pub name: String,
+    pub size_bytes: u64,
+    pub vram_bytes: u64,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PromptLatency {
+    pub model: String,
+    pub latency_ms: u128,
+    pub load_duration_ms: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct OllamaHealthReport {
+    pub base_url: String,
+    pub reachable: bool,
+    pub version: Option<String>,
+    pub loaded_models: Vec<LoadedModel>,
+    pub test_prompt: Option<PromptLatency>,
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthCheck {
+    fn new(name: &str, status: CheckStatus, message: String, hint: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            message,
+            hint: hint.map(|h| h.to_string()),
+        }
+    }
+}
+
+#[command]
+pub async fn ollama_health_check(model: Option<String>) -> Result<OllamaHealthReport, FenrisError> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| FenrisError::network("http.client_failed", &[("error", &e)]))?;
+
+    let mut report = OllamaHealthReport {
+        base_url: OLLAMA_BASE_URL.to_string(),
+        reachable: false,
+        version: None,
+        loaded_models: Vec::new(),
+        test_prompt: None,
+        checks: Vec::new(),
+    };
+
+    // Reachability and version share the same request
+    match client
+        .get(format!("{}/api/version", OLLAMA_BASE_URL))
+        .send()
+        .await
+    {
+        Ok(response) if response.status().is_success() => {
+            report.reachable = true;
+            let body: Value = response.json().await.unwrap_or(Value::Null);
+            report.version = body["version"].as_str().map(|v| v.to_string());
+
+            report.checks.push(HealthCheck::new(
+                "server",
+                CheckStatus::Ok,
+                t("health.server_running", &[("url", &OLLAMA_BASE_URL)]),
+                None,
+            ));
+            report.checks.push(match &report.version {
+                Some(version) => HealthCheck::new(
+                    "version",
+                    CheckStatus::Ok,
+                    t("health.server_version", &[("version", version)]),
+                    None,
+                ),
+                None => HealthCheck::new(
+                    "version",
+                    CheckStatus::Warning,
+                    t("health.version_missing", &[]),
+                    Some(t("health.hints.update", &[]).as_str()),
+                ),
+            });
+        }
+        Ok(response) => {
+            report.checks.push(HealthCheck::new(
+                "server",
+                CheckStatus::Error,
+                t(
+                    "health.unexpected_status",
+                    &[("status", &response.status())],
+                ),
+                Some(t("health.hints.port_in_use", &[]).as_str()),
+            ));
+        }
+        Err(e) => {
+            report.checks.push(connection_failure("server", &e));
+        }
+    }
+
+    if !report.reachable {
+        report.checks.push(HealthCheck::new(
+            "models",
+            CheckStatus::Skipped,
+            t("health.skipped_unreachable", &[]),
+            None,
+        ));
+        report.checks.push(HealthCheck::new(
+            "prompt",
+            CheckStatus::Skipped,
+            t("health.skipped_unreachable", &[]),
+            None,
+        ));
+        return Ok(report);
+    }
+
+    match fetch_loaded_models(&client).await {
+        Ok(models) => {
+            let message = if models.is_empty() {
+                t("health.no_model_loaded", &[])
+            } else {
+                t("health.models_loaded", &[("count", &models.len())])
+            };
+            report.loaded_models = models;
+            report
+                .checks
+                .push(HealthCheck::new("models", CheckStatus::Ok, message, None));
+        }
+        Err(e) => {
+            report.checks.push(HealthCheck::new(
+                "models",
+                CheckStatus::Warning,
+                t("health.models_failed", &[("error", &e)]),
+                Some(t("health.hints.update_ps", &[]).as_str()),
+            ));
+        }
+    }
+
+    match model {
+        Some(model) if !model.trim().is_empty() => match measure_prompt_latency(&model).await {
+            Ok(latency) => {
+                report.checks.push(HealthCheck::new(
+                    "prompt",
+                    CheckStatus::Ok,
+                    t(
+                        "health.prompt_answered",
+                        &[("model", &model), ("latency", &latency.latency_ms)],
+                    ),
+                    None,
+                ));
+                report.test_prompt = Some(latency);
+            }
+            Err(check) => report.checks.push(check),
+        },
+        _ => {
+            report.checks.push(HealthCheck::new(
+                "prompt",
+                CheckStatus::Skipped,
+                t("health.no_model_selected", &[]),
+                Some(t("health.hints.choose_model", &[]).as_str()),
+            ));
+        }
+    }
+
+    Ok(report)
+}
+
+#[command]
+pub async fn handle_shell_ai_status_command(app: AppHandle) -> Result<String, FenrisError> {
+    let model = store_and_get_ai_model(app).await.ok();
+    let report = ollama_health_check(model).await?;
+    Ok(format_health_report(&report))
+}
+
+async fn fetch_loaded_models(client: &Client) -> Result<Vec<LoadedModel>, FenrisError> {
+    let response = client
+        .get(format!("{}/api/ps", OLLAMA_BASE_URL))
+        .send()
+        .await
+        .map_err(|e| FenrisError::network("ai.network_error", &[("error", &e)]))?;
+
+    if !response.status().is_success() {
+        return Err(FenrisError::network(
+            "ai.http_error",
+            &[("status", &response.status())],
+        ));
+    }
+
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| FenrisError::network("ai.invalid_response", &[("error", &e)]))?;
+
+    let models = body["models"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .map(|m| LoadedModel {
+                    name: m["name"].as_str().unwrap_or("unknown").to_string(),
+                    size_bytes: m["size"].as_u64().unwrap_or(0),
+                    vram_bytes: m["size_vram"].as_u64().unwrap_or(0),
+                    expires_at: m["expires_at"].as_str().map(|s| s.to_string()),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(models)
+}
+
+async fn measure_prompt_latency(model: &str) -> Result<PromptLatency, HealthCheck> {
+    // Loading a model from disk can take a while, so the prompt gets its own timeout
+    let client = Client::builder()
+        .timeout(Duration::from_secs(120))
+        .build()
+        .map_err(|e| HealthCheck::new("prompt", CheckStatus::Error, e.to_string(), None))?;
+
+    let body = json!({
+        "model": model,
+        "prompt": "ping",
+        "stream": false,
+        "options": { "num_predict": 1 }
+    });
+
+    let start_time = Instant::now();
+
+    let response = client
+        .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| connection_failure("prompt", &e))?;
+
+    let latency_ms = start_time.elapsed().as_millis();
+
+    match response.status() {
+        StatusCode::NOT_FOUND => {
+            return Err(HealthCheck::new(
+                "prompt",
+                CheckStatus::Error,
+                t("health.model_missing", &[("model", &model)]),
+                Some(t("health.hints.pull", &[("model", &model)]).as_str()),
+            ));
+        }
+        status if !status.is_success() => {
+            let detail: Value = response.json().await.unwrap_or(Value::Null);
+            let message = detail["error"]
+                .as_str()
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| t("health.http_error", &[("status", &status)]));
+            return Err(HealthCheck::new(
+                "prompt",
+                CheckStatus::Error,
+                message,
+                Some(t("health.hints.server_logs", &[]).as_str()),
+            ));
+        }
+        _ => {}
+    }
+
+    let json_res: Value = response.json().await.unwrap_or(Value::Null);
+
+    Ok(PromptLatency {
+        model: model.to_string(),
+        latency_ms,
+        // Ollama reports durations in nanoseconds
+        load_duration_ms: json_res["load_duration"].as_u64().map(|ns| ns / 1_000_000),
+    })
+}
+
+fn connection_failure(name: &str, error: &reqwest::Error) -> HealthCheck {
+    if error.is_connect() {
+        HealthCheck::new(
+            name,
+            CheckStatus::Error,
+            t("health.connect_failed", &[("url", &OLLAMA_BASE_URL)]),
+            Some(t("health.hints.start", &[]).as_str()),
+        )
+    } else if error.is_timeout() {
+        HealthCheck::new(
+            name,
+            CheckStatus::Error,
+            t("health.timeout", &[]),
+            Some(t("health.hints.busy", &[]).as_str()),
+        )
+    } else {
+        HealthCheck::new(
+            name,
+            CheckStatus::Error,
+            t("health.network_error", &[("error", &error)]),
+            Some(t("health.hints.blocked", &[]).as_str()),
+        )
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+pub fn format_health_report(report: &OllamaHealthReport) -> String {
+    let mut lines = vec![t("health.report.title", &[("url", &report.base_url)])];
+
+    for check in &report.checks {
+        let marker = match check.status {
+            CheckStatus::Ok => "[ok]",
+            CheckStatus::Warning => "[warn]",
+            CheckStatus::Error => "[error]",
+            CheckStatus::Skipped => "[skip]",
+        };
+        lines.push(format!(
+            "  {:<8} {:<8} {}",
+            marker, check.name, check.message
+        ));
+        if let Some(hint) = &check.hint {
+            lines.push(format!("  {:<8} {:<8} -> {}", "", "", hint));
+        }
+    }
+
+    if !report.loaded_models.is_empty() {
+        lines.push(t("health.report.loaded_models", &[]));
+        for model in &report.loaded_models {
+            lines.push(format!(
+                "  {:<30} {}",
+                model.name,
+                t(
+                    "health.report.model_size",
+                    &[
+                        ("total", &format!("{:>10}", format_bytes(model.size_bytes))),
+                        ("vram", &format!("{:>10}", format_bytes(model.vram_bytes))),
+                    ],
+                )
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
@@ -1,7 +1,9 @@
 mod sqlite_commands_helper;
 mod sqlite_migrations;
+mod sqlite_pool;
 mod sqlite_tags_commands;
 
 pub use sqlite_commands_helper::*;
 pub use sqlite_migrations::*;
+pub use sqlite_pool::*;
 pub use sqlite_tags_commands::*;
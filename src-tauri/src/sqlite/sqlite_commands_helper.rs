@@ -1,8 +1,9 @@
+use crate::error::FenrisError;
 use chrono::{DateTime, Utc};
 use tauri::command;
 
 #[command]
-pub fn save_folder_sqlite(folder_name: String) -> Result<String, ()> {
+pub fn save_folder_sqlite(folder_name: String) -> Result<String, FenrisError> {
     let now: DateTime<Utc> = Utc::now();
     let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
@@ -15,27 +16,27 @@ pub fn save_folder_sqlite(folder_name: String) -> Result<String, ()> {
 }
 
 #[command]
-pub fn delete_folder_sqlite(folder_id: usize) -> Result<String, ()> {
+pub fn delete_folder_sqlite(folder_id: usize) -> Result<String, FenrisError> {
     let delete_folder_command = format!("DELETE FROM folders WHERE id = {}", folder_id);
     Ok(delete_folder_command)
 }
 
 #[command]
-pub fn delete_folder_by_name_sqlite(folder_name: String) -> Result<String, ()> {
+pub fn delete_folder_by_name_sqlite(folder_name: String) -> Result<String, FenrisError> {
     let delete_folder_by_name_command =
         format!("SELECT id FROM folders WHERE name = '{}'", folder_name);
     Ok(delete_folder_by_name_command)
 }
 
 #[command]
-pub fn delete_folder_and_note_sqlite(folder_id: usize) -> Result<String, ()> {
+pub fn delete_folder_and_note_sqlite(folder_id: usize) -> Result<String, FenrisError> {
     let delete_folder_and_notes_command =
         format!("DELETE FROM note WHERE folder_id = {}", folder_id);
     Ok(delete_folder_and_notes_command)
 }
 
 #[command]
-pub fn update_folder_sqlite(folder_name: String, folder_id: usize) -> Result<String, ()> {
+pub fn update_folder_sqlite(folder_name: String, folder_id: usize) -> Result<String, FenrisError> {
     let update_folder_and_notes_command = format!(
         "UPDATE folders SET name = '{}' WHERE id = {}",
         folder_name, folder_id
@@ -44,7 +45,7 @@ pub fn update_folder_sqlite(folder_name: String, folder_id: usize) -> Result<Str
 }
 
 #[command]
-pub fn update_folder_by_name_sqlite(current_folder_name: String) -> Result<String, ()> {
+pub fn update_folder_by_name_sqlite(current_folder_name: String) -> Result<String, FenrisError> {
     let update_folder_by_name_command = format!(
         "SELECT id FROM folders WHERE name = '{}'",
         current_folder_name
@@ -53,7 +54,10 @@ pub fn update_folder_by_name_sqlite(current_folder_name: String) -> Result<Strin
 }
 
 #[command]
-pub fn update_folder_by_id_sqlite(new_folder_name: String, folder_id: usize) -> Result<String, ()> {
+pub fn update_folder_by_id_sqlite(
+    new_folder_name: String,
+    folder_id: usize,
+) -> Result<String, FenrisError> {
     let update_folder_by_name_command = format!(
         "UPDATE folders SET name = '{}' WHERE id = {}",
         new_folder_name, folder_id
@@ -62,7 +66,7 @@ pub fn update_folder_by_id_sqlite(new_folder_name: String, folder_id: usize) ->
 }
 
 #[command]
-pub fn get_all_folders() -> Result<String, ()> {
+pub fn get_all_folders() -> Result<String, FenrisError> {
     let get_all_folders_command = "SELECT * FROM folders ORDER BY date_created DESC".to_string();
     Ok(get_all_folders_command)
 }
@@ -73,7 +77,7 @@ pub fn save_note_to_folder_sqlite(
     title: String,
     content: String,
     folder_id: usize,
-) -> Result<String, ()> {
+) -> Result<String, FenrisError> {
     let now: DateTime<Utc> = Utc::now();
     let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
@@ -86,7 +90,7 @@ pub fn save_note_to_folder_sqlite(
 }
 
 #[command]
-pub fn get_notes_by_folder_sqlite(folder_id: usize) -> Result<String, ()> {
+pub fn get_notes_by_folder_sqlite(folder_id: usize) -> Result<String, FenrisError> {
     let get_notes_command = format!(
         "SELECT * FROM note WHERE folder_id = {} ORDER BY date_created DESC",
         folder_id
@@ -99,7 +103,7 @@ pub fn update_note_in_folder_sqlite(
     note_id: usize,
     title: String,
     content: String,
-) -> Result<String, ()> {
+) -> Result<String, FenrisError> {
     let now: DateTime<Utc> = Utc::now();
     let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
@@ -111,14 +115,14 @@ pub fn update_note_in_folder_sqlite(
 }
 
 #[command]
-pub fn delete_note_from_folder_sqlite(note_id: usize) -> Result<String, ()> {
+pub fn delete_note_from_folder_sqlite(note_id: usize) -> Result<String, FenrisError> {
     let delete_note_command = format!("DELETE FROM note WHERE id = {}", note_id);
     Ok(delete_note_command)
 }
 
 // Single Note Commands
 #[command]
-pub fn create_single_note(note_name: String, content: String) -> Result<String, ()> {
+pub fn create_single_note(note_name: String, content: String) -> Result<String, FenrisError> {
     let now: DateTime<Utc> = Utc::now();
     let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
@@ -131,14 +135,14 @@ pub fn create_single_note(note_name: String, content: String) -> Result<String,
 }
 
 #[command]
-pub fn delete_single_note(note_id: usize) -> Result<String, ()> {
+pub fn delete_single_note(note_id: usize) -> Result<String, FenrisError> {
     let delete_note_command = format!("DELETE FROM single_notes WHERE id = {}", note_id);
 
     Ok(delete_note_command)
 }
 
 #[command]
-pub fn update_single_note(new_note_name: String, note_id: usize) -> Result<String, ()> {
+pub fn update_single_note(new_note_name: String, note_id: usize) -> Result<String, FenrisError> {
     let update_note_command = format!(
         "UPDATE single_notes SET title = '{}' WHERE id = {}",
         new_note_name, note_id
@@ -148,7 +152,7 @@ pub fn update_single_note(new_note_name: String, note_id: usize) -> Result<Strin
 }
 
 #[command]
-pub fn get_all_single_note() -> Result<String, ()> {
+pub fn get_all_single_note() -> Result<String, FenrisError> {
     let get_all_single_note_command =
         "SELECT * FROM single_notes ORDER BY date_created DESC".to_string();
 
@@ -156,19 +160,19 @@ pub fn get_all_single_note() -> Result<String, ()> {
 }
 
 #[command]
-pub fn get_note_by_id_sqlite(note_id: usize) -> Result<String, ()> {
+pub fn get_note_by_id_sqlite(note_id: usize) -> Result<String, FenrisError> {
     let get_note_command = format!("SELECT * FROM note WHERE id = {}", note_id);
     Ok(get_note_command)
 }
 
 #[command]
-pub fn get_single_note_by_id_sqlite(note_id: usize) -> Result<String, ()> {
+pub fn get_single_note_by_id_sqlite(note_id: usize) -> Result<String, FenrisError> {
     let get_single_note_command = format!("SELECT * FROM single_notes WHERE id = {}", note_id);
     Ok(get_single_note_command)
 }
 
 #[command]
-pub fn update_note_content_sqlite(note_id: usize, content: String) -> Result<String, ()> {
+pub fn update_note_content_sqlite(note_id: usize, content: String) -> Result<String, FenrisError> {
     let now: DateTime<Utc> = Utc::now();
     let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
@@ -182,7 +186,10 @@ pub fn update_note_content_sqlite(note_id: usize, content: String) -> Result<Str
 }
 
 #[command]
-pub fn update_single_note_content_sqlite(note_id: usize, content: String) -> Result<String, ()> {
+pub fn update_single_note_content_sqlite(
+    note_id: usize,
+    content: String,
+) -> Result<String, FenrisError> {
     let now: DateTime<Utc> = Utc::now();
     let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
@@ -196,14 +203,14 @@ pub fn update_single_note_content_sqlite(note_id: usize, content: String) -> Res
 }
 
 #[command]
-pub fn delete_note_by_name_sqlite(note_name: String) -> Result<String, ()> {
+pub fn delete_note_by_name_sqlite(note_name: String) -> Result<String, FenrisError> {
     let delete_note_by_name_command =
         format!("SELECT id FROM single_notes WHERE title = '{}'", note_name);
     Ok(delete_note_by_name_command)
 }
 
 #[command]
-pub fn vacuum_sqlite() -> Result<String, ()> {
+pub fn vacuum_sqlite() -> Result<String, FenrisError> {
     let vacuum_db = "VACUUM".to_string();
     Ok(vacuum_db)
-}
\ No newline at end of file
+}
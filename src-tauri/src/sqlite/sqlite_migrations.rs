@@ -85,6 +85,40 @@ pub fn sqlite_migrations() -> Vec<Migration> {
                 CREATE INDEX IF NOT EXISTS idx_tags_name ON tags(name);
             "#,
             kind: MigrationKind::Up,
-        }
+        },
+        Migration {
+            version: 7,
+            description: "create_json_document_tables",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS json_documents (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE,
+                    source_url TEXT DEFAULT NULL,
+                    current_version INTEGER NOT NULL DEFAULT 1,
+                    size_bytes INTEGER NOT NULL DEFAULT 0,
+                    date_created DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+                    date_modified DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS json_document_versions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    document_id INTEGER NOT NULL,
+                    version INTEGER NOT NULL,
+                    content TEXT NOT NULL,
+                    size_bytes INTEGER NOT NULL,
+                    date_created DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+                    UNIQUE(document_id, version),
+                    FOREIGN KEY(document_id) REFERENCES json_documents(id) ON DELETE CASCADE
+                );
+                CREATE TABLE IF NOT EXISTS json_document_tags (
+                    document_id INTEGER NOT NULL,
+                    tag_id INTEGER NOT NULL,
+                    PRIMARY KEY(document_id, tag_id),
+                    FOREIGN KEY(document_id) REFERENCES json_documents(id) ON DELETE CASCADE,
+                    FOREIGN KEY(tag_id) REFERENCES tags(id) ON DELETE CASCADE
+                );
+                CREATE INDEX IF NOT EXISTS idx_json_document_versions_document_id ON json_document_versions(document_id, version);
+            "#,
+            kind: MigrationKind::Up,
+        },
     ]
-}
\ No newline at end of file
+}
@@ -0,0 +1,17 @@
+use crate::error::FenrisError;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+pub const APP_DATABASE: &str = "sqlite:fenris_app_notes.db";
+
+// Borrows the pool the SQL plugin preloads, so Rust and the frontend share one connection pool
+pub async fn app_sqlite_pool(app: &AppHandle) -> Result<Pool<Sqlite>, FenrisError> {
+    let instances = app.state::<DbInstances>();
+    let instances = instances.0.read().await;
+
+    match instances.get(APP_DATABASE) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        None => Err(FenrisError::database("database.not_loaded", &[])),
+    }
+}
@@ -1,19 +1,20 @@
+use crate::error::FenrisError;
 use tauri::command;
 
 #[command]
-pub fn select_all_tags_by_name() -> Result<String, ()> {
+pub fn select_all_tags_by_name() -> Result<String, FenrisError> {
     let sql = "SELECT * FROM tags ORDER BY name".to_string();
     Ok(sql)
 }
 
 #[command]
-pub fn select_all_tags_where_name(name: String) -> Result<String, ()> {
+pub fn select_all_tags_where_name(name: String) -> Result<String, FenrisError> {
     let sql = format!("SELECT * FROM tags WHERE name = '{}'", name);
     Ok(sql)
 }
 
 #[command]
-pub fn create_tag(name: String, color: String) -> Result<String, ()> {
+pub fn create_tag(name: String, color: String) -> Result<String, FenrisError> {
     let sql = format!(
         "INSERT OR IGNORE INTO tags (name, color) VALUES ('{}', '{}')",
         name, color
@@ -26,7 +27,7 @@ pub fn insert_or_ignore_note_tag(
     note_id: usize,
     tag_id: String,
     note_type: String,
-) -> Result<String, ()> {
+) -> Result<String, FenrisError> {
     let sql = format!(
         "INSERT OR IGNORE INTO note_tags (note_id, tag_id, note_type) VALUES ({}, {}, {})",
         note_id, tag_id, note_type
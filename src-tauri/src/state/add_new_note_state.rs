@@ -1,3 +1,4 @@
+use crate::error::{lock, FenrisError};
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::{command, State};
@@ -13,35 +14,49 @@ pub struct AddNoteState {
 pub struct AddNoteManager(pub Mutex<AddNoteState>);
 
 #[command]
-pub fn get_add_note_state(state: State<AddNoteManager>) -> Result<AddNoteState, String> {
-    Ok(state.0.lock().map_err(|e| e.to_string())?.clone())
+pub fn get_add_note_state(state: State<AddNoteManager>) -> Result<AddNoteState, FenrisError> {
+    Ok(lock(&state.0)?.clone())
 }
 
 #[command]
-pub fn set_add_note_state(updates: AddNoteState, state: State<AddNoteManager>) -> Result<AddNoteState, String> {
-    let mut state = state.0.lock().map_err(|e| e.to_string())?;
+pub fn set_add_note_state(
+    updates: AddNoteState,
+    state: State<AddNoteManager>,
+) -> Result<AddNoteState, FenrisError> {
+    let mut state = lock(&state.0)?;
     *state = updates;
     Ok(state.clone())
 }
 
 #[command]
-pub fn update_add_note_field(field: String, value: serde_json::Value, state: State<AddNoteManager>) -> Result<AddNoteState, String> {
-    let mut state = state.0.lock().map_err(|e| e.to_string())?;
+pub fn update_add_note_field(
+    field: String,
+    value: serde_json::Value,
+    state: State<AddNoteManager>,
+) -> Result<AddNoteState, FenrisError> {
+    let mut state = lock(&state.0)?;
 
     match field.as_str() {
-        "new_note_title" => state.new_note_title = serde_json::from_value(value).unwrap_or_default(),
+        "new_note_title" => {
+            state.new_note_title = serde_json::from_value(value).unwrap_or_default()
+        }
         "is_loading" => state.is_loading = serde_json::from_value(value).unwrap_or(false),
         "is_popup_open" => state.is_popup_open = serde_json::from_value(value).unwrap_or(false),
         "error" => state.error = serde_json::from_value(value).unwrap_or_default(),
-        _ => return Err(format!("Unknown field: {}", field)),
+        _ => {
+            return Err(FenrisError::validation(
+                "state.unknown_field",
+                &[("field", &field)],
+            ))
+        }
     }
 
     Ok(state.clone())
 }
 
 #[command]
-pub fn reset_add_note_state(state: State<AddNoteManager>) -> Result<AddNoteState, String> {
-    let mut state = state.0.lock().map_err(|e| e.to_string())?;
+pub fn reset_add_note_state(state: State<AddNoteManager>) -> Result<AddNoteState, FenrisError> {
+    let mut state = lock(&state.0)?;
     *state = AddNoteState::default();
     Ok(state.clone())
-}
\ No newline at end of file
+}
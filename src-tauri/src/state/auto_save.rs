@@ -1,11 +1,9 @@
+use crate::error::FenrisError;
 use chrono::{DateTime, Utc};
 use tauri::command;
 
 #[command]
-pub async fn auto_save_folder_note(
-    note_id: usize,
-    content: String,
-) -> Result<String, String> {
+pub async fn auto_save_folder_note(note_id: usize, content: String) -> Result<String, FenrisError> {
     let now: DateTime<Utc> = Utc::now();
     let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
@@ -19,10 +17,7 @@ pub async fn auto_save_folder_note(
 }
 
 #[command]
-pub async fn auto_save_single_note(
-    note_id: usize,
-    content: String,
-) -> Result<String, String> {
+pub async fn auto_save_single_note(note_id: usize, content: String) -> Result<String, FenrisError> {
     let now: DateTime<Utc> = Utc::now();
     let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
@@ -36,13 +31,13 @@ pub async fn auto_save_single_note(
 }
 
 #[command]
-pub async fn get_folder_note_by_id(note_id: usize) -> Result<String, String> {
+pub async fn get_folder_note_by_id(note_id: usize) -> Result<String, FenrisError> {
     let sql = format!("SELECT * FROM note WHERE id = {}", note_id);
     Ok(sql)
 }
 
 #[command]
-pub async fn get_single_note_by_id(note_id: usize) -> Result<String, String> {
+pub async fn get_single_note_by_id(note_id: usize) -> Result<String, FenrisError> {
     let sql = format!("SELECT * FROM single_notes WHERE id = {}", note_id);
     Ok(sql)
-}
\ No newline at end of file
+}
@@ -1,3 +1,4 @@
+use crate::error::{lock, FenrisError};
 use std::sync::Mutex;
 use tauri::{command, State};
 
@@ -6,18 +7,18 @@ pub struct Counter {
 }
 
 #[command]
-pub fn calculate(method: &str, state: State<Counter>) -> i32 {
-    let mut counter = state.count.lock().unwrap();
+pub fn calculate(method: &str, state: State<Counter>) -> Result<i32, FenrisError> {
+    let mut counter = lock(&state.count)?;
 
     match method {
         "add" => {
             *counter = *counter + 1;
-        },
+        }
         "subtract" => {
             *counter = *counter - 1;
-        },
-        _ => ()
+        }
+        _ => (),
     }
 
-    *counter
-}
\ No newline at end of file
+    Ok(*counter)
+}
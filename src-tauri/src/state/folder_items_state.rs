@@ -1,3 +1,4 @@
+use crate::error::{lock, FenrisError};
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::{command, State};
@@ -26,16 +27,16 @@ pub struct FolderItemsManager(pub Mutex<FolderItemsState>);
 #[command]
 pub fn get_folder_items_state(
     state: State<FolderItemsManager>,
-) -> Result<FolderItemsState, String> {
-    Ok(state.0.lock().map_err(|e| e.to_string())?.clone())
+) -> Result<FolderItemsState, FenrisError> {
+    Ok(lock(&state.0)?.clone())
 }
 
 #[command]
 pub fn set_folder_items_state(
     updates: FolderItemsState,
     state: State<FolderItemsManager>,
-) -> Result<FolderItemsState, String> {
-    let mut state = state.0.lock().map_err(|e| e.to_string())?;
+) -> Result<FolderItemsState, FenrisError> {
+    let mut state = lock(&state.0)?;
     *state = updates;
     Ok(state.clone())
 }
@@ -45,8 +46,8 @@ pub fn update_folder_items_field(
     field: String,
     value: serde_json::Value,
     state: State<FolderItemsManager>,
-) -> Result<FolderItemsState, String> {
-    let mut state = state.0.lock().map_err(|e| e.to_string())?;
+) -> Result<FolderItemsState, FenrisError> {
+    let mut state = lock(&state.0)?;
 
     match field.as_str() {
         "notes" => state.notes = serde_json::from_value(value).unwrap_or_default(),
@@ -58,7 +59,12 @@ pub fn update_folder_items_field(
         "is_expanded" => state.is_expanded = serde_json::from_value(value).unwrap_or(false),
         "error" => state.error = serde_json::from_value(value).unwrap_or_default(),
         "error_message" => state.error_message = serde_json::from_value(value).unwrap_or_default(),
-        _ => return Err(format!("Unknown field: {}", field)),
+        _ => {
+            return Err(FenrisError::validation(
+                "state.unknown_field",
+                &[("field", &field)],
+            ))
+        }
     }
 
     Ok(state.clone())
@@ -67,8 +73,8 @@ pub fn update_folder_items_field(
 #[command]
 pub fn reset_folder_items_state(
     state: State<FolderItemsManager>,
-) -> Result<FolderItemsState, String> {
-    let mut state = state.0.lock().map_err(|e| e.to_string())?;
+) -> Result<FolderItemsState, FenrisError> {
+    let mut state = lock(&state.0)?;
     *state = FolderItemsState::default();
     Ok(state.clone())
-}
\ No newline at end of file
+}
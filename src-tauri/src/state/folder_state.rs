@@ -1,6 +1,7 @@
+use crate::error::{lock, FenrisError};
+use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::{command, State};
-use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FolderState {
@@ -26,8 +27,11 @@ pub struct FolderManager {
 }
 
 #[command]
-pub fn set_folder_name(folder_name: String, state: State<FolderManager>) -> Result<FolderState, String> {
-    let mut folder_state = state.state.lock().unwrap();
+pub fn set_folder_name(
+    folder_name: String,
+    state: State<FolderManager>,
+) -> Result<FolderState, FenrisError> {
+    let mut folder_state = lock(&state.state)?;
     folder_state.folder_name = folder_name;
     folder_state.show_error = false;
     folder_state.error_message.clear();
@@ -36,25 +40,25 @@ pub fn set_folder_name(folder_name: String, state: State<FolderManager>) -> Resu
 }
 
 #[command]
-pub fn validate_folder_name(state: State<FolderManager>) -> Result<FolderState, String> {
-    let folder_state = state.state.lock().unwrap();
+pub fn validate_folder_name(state: State<FolderManager>) -> Result<FolderState, FenrisError> {
+    let folder_state = lock(&state.state)?;
 
     if folder_state.folder_name.trim().is_empty() {
-        return Err("Folder name cannot be empty".to_string());
+        return Err(FenrisError::validation("folder.name_empty", &[]));
     }
 
     Ok(folder_state.clone())
 }
 
 #[command]
-pub fn reset_folder_state(state: State<FolderManager>) -> Result<FolderState, String> {
-    let mut folder_state = state.state.lock().unwrap();
+pub fn reset_folder_state(state: State<FolderManager>) -> Result<FolderState, FenrisError> {
+    let mut folder_state = lock(&state.state)?;
     *folder_state = FolderState::default();
     Ok(folder_state.clone())
 }
 
 #[command]
-pub fn get_folder_state(state: State<FolderManager>) -> Result<FolderState, String> {
-    let folder_state = state.state.lock().unwrap();
+pub fn get_folder_state(state: State<FolderManager>) -> Result<FolderState, FenrisError> {
+    let folder_state = lock(&state.state)?;
     Ok(folder_state.clone())
-}
\ No newline at end of file
+}
@@ -1,3 +1,4 @@
+use crate::error::{lock, FenrisError};
 use std::sync::Mutex;
 use tauri::{command, State};
 
@@ -28,8 +29,8 @@ impl Default for MarkdownState {
 pub struct MarkdownPreviewManager(pub Mutex<MarkdownState>);
 
 #[command]
-pub fn editor_state(state: State<MarkdownPreviewManager>) -> Result<bool, String> {
-    let state = state.0.lock().unwrap();
+pub fn editor_state(state: State<MarkdownPreviewManager>) -> Result<bool, FenrisError> {
+    let state = lock(&state.0)?;
     Ok(state.is_editor_open)
 }
 
@@ -37,8 +38,8 @@ pub fn editor_state(state: State<MarkdownPreviewManager>) -> Result<bool, String
 pub fn set_editor_state(
     is_open: bool,
     state: State<MarkdownPreviewManager>,
-) -> Result<bool, String> {
-    let mut state = state.0.lock().unwrap();
+) -> Result<bool, FenrisError> {
+    let mut state = lock(&state.0)?;
     state.is_editor_open = is_open;
     Ok(state.is_editor_open)
 }
@@ -47,8 +48,8 @@ pub fn set_editor_state(
 pub fn toggle_view_mode(
     mode: String,
     state: State<MarkdownPreviewManager>,
-) -> Result<(bool, bool), String> {
-    let mut state = state.0.lock().unwrap();
+) -> Result<(bool, bool), FenrisError> {
+    let mut state = lock(&state.0)?;
 
     match mode.as_str() {
         "split" => {
@@ -64,7 +65,7 @@ pub fn toggle_view_mode(
             state.is_preview_open = true;
         }
         _ => {
-            return Err("Invalid view mode".to_string());
+            return Err(FenrisError::validation("state.invalid_view_mode", &[]));
         }
     }
 
@@ -75,8 +76,8 @@ pub fn toggle_view_mode(
 pub fn set_markdown_content(
     content: String,
     state: State<MarkdownPreviewManager>,
-) -> Result<String, String> {
-    let mut state = state.0.lock().unwrap();
+) -> Result<String, FenrisError> {
+    let mut state = lock(&state.0)?;
     state.content = content;
     Ok(state.content.clone())
 }
@@ -85,8 +86,8 @@ pub fn set_markdown_content(
 pub fn set_word_count_for_markdown(
     count: usize,
     state: State<MarkdownPreviewManager>,
-) -> Result<String, String> {
-    let mut state = state.0.lock().unwrap();
+) -> Result<String, FenrisError> {
+    let mut state = lock(&state.0)?;
     state.word_count = count;
     Ok(state.word_count.to_string().clone())
 }
@@ -95,8 +96,8 @@ pub fn set_word_count_for_markdown(
 pub fn set_char_count_for_markdown(
     char_count: usize,
     state: State<MarkdownPreviewManager>,
-) -> Result<usize, String> {
-    let mut state = state.0.lock().unwrap();
+) -> Result<usize, FenrisError> {
+    let mut state = lock(&state.0)?;
     state.char_count = char_count;
     Ok(state.char_count)
 }
@@ -105,8 +106,8 @@ pub fn set_char_count_for_markdown(
 pub fn is_markdown_full_screen(
     is_full_screen: bool,
     state: State<MarkdownPreviewManager>,
-) -> Result<bool, String> {
-    let mut state = state.0.lock().unwrap();
+) -> Result<bool, FenrisError> {
+    let mut state = lock(&state.0)?;
     state.is_full_screen = is_full_screen;
     Ok(state.is_full_screen.clone())
-}
\ No newline at end of file
+}
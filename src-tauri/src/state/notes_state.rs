@@ -1,6 +1,7 @@
+use crate::error::{lock, FenrisError};
+use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::{command, State};
-use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NoteState {
@@ -26,8 +27,11 @@ pub struct NoteManager {
 }
 
 #[command]
-pub fn set_note_name(note_name: String, state: State<NoteManager>) -> Result<NoteState, String> {
-    let mut note_state = state.state.lock().unwrap();
+pub fn set_note_name(
+    note_name: String,
+    state: State<NoteManager>,
+) -> Result<NoteState, FenrisError> {
+    let mut note_state = lock(&state.state)?;
     note_state.note_name = note_name;
     note_state.show_error = false;
     note_state.error_message.clear();
@@ -36,25 +40,25 @@ pub fn set_note_name(note_name: String, state: State<NoteManager>) -> Result<Not
 }
 
 #[command]
-pub fn validate_note_name(state: State<NoteManager>) -> Result<NoteState, String> {
-    let note_state = state.state.lock().unwrap();
+pub fn validate_note_name(state: State<NoteManager>) -> Result<NoteState, FenrisError> {
+    let note_state = lock(&state.state)?;
 
     if note_state.note_name.trim().is_empty() {
-        return Err("Note name cannot be empty".to_string());
+        return Err(FenrisError::validation("note.name_empty", &[]));
     }
 
     Ok(note_state.clone())
 }
 
 #[command]
-pub fn reset_note_state(state: State<NoteManager>) -> Result<NoteState, String> {
-    let mut note_state = state.state.lock().unwrap();
+pub fn reset_note_state(state: State<NoteManager>) -> Result<NoteState, FenrisError> {
+    let mut note_state = lock(&state.state)?;
     *note_state = NoteState::default();
     Ok(note_state.clone())
 }
 
 #[command]
-pub fn get_note_state(state: State<NoteManager>) -> Result<NoteState, String> {
-    let note_state = state.state.lock().unwrap();
+pub fn get_note_state(state: State<NoteManager>) -> Result<NoteState, FenrisError> {
+    let note_state = lock(&state.state)?;
     Ok(note_state.clone())
-}
\ No newline at end of file
+}
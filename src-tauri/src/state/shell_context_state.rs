@@ -1,37 +1,151 @@
+use crate::error::{lock, FenrisError};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
-use tauri::{command, State};
+use tauri::{command, AppHandle, Manager, State};
+use tauri_plugin_store::StoreExt;
+
+const HISTORY_STORE: &str = "shell_history.json";
+const HISTORY_KEY: &str = "history";
+const MAX_HISTORY: usize = 1000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub command: String,
+    pub timestamp: String,
+    /// 0 when the command succeeded, 1 when it failed
+    pub exit_status: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ShellState {
-    pub history: Vec<String>,
+    /// Oldest first, entry n is shown as `n` by `history` and re-run with `!n`
+    pub history: Vec<HistoryEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryMatch {
+    /// 1-based history number
+    pub number: usize,
+    pub entry: HistoryEntry,
 }
 
-impl Default for ShellState {
-    fn default() -> Self {
-        Self {
-            history: Vec::new(),
+impl ShellState {
+    // Re-running a command moves it to the end instead of keeping a second copy
+    pub fn record(&mut self, command: &str, success: bool) {
+        let command = command.trim();
+        if command.is_empty() {
+            return;
+        }
+        self.history.retain(|entry| entry.command != command);
+        self.history.push(HistoryEntry {
+            command: command.to_string(),
+            timestamp: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            exit_status: if success { 0 } else { 1 },
+        });
+
+        if self.history.len() > MAX_HISTORY {
+            let overflow = self.history.len() - MAX_HISTORY;
+            self.history.drain(..overflow);
         }
     }
+
+    pub fn entry(&self, number: usize) -> Option<&HistoryEntry> {
+        number
+            .checked_sub(1)
+            .and_then(|index| self.history.get(index))
+    }
+
+    // Reverse incremental search: the newest entry containing `query` that comes before `before`
+    pub fn search(&self, query: &str, before: Option<usize>) -> Option<HistoryMatch> {
+        let query = query.to_lowercase();
+        let end = before
+            .map(|number| number.saturating_sub(1))
+            .unwrap_or(self.history.len())
+            .min(self.history.len());
+
+        self.history[..end]
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, entry)| entry.command.to_lowercase().contains(&query))
+            .map(|(index, entry)| HistoryMatch {
+                number: index + 1,
+                entry: entry.clone(),
+            })
+    }
 }
 
 pub struct ShellManager {
     pub state: Mutex<ShellState>,
 }
 
-#[command]
-pub fn set_vec_history(
-    set_history: Vec<String>,
-    state: State<ShellManager>,
-) -> Result<ShellState, String> {
-    let mut shell_state = state.state.lock().unwrap();
-    shell_state.history = set_history;
+// Called once at startup so history survives restarts
+pub fn load_shell_history(app: &AppHandle) -> Result<(), FenrisError> {
+    let store = app
+        .store(HISTORY_STORE)
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+    // A damaged history file should not keep the app from starting
+    let history: Vec<HistoryEntry> = store
+        .get(HISTORY_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
 
-    Ok(shell_state.clone())
+    let manager = app.state::<ShellManager>();
+    let mut shell_state = lock(&manager.state)?;
+    shell_state.history = history;
+    Ok(())
+}
+
+pub fn record_shell_history(
+    app: &AppHandle,
+    command: &str,
+    success: bool,
+) -> Result<(), FenrisError> {
+    let manager = app.state::<ShellManager>();
+    let mut shell_state = lock(&manager.state)?;
+    shell_state.record(command, success);
+    save_shell_history(app, &shell_state)
+}
+
+pub fn clear_shell_history(app: &AppHandle) -> Result<(), FenrisError> {
+    let manager = app.state::<ShellManager>();
+    let mut shell_state = lock(&manager.state)?;
+    shell_state.history.clear();
+    save_shell_history(app, &shell_state)
+}
+
+fn save_shell_history(app: &AppHandle, shell_state: &ShellState) -> Result<(), FenrisError> {
+    let store = app
+        .store(HISTORY_STORE)
+        .map_err(|e| FenrisError::io("history.save_failed", &[("error", &e)]))?;
+    let history = serde_json::to_value(&shell_state.history)
+        .map_err(|e| FenrisError::internal("history.serialize_failed", &[("error", &e)]))?;
+    store.set(HISTORY_KEY, history);
+    store
+        .save()
+        .map_err(|e| FenrisError::io("history.save_failed", &[("error", &e)]))?;
+    Ok(())
 }
 
 #[command]
-pub fn get_vec_history(state: State<ShellManager>) -> Result<ShellState, String> {
-    let shell_state = state.state.lock().unwrap();
+pub fn get_vec_history(state: State<ShellManager>) -> Result<ShellState, FenrisError> {
+    let shell_state = lock(&state.state)?;
     Ok(shell_state.clone())
-}
\ No newline at end of file
+}
+
+#[command]
+pub fn search_shell_history(
+    query: String,
+    before: Option<usize>,
+    state: State<ShellManager>,
+) -> Result<Option<HistoryMatch>, FenrisError> {
+    let shell_state = lock(&state.state)?;
+    Ok(shell_state.search(&query, before))
+}
+
+#[command]
+pub fn delete_shell_history(app: AppHandle) -> Result<ShellState, FenrisError> {
+    clear_shell_history(&app)?;
+    Ok(ShellState::default())
+}
@@ -1,19 +1,28 @@
+use crate::error::FenrisError;
+use crate::i18n::t;
+use crate::ollama::list_of_models;
 use serde_json::Value;
 use tauri::{command, AppHandle, Manager};
 use tauri_plugin_store::StoreBuilder;
-use crate::ollama::list_of_models;
 
 #[command]
-pub async fn store_and_set_ai_model(app: AppHandle, app_ai_model: String) -> Result<String, String> {
+pub async fn store_and_set_ai_model(
+    app: AppHandle,
+    app_ai_model: String,
+) -> Result<String, FenrisError> {
+    save_ai_model(&app, app_ai_model)
+}
+
+fn save_ai_model(app: &AppHandle, app_ai_model: String) -> Result<String, FenrisError> {
     let store_path = app
         .path()
         .app_config_dir()
-        .map_err(|e| format!("Failed to get config dir: {}", e))?
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
         .join("ai-config.json");
 
-    let store = StoreBuilder::new(&app.clone(), store_path)
+    let store = StoreBuilder::new(app, store_path)
         .build()
-        .map_err(|e| format!("Failed to build store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
 
     // Store the custom model
     store.set("ai-model", Value::String(app_ai_model.clone()));
@@ -31,32 +40,36 @@ pub async fn store_and_set_ai_model(app: AppHandle, app_ai_model: String) -> Res
     if !custom_models.contains(&app_ai_model) {
         let mut updated_models = custom_models;
         updated_models.push(app_ai_model.clone());
-        store.set("custom-models", Value::Array(
-            updated_models.into_iter().map(Value::String).collect()
-        ));
+        store.set(
+            "custom-models",
+            Value::Array(updated_models.into_iter().map(Value::String).collect()),
+        );
     }
 
     store
         .save()
-        .map_err(|e| format!("Failed to save store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))?;
 
-    Ok(format!("AI model '{}' saved successfully", app_ai_model))
+    Ok(t("shell.ai.model_saved", &[("model", &app_ai_model)]))
 }
 
 #[command]
-pub async fn handle_shell_ai_model_command(app: AppHandle, ai_model_arg: String) -> Result<String, String> {
+pub async fn handle_shell_ai_model_command(
+    app: AppHandle,
+    ai_model_arg: String,
+) -> Result<String, FenrisError> {
     let available_models = list_of_models();
 
     // Also check custom models from store
     let store_path = app
         .path()
         .app_config_dir()
-        .map_err(|e| format!("Failed to get config dir: {}", e))?
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
         .join("ai-config.json");
 
     let store = StoreBuilder::new(&app.clone(), store_path)
         .build()
-        .map_err(|e| format!("Failed to build store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
 
     let custom_models = match store.get("custom-models") {
         Some(Value::Array(models)) => models
@@ -68,23 +81,26 @@ pub async fn handle_shell_ai_model_command(app: AppHandle, ai_model_arg: String)
 
     // Check if model is available in either built-in or custom models
     if !available_models.contains(&ai_model_arg) && !custom_models.contains(&ai_model_arg) {
-        return Err(format!("Model '{}' is not available", ai_model_arg));
+        return Err(FenrisError::not_found(
+            "ai.model_unavailable",
+            &[("model", &ai_model_arg)],
+        ));
     }
 
-    store_and_set_ai_model(app, ai_model_arg).await
+    save_ai_model(&app, ai_model_arg)
 }
 
 #[command]
-pub async fn store_and_get_ai_model(app: AppHandle) -> Result<String, String> {
+pub async fn store_and_get_ai_model(app: AppHandle) -> Result<String, FenrisError> {
     let store_path = app
         .path()
         .app_config_dir()
-        .map_err(|e| format!("Failed to get config dir: {}", e))?
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
         .join("ai-config.json");
 
     let store = StoreBuilder::new(&app.clone(), store_path)
         .build()
-        .map_err(|e| format!("Failed to build store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
 
     match store.get("ai-model") {
         Some(Value::String(model)) => Ok(model.clone()),
@@ -98,18 +114,18 @@ pub async fn store_and_get_ai_model(app: AppHandle) -> Result<String, String> {
 }
 
 #[command]
-pub async fn get_available_models_with_custom(app: AppHandle) -> Result<Vec<String>, String> {
+pub async fn get_available_models_with_custom(app: AppHandle) -> Result<Vec<String>, FenrisError> {
     let mut models = list_of_models();
 
     let store_path = app
         .path()
         .app_config_dir()
-        .map_err(|e| format!("Failed to get config dir: {}", e))?
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
         .join("ai-config.json");
 
     let store = StoreBuilder::new(&app.clone(), store_path)
         .build()
-        .map_err(|e| format!("Failed to build store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
 
     if let Some(Value::Array(custom_models)) = store.get("custom-models") {
         for model in custom_models {
@@ -126,4 +142,4 @@ pub async fn get_available_models_with_custom(app: AppHandle) -> Result<Vec<Stri
     models.sort();
 
     Ok(models)
-}
\ No newline at end of file
+}
@@ -0,0 +1,345 @@
+use crate::error::FenrisError;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, SecondsFormat, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+use tauri::{command, AppHandle, Manager};
+use tauri_plugin_store::StoreBuilder;
+
+pub const DATE_SETTINGS_STORE: &str = "date-settings.json";
+const TIMEZONE_KEY: &str = "timezone";
+const LOCALE_KEY: &str = "locale";
+// Same languages as the interface translations
+pub const DATE_LOCALES: &[&str] = &["en", "fr", "de"];
+// Notes, folders and the shell history store their dates in UTC with this pattern
+pub const STORED_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timezone {
+    /// Follows the system timezone, daylight saving time included
+    Local,
+    /// IANA timezone such as "Europe/Berlin", daylight saving time included
+    Named(Tz),
+    Fixed(FixedOffset),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateStyle {
+    Date,
+    Time,
+    DateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct DateSettings {
+    pub timezone: Timezone,
+    pub locale: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DateSettingsInfo {
+    /// "local", "UTC", an IANA name such as "Europe/Berlin" or a fixed offset such as "+02:00"
+    pub timezone: String,
+    pub locale: String,
+    /// IANA name reported by the system, e.g. "Europe/Paris"
+    pub detected_timezone: String,
+    pub utc_offset: String,
+    pub now: String,
+}
+
+impl Default for DateSettings {
+    fn default() -> Self {
+        Self {
+            timezone: Timezone::Local,
+            locale: DATE_LOCALES[0],
+        }
+    }
+}
+
+impl Timezone {
+    // Accepts "local", "UTC", IANA names such as "Europe/Berlin", and "+02:00", "-0530", "+2" or "UTC+2"
+    pub fn parse(value: &str) -> Result<Self, FenrisError> {
+        let value = value.trim();
+        let invalid = || FenrisError::validation("date.invalid_timezone", &[("timezone", &value)]);
+        if value.eq_ignore_ascii_case("local") {
+            return Ok(Timezone::Local);
+        }
+        if value.eq_ignore_ascii_case("utc") || value.eq_ignore_ascii_case("z") {
+            return Ok(Timezone::Fixed(Utc.fix()));
+        }
+        if let Ok(tz) = value.parse::<Tz>() {
+            return Ok(Timezone::Named(tz));
+        }
+
+        let offset = value
+            .strip_prefix("UTC")
+            .or_else(|| value.strip_prefix("utc"))
+            .unwrap_or(value);
+        let (sign, rest) = match offset.chars().next() {
+            Some('+') => (1, &offset[1..]),
+            Some('-') => (-1, &offset[1..]),
+            _ => return Err(invalid()),
+        };
+        let (hours, minutes) = match rest.split_once(':') {
+            Some((hours, minutes)) => (hours, minutes),
+            None if rest.len() == 4 => rest.split_at(2),
+            None => (rest, "0"),
+        };
+        let hours: i32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+        if hours > 14 || minutes > 59 {
+            return Err(invalid());
+        }
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+            .map(Timezone::Fixed)
+            .ok_or_else(invalid)
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Timezone::Local => String::from("local"),
+            Timezone::Named(tz) => tz.name().to_string(),
+            Timezone::Fixed(offset) if offset.local_minus_utc() == 0 => String::from("UTC"),
+            Timezone::Fixed(offset) => offset.to_string(),
+        }
+    }
+
+    fn offset_at(&self, utc: &NaiveDateTime) -> FixedOffset {
+        match self {
+            Timezone::Local => Local.offset_from_utc_datetime(utc).fix(),
+            Timezone::Named(tz) => tz.offset_from_utc_datetime(utc).fix(),
+            Timezone::Fixed(offset) => *offset,
+        }
+    }
+}
+
+impl DateStyle {
+    pub fn parse(value: &str) -> Result<Self, FenrisError> {
+        match value {
+            "date" => Ok(DateStyle::Date),
+            "time" => Ok(DateStyle::Time),
+            "datetime" => Ok(DateStyle::DateTime),
+            _ => Err(FenrisError::validation(
+                "date.invalid_style",
+                &[("style", &value)],
+            )),
+        }
+    }
+}
+
+impl DateSettings {
+    // Unknown values fall back to the defaults, so a hand-edited store cannot break date output
+    pub fn from_values(timezone: Option<&str>, locale: Option<&str>) -> Self {
+        let defaults = Self::default();
+        Self {
+            timezone: timezone
+                .and_then(|timezone| Timezone::parse(timezone).ok())
+                .unwrap_or(defaults.timezone),
+            locale: locale.and_then(date_locale).unwrap_or(defaults.locale),
+        }
+    }
+
+    pub fn pattern(&self, style: DateStyle) -> &'static str {
+        match (self.locale, style) {
+            ("fr", DateStyle::Date) => "%d/%m/%Y",
+            ("fr", DateStyle::DateTime) => "%d/%m/%Y %H:%M:%S",
+            ("de", DateStyle::Date) => "%d.%m.%Y",
+            ("de", DateStyle::DateTime) => "%d.%m.%Y %H:%M:%S",
+            ("fr" | "de", DateStyle::Time) => "%H:%M:%S",
+            (_, DateStyle::Date) => "%m/%d/%Y",
+            (_, DateStyle::Time) => "%I:%M:%S %p",
+            (_, DateStyle::DateTime) => "%m/%d/%Y %I:%M:%S %p",
+        }
+    }
+
+    pub fn to_timezone(&self, utc: DateTime<Utc>) -> DateTime<FixedOffset> {
+        utc.with_timezone(&self.timezone.offset_at(&utc.naive_utc()))
+    }
+
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.to_timezone(Utc::now())
+    }
+
+    pub fn format(&self, utc: DateTime<Utc>, style: DateStyle) -> String {
+        self.to_timezone(utc)
+            .format(self.pattern(style))
+            .to_string()
+    }
+
+    // Values that are not a known date format are shown as stored
+    pub fn format_stored(&self, stored: &str, style: DateStyle) -> String {
+        match parse_stored_date(stored) {
+            Some(utc) => self.format(utc, style),
+            None => stored.to_string(),
+        }
+    }
+}
+
+// Presets for the shell `date` command, anything containing % is used as a strftime pattern
+pub fn format_date_with(settings: &DateSettings, format: &str) -> Result<String, FenrisError> {
+    let now = settings.now();
+    match format.trim() {
+        "" | "datetime" => Ok(now
+            .format(settings.pattern(DateStyle::DateTime))
+            .to_string()),
+        "date" => Ok(now.format(settings.pattern(DateStyle::Date)).to_string()),
+        "time" => Ok(now.format(settings.pattern(DateStyle::Time)).to_string()),
+        "iso" => Ok(now.to_rfc3339_opts(SecondsFormat::Secs, false)),
+        "rfc2822" => Ok(now.to_rfc2822()),
+        "utc" => Ok(now
+            .with_timezone(&Utc)
+            .format(STORED_DATE_FORMAT)
+            .to_string()),
+        "unix" => Ok(now.timestamp().to_string()),
+        pattern if pattern.contains('%') => {
+            // Formatting an invalid pattern panics, so it is checked first
+            let items: Vec<Item> = StrftimeItems::new(pattern).collect();
+            if items.iter().any(|item| matches!(item, Item::Error)) {
+                return Err(FenrisError::validation(
+                    "date.invalid_format",
+                    &[("format", &pattern)],
+                ));
+            }
+            Ok(now.format_with_items(items.into_iter()).to_string())
+        }
+        other => Err(FenrisError::validation(
+            "date.unknown_format",
+            &[("format", &other)],
+        )),
+    }
+}
+
+pub fn parse_stored_date(stored: &str) -> Option<DateTime<Utc>> {
+    let stored = stored.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(stored) {
+        return Some(date.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(stored, STORED_DATE_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(stored, "%Y-%m-%dT%H:%M:%S%.f"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(stored, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        })
+        .ok()
+        .map(|date| Utc.from_utc_datetime(&date))
+}
+
+// Accepts i18next codes such as "fr-FR"
+pub fn date_locale(code: &str) -> Option<&'static str> {
+    let language = code.split(['-', '_']).next().unwrap_or_default();
+    DATE_LOCALES
+        .iter()
+        .find(|locale| locale.eq_ignore_ascii_case(language))
+        .copied()
+}
+
+pub fn detected_timezone() -> String {
+    iana_time_zone::get_timezone().unwrap_or_else(|_| String::from("unknown"))
+}
+
+// Used by fenris-cli, which reads the store file the app writes without an AppHandle
+pub fn load_date_settings_file(path: &Path) -> DateSettings {
+    let values: Map<String, Value> = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    DateSettings::from_values(
+        values.get(TIMEZONE_KEY).and_then(Value::as_str),
+        values.get(LOCALE_KEY).and_then(Value::as_str),
+    )
+}
+
+pub fn load_date_settings(app: &AppHandle) -> Result<DateSettings, FenrisError> {
+    let store_path = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
+        .join(DATE_SETTINGS_STORE);
+
+    let store = StoreBuilder::new(app, store_path)
+        .build()
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+
+    let timezone = store.get(TIMEZONE_KEY);
+    let locale = store.get(LOCALE_KEY);
+    Ok(DateSettings::from_values(
+        timezone.as_ref().and_then(Value::as_str),
+        locale.as_ref().and_then(Value::as_str),
+    ))
+}
+
+fn save_date_setting(app: &AppHandle, key: &str, value: String) -> Result<(), FenrisError> {
+    let store_path = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
+        .join(DATE_SETTINGS_STORE);
+
+    let store = StoreBuilder::new(app, store_path)
+        .build()
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
+
+    store.set(key, Value::String(value));
+
+    store
+        .save()
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))
+}
+
+// Returns the normalized name, "local" goes back to the system timezone
+pub fn save_date_timezone(app: &AppHandle, timezone: &str) -> Result<String, FenrisError> {
+    let name = Timezone::parse(timezone)?.name();
+    save_date_setting(app, TIMEZONE_KEY, name.clone())?;
+    Ok(name)
+}
+
+pub fn save_date_locale(app: &AppHandle, locale: &str) -> Result<&'static str, FenrisError> {
+    let code = date_locale(locale).ok_or_else(|| {
+        FenrisError::validation(
+            "date.unknown_locale",
+            &[("locale", &locale), ("available", &DATE_LOCALES.join(", "))],
+        )
+    })?;
+    save_date_setting(app, LOCALE_KEY, code.to_string())?;
+    Ok(code)
+}
+
+#[command]
+pub async fn get_date_settings(app: AppHandle) -> Result<DateSettingsInfo, FenrisError> {
+    let settings = load_date_settings(&app)?;
+    let now = settings.now();
+    Ok(DateSettingsInfo {
+        timezone: settings.timezone.name(),
+        locale: settings.locale.to_string(),
+        detected_timezone: detected_timezone(),
+        utc_offset: now.offset().to_string(),
+        now: now
+            .format(settings.pattern(DateStyle::DateTime))
+            .to_string(),
+    })
+}
+
+#[command]
+pub async fn set_date_timezone(app: AppHandle, timezone: String) -> Result<String, FenrisError> {
+    save_date_timezone(&app, &timezone)
+}
+
+#[command]
+pub async fn set_date_locale(app: AppHandle, locale: String) -> Result<String, FenrisError> {
+    Ok(save_date_locale(&app, &locale)?.to_string())
+}
+
+// Formats a date read from the database for display, e.g. note metadata
+#[command]
+pub async fn format_stored_date(
+    app: AppHandle,
+    value: String,
+    style: Option<String>,
+) -> Result<String, FenrisError> {
+    let style = DateStyle::parse(style.as_deref().unwrap_or("datetime"))?;
+    Ok(load_date_settings(&app)?.format_stored(&value, style))
+}
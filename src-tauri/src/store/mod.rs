@@ -1,7 +1,9 @@
 mod ai_shell_store;
+mod date_settings_store;
 mod theme_provider;
 pub mod theme_store;
 
 pub use ai_shell_store::*;
+pub use date_settings_store::*;
 pub use theme_provider::*;
 pub use theme_store::*;
@@ -1,75 +1,76 @@
-use std::collections::HashMap;
+use crate::error::FenrisError;
+use crate::i18n::t;
 use serde_json::Value;
 use tauri::{command, AppHandle, Manager};
 use tauri_plugin_store::StoreBuilder;
 
+const APP_THEMES: [&str; 3] = ["light", "dark", "nord"];
+
 #[command]
-pub async fn store_and_set_theme(app: AppHandle, app_theme: String) -> Result<String, String> {
-    let valid_themes = vec!["light", "dark", "nord"];
+pub async fn store_and_set_theme(app: AppHandle, app_theme: String) -> Result<String, FenrisError> {
+    save_app_theme(&app, app_theme)
+}
 
-    if !valid_themes.contains(&app_theme.as_str()) {
-        return Err(format!("Invalid theme: {}. Available themes: {}", app_theme, valid_themes.join(", ")));
+fn save_app_theme(app: &AppHandle, app_theme: String) -> Result<String, FenrisError> {
+    if !APP_THEMES.contains(&app_theme.as_str()) {
+        return Err(FenrisError::validation(
+            "theme.invalid",
+            &[("theme", &app_theme), ("available", &APP_THEMES.join(", "))],
+        ));
     }
 
     let store_path = app
         .path()
         .app_config_dir()
-        .map_err(|e| format!("Failed to get config dir: {}", e))?
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
         .join("store-theme.json");
 
-    let store = StoreBuilder::new(&app.clone(), store_path)
+    let store = StoreBuilder::new(app, store_path)
         .build()
-        .map_err(|e| format!("Failed to build store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
 
     store.set("app-theme", Value::String(app_theme.clone()));
 
     store
         .save()
-        .map_err(|e| format!("Failed to save store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))?;
 
     Ok(app_theme)
 }
 
+// The reply names the theme in the active locale, e.g. "Theme set to Dark"
 #[command]
-pub async fn handle_shell_theme_command(app: AppHandle, theme_arg: String) -> Result<String, String> {
-    let theme_map: HashMap<&str, &str> = [
-        ("light", "light"),
-        ("dark", "dark"),
-        ("nord", "nord"),
-    ].iter().cloned().collect();
-
+pub async fn handle_shell_theme_command(
+    app: AppHandle,
+    theme_arg: String,
+) -> Result<String, FenrisError> {
     let normalized_theme = theme_arg.to_lowercase();
 
-    match theme_map.get(normalized_theme.as_str()) {
+    match APP_THEMES.iter().find(|theme| **theme == normalized_theme) {
         Some(&theme) => {
-            store_and_set_theme(app, theme.to_string()).await?;
+            save_app_theme(&app, theme.to_string())?;
 
-            let message = match theme {
-                "light" => "Theme auf Hell gesetzt",
-                "dark" => "Theme auf Dunkel gesetzt",
-                "nord" => "Theme auf Nord gesetzt",
-                _ => "Theme geändert"
-            };
-            Ok(message.to_string())
-        }
-        None => {
-            let available: Vec<&str> = theme_map.values().copied().collect();
-            Err(format!("Ungültiges Theme: {}. Verfügbar: {}", theme_arg, available.join(", ")))
+            let name = t(&format!("shell.theme.names.{}", theme), &[]);
+            Ok(t("shell.theme.set", &[("theme", &name)]))
         }
+        None => Err(FenrisError::validation(
+            "theme.invalid",
+            &[("theme", &theme_arg), ("available", &APP_THEMES.join(", "))],
+        )),
     }
 }
 
 #[command]
-pub async fn store_and_get_theme(app: AppHandle) -> Result<String, String> {
+pub async fn store_and_get_theme(app: AppHandle) -> Result<String, FenrisError> {
     let store_path = app
         .path()
         .app_config_dir()
-        .map_err(|e| format!("Failed to get config dir: {}", e))?
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
         .join("store-theme.json");
 
     let store = StoreBuilder::new(&app.clone(), store_path)
         .build()
-        .map_err(|e| format!("Failed to build store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
 
     match store.get("app-theme") {
         Some(Value::String(theme)) => Ok(theme.clone()),
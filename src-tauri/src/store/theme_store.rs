@@ -1,42 +1,47 @@
+use crate::error::FenrisError;
 use serde_json::Value;
 use tauri::{command, AppHandle, Manager};
 use tauri_plugin_store::StoreBuilder;
 
 #[command]
-pub async fn set_theme(app: AppHandle, code_theme: String) -> Result<(), String> {
+pub async fn set_theme(app: AppHandle, code_theme: String) -> Result<(), FenrisError> {
+    save_code_theme(&app, code_theme)
+}
+
+pub fn save_code_theme(app: &AppHandle, code_theme: String) -> Result<(), FenrisError> {
     let store_path = app
         .path()
         .app_config_dir()
-        .map_err(|e| format!("Failed to get config dir: {}", e))?
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
         .join("theme.json");
 
-    let store = StoreBuilder::new(&app.clone(), store_path)
+    let store = StoreBuilder::new(app, store_path)
         .build()
-        .map_err(|e| format!("Failed to build store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
 
     store.set("code-theme", Value::String(code_theme));
 
     store
         .save()
-        .map_err(|e| format!("Failed to save store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.save_failed", &[("error", &e)]))?;
 
     Ok(())
 }
 
 #[command]
-pub async fn get_theme(app: AppHandle) -> Result<String, String> {
+pub async fn get_theme(app: AppHandle) -> Result<String, FenrisError> {
     let store_path = app
         .path()
         .app_config_dir()
-        .map_err(|e| format!("Failed to get config dir: {}", e))?
+        .map_err(|e| FenrisError::io("store.config_dir_failed", &[("error", &e)]))?
         .join("theme.json");
 
     let store = StoreBuilder::new(&app.clone(), store_path)
         .build()
-        .map_err(|e| format!("Failed to build store: {}", e))?;
+        .map_err(|e| FenrisError::io("store.open_failed", &[("error", &e)]))?;
 
     match store.get("code-theme") {
         Some(Value::String(theme)) => Ok(theme.clone()),
         _ => Ok("atomDark".to_string()),
     }
-}
\ No newline at end of file
+}
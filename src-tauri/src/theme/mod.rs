@@ -1,3 +1,3 @@
 mod theme_lists;
-pub use theme_lists::*;
 pub use crate::store::theme_store::*;
+pub use theme_lists::*;
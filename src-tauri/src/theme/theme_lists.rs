@@ -1,3 +1,4 @@
+use crate::error::FenrisError;
 use serde::{Deserialize, Serialize};
 use tauri::command;
 
@@ -9,7 +10,7 @@ pub struct Theme {
 }
 
 #[command]
-pub fn list_of_themes() -> Result<Vec<Theme>, String> {
+pub fn list_of_themes() -> Result<Vec<Theme>, FenrisError> {
     let themes = vec![
         Theme {
             name: "nord",
@@ -59,4 +60,4 @@ pub fn list_of_themes() -> Result<Vec<Theme>, String> {
     ];
 
     Ok(themes)
-}
\ No newline at end of file
+}
@@ -1,3 +1,4 @@
+use crate::error::FenrisError;
 use tauri::{command, AppHandle};
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
 
@@ -9,7 +10,7 @@ pub fn delete_folder_dialog(
     confirmation: String,
     cancellation: String,
     folder_name: String,
-) -> Result<bool, String> {
+) -> Result<bool, FenrisError> {
     let delete_dialog = app
         .dialog()
         .message(format!("{} \"{}\"?", message, folder_name))
@@ -29,7 +30,7 @@ pub fn delete_single_note_dialog(
     title: String,
     confirmation: String,
     cancellation: String,
-) -> Result<bool, String> {
+) -> Result<bool, FenrisError> {
     let delete_dialog = app
         .dialog()
         .message(format!("{}?", message))
@@ -1,11 +1,13 @@
-use fs::{read_to_string, write};
+use crate::error::FenrisError;
+use fs::{metadata, read_to_string, write};
+use serde::Serialize;
 use std::fs;
-use tauri::{command, AppHandle};
 use tauri::async_runtime::spawn_blocking;
+use tauri::{command, AppHandle};
 use tauri_plugin_dialog::DialogExt;
 
 #[command]
-pub async fn pick_json_file(app: AppHandle) -> Result<String, String> {
+pub async fn pick_json_file(app: AppHandle) -> Result<String, FenrisError> {
     let result = spawn_blocking(move || {
         app.dialog()
             .file()
@@ -13,37 +15,84 @@ pub async fn pick_json_file(app: AppHandle) -> Result<String, String> {
             .blocking_pick_file()
     })
     .await
-    .map_err(|e| format!("Failed to spawn blocking task: {}", e))?;
+    .map_err(|e| FenrisError::internal("task.spawn_failed", &[("error", &e)]))?;
 
     match result {
         Some(file_path) => {
             let path_string = file_path.to_string();
-            let content =
-                read_to_string(&path_string).map_err(|e| format!("Failed to read file: {}", e))?;
+            let content = read_to_string(&path_string)
+                .map_err(|e| FenrisError::io("file.read_failed", &[("error", &e)]))?;
             Ok(content)
         }
-        None => Err("No file selected".to_string()),
+        None => Err(FenrisError::validation("file.none_selected", &[])),
     }
 }
 
+#[derive(Serialize)]
+pub struct PickedJsonFile {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+// Returns the path without reading the file, so large files can be indexed instead
 #[command]
-pub async fn save_json_as_file(app: AppHandle, json_string: String) -> Result<(), String> {
+pub async fn pick_json_file_path(app: AppHandle) -> Result<PickedJsonFile, FenrisError> {
     let result = spawn_blocking(move || {
         app.dialog()
             .file()
             .add_filter("JSON File", &["json"])
+            .blocking_pick_file()
+    })
+    .await
+    .map_err(|e| FenrisError::internal("task.spawn_failed", &[("error", &e)]))?;
+
+    match result {
+        Some(file_path) => {
+            let path_string = file_path.to_string();
+            let size_bytes = metadata(&path_string)
+                .map_err(|e| FenrisError::io("file.read_failed", &[("error", &e)]))?
+                .len();
+            Ok(PickedJsonFile {
+                path: path_string,
+                size_bytes,
+            })
+        }
+        None => Err(FenrisError::validation("file.none_selected", &[])),
+    }
+}
+
+// `format` picks the file filter for converted output, e.g. "csv", "yaml", "toml" or "xml"
+#[command]
+pub async fn save_json_as_file(
+    app: AppHandle,
+    json_string: String,
+    format: Option<String>,
+) -> Result<(), FenrisError> {
+    let (filter_name, extensions): (&str, &[&str]) = match format.as_deref() {
+        Some("csv") => ("CSV File", &["csv"]),
+        Some("yaml") => ("YAML File", &["yaml", "yml"]),
+        Some("toml") => ("TOML File", &["toml"]),
+        Some("xml") => ("XML File", &["xml"]),
+        _ => ("JSON File", &["json"]),
+    };
+
+    let result = spawn_blocking(move || {
+        app.dialog()
+            .file()
+            .add_filter(filter_name, extensions)
             .blocking_save_file()
     })
     .await
-    .map_err(|e| format!("Failed to spawn blocking task: {}", e))?;
+    .map_err(|e| FenrisError::internal("task.spawn_failed", &[("error", &e)]))?;
 
     match result {
         Some(file_path) => {
             let path_string = file_path.to_string();
             // Write the JSON content to the file
-            write(&path_string, json_string).map_err(|e| format!("Failed to write file: {}", e))?;
+            write(&path_string, json_string)
+                .map_err(|e| FenrisError::io("file.write_failed", &[("error", &e)]))?;
             Ok(())
         }
-        None => Err(String::from("No file selected")),
+        None => Err(FenrisError::validation("file.none_selected", &[])),
     }
 }
@@ -1,5 +1,5 @@
-mod file_picker;
 mod dialogs;
+mod file_picker;
 
+pub use dialogs::*;
 pub use file_picker::*;
-pub use dialogs::*;
\ No newline at end of file
@@ -4,6 +4,7 @@ import {invoke} from "@tauri-apps/api/core";
 import AiShellSidebar from "./AiShellSidebar.jsx";
 import AiShellChatArea from "./AiShellChatArea.jsx";
 import {useTranslation} from "react-i18next";
+import {errorMessage} from "../../data/FenrisError.js";
 
 export default function AiShellPopup() {
     const {showAiShell, setShowAiShell, prompt, setPrompt} = useAiShell();
@@ -168,7 +169,7 @@ export default function AiShellPopup() {
             };
             setMessages(prev => [...prev, aiMessage]);
         } catch (err) {
-            setError(t("aiChatbot.errors.apiError", { error: err }));
+            setError(t("aiChatbot.errors.apiError", { error: errorMessage(err) }));
             console.error("Ollama API error:", err);
         } finally {
             setLoading(false);
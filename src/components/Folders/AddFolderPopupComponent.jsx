@@ -4,6 +4,7 @@ import Database from '@tauri-apps/plugin-sql';
 import {useState, useEffect} from "react";
 import {invoke} from "@tauri-apps/api/core";
 import {useTranslation} from 'react-i18next';
+import {ErrorKind, errorMessage, isErrorKind} from "../../data/FenrisError.js";
 
 export default function AddFolderPopupComponent({isPopupClosed}) {
     const {t} = useTranslation();
@@ -59,7 +60,9 @@ export default function AddFolderPopupComponent({isPopupClosed}) {
             setFolderState(prev => ({
                 ...prev,
                 showError: true,
-                errorMessage: t('addFolderPopup.errors.folderNameEmpty')
+                errorMessage: isErrorKind(error, ErrorKind.VALIDATION)
+                    ? t('addFolderPopup.errors.folderNameEmpty')
+                    : errorMessage(error)
             }));
             return;
         }
@@ -77,16 +80,18 @@ export default function AddFolderPopupComponent({isPopupClosed}) {
             await invoke("reset_folder_state");
             isPopupClosed();
         } catch (e) {
-            const errorMessage = e.toString().includes('UNIQUE') ||
-            e.toString().includes('unique') ||
-            e.toString().includes('duplicate')
-                ? t('addFolderPopup.errors.folderNameExists')
-                : t('addFolderPopup.errors.createFailed', {error: e.toString()});
+            const message = errorMessage(e);
+            const isDuplicate = isErrorKind(e, ErrorKind.CONFLICT) ||
+            message.includes('UNIQUE') ||
+            message.includes('unique') ||
+            message.includes('duplicate');
 
             setFolderState(prev => ({
                 ...prev,
                 showError: true,
-                errorMessage
+                errorMessage: isDuplicate
+                    ? t('addFolderPopup.errors.folderNameExists')
+                    : t('addFolderPopup.errors.createFailed', {error: message})
             }));
         } finally {
             setFolderState(prev => ({...prev, isLoading: false}));
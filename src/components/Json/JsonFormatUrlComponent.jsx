@@ -1,6 +1,7 @@
 import {invoke} from "@tauri-apps/api/core";
 import {useState, useEffect, useRef} from "react";
 import {useTranslation} from "react-i18next";
+import {errorMessage} from "../../data/FenrisError.js";
 import DaisyToast from "../DaisyToast.jsx";
 import SearchJsonComponent from "./SearchJsonComponent.jsx";
 import {TiCloudStorageOutline} from "react-icons/ti";
@@ -73,9 +74,10 @@ export default function JsonFormatUrlComponent() {
 
         setSearchLoading(true);
         try {
-            const result = await invoke("perform_search", {
-                jsonResponse: response,
-                searchTerm: searchTerm
+            const result = await invoke("search_text", {
+                text: response,
+                searchTerm: searchTerm,
+                options: { regex: true, case_sensitive: true }
             });
 
             setSearchResults(result.matches || []);
@@ -128,7 +130,7 @@ export default function JsonFormatUrlComponent() {
             setIsEditing(false);
             showToast(t('jsonFormatter.toast.fetchSuccess'));
         } catch (error) {
-            setResponse(`${t('jsonFormatter.errors.error')}: ${error}`);
+            setResponse(`${t('jsonFormatter.errors.error')}: ${errorMessage(error)}`);
             setOutputLength(0);
             showToast(t('jsonFormatter.toast.fetchFailed'));
         } finally {
@@ -158,7 +160,7 @@ export default function JsonFormatUrlComponent() {
                     setCurrentMatchIndex(0);
                     showToast(t('jsonFormatter.toast.importSuccess'));
                 } catch (formatError) {
-                    setResponse(`${t('jsonFormatter.errors.formatError')}: ${formatError}\n\n${t('jsonFormatter.rawContent')}:\n${fileContent}`);
+                    setResponse(`${t('jsonFormatter.errors.formatError')}: ${errorMessage(formatError)}\n\n${t('jsonFormatter.rawContent')}:\n${fileContent}`);
                     setOutputLength(fileContent.length);
                     setIsEditing(true);
                     showToast(t('jsonFormatter.toast.importInvalid'));
@@ -167,7 +169,7 @@ export default function JsonFormatUrlComponent() {
                 showToast(t('jsonFormatter.toast.noContent'));
             }
         } catch (error) {
-            showToast(t('jsonFormatter.toast.importError', {error}));
+            showToast(t('jsonFormatter.toast.importError', {error: errorMessage(error)}));
         }
     };
 
@@ -185,7 +187,7 @@ export default function JsonFormatUrlComponent() {
             setCurrentMatchIndex(0);
             showToast(t('jsonFormatter.toast.formatSuccess'));
         } catch (error) {
-            showToast(t('jsonFormatter.toast.formatError', {error}));
+            showToast(t('jsonFormatter.toast.formatError', {error: errorMessage(error)}));
         }
     };
 
@@ -203,7 +205,8 @@ export default function JsonFormatUrlComponent() {
         try {
             await invoke("save_json_file", {
                 key: saveFileName,
-                value: response
+                value: response,
+                sourceUrl: url.trim() || null
             });
 
             setSaveFileName("");
@@ -211,7 +214,7 @@ export default function JsonFormatUrlComponent() {
             await loadStoredFiles();
             showToast(t('jsonFormatter.toast.saveSuccess'));
         } catch (error) {
-            showToast(t('jsonFormatter.toast.saveError', {error}));
+            showToast(t('jsonFormatter.toast.saveError', {error: errorMessage(error)}));
         }
     };
 
@@ -281,7 +284,7 @@ export default function JsonFormatUrlComponent() {
             setCurrentMatchIndex(0);
             showToast(t('jsonFormatter.toast.fileLoaded'));
         } catch (error) {
-            showToast(t('jsonFormatter.toast.fileLoadError', {error}));
+            showToast(t('jsonFormatter.toast.fileLoadError', {error: errorMessage(error)}));
         }
     };
 
@@ -300,7 +303,7 @@ export default function JsonFormatUrlComponent() {
                 setSearchCount(0);
             }
         } catch (error) {
-            showToast(t('jsonFormatter.toast.fileDeleteError', {error}));
+            showToast(t('jsonFormatter.toast.fileDeleteError', {error: errorMessage(error)}));
         }
     };
 
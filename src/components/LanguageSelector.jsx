@@ -1,5 +1,6 @@
 import {useState, useEffect} from "react";
 import i18next from "i18next";
+import {invoke} from "@tauri-apps/api/core";
 import {useTranslation} from "react-i18next";
 
 const languageOptions = [
@@ -14,7 +15,11 @@ const LanguageSelector = () => {
 
     const handleLanguageChange = (code) => {
         setCurrentLanguage(code);
-        i18next.changeLanguage(code).then(r => "");
+        // Messages and dates shown by Rust follow the interface language
+        Promise.allSettled([
+            invoke("set_locale", {locale: code}),
+            invoke("set_date_locale", {locale: code})
+        ]).finally(() => i18next.changeLanguage(code));
     };
 
     useEffect(() => {
@@ -39,7 +39,7 @@ const useTextareaBehavior = () => {
 
 
 export default function MarkdownEditorComponent({selectedNote}) {
-    const {t} = useTranslation();
+    const {t, i18n} = useTranslation();
     const [markdown, setMarkdown] = useState("");
     const [title, setTitle] = useState("");
     const [viewMode, setViewMode] = useState("split");
@@ -49,6 +49,9 @@ export default function MarkdownEditorComponent({selectedNote}) {
     const [isSettingsOpen, setIsSettingsOpen] = useState(false);
     const [saveStatus, setSaveStatus] = useState("saved");
     const [lastSaveTime, setLastSaveTime] = useState(null);
+    // Dates formatted by Rust with the timezone and locale from the date settings
+    const [lastSavedLabel, setLastSavedLabel] = useState("");
+    const [modifiedLabel, setModifiedLabel] = useState("");
     const [theme, setTheme] = useState('atomDark');
     const [tags, setTags] = useState([]);
     const {textareaRef, handleKeyDown} = useTextareaBehavior();
@@ -154,6 +157,26 @@ export default function MarkdownEditorComponent({selectedNote}) {
         selectedNoteRef.current = selectedNote;
     }, [selectedNote]);
 
+    useEffect(() => {
+        if (!selectedNote?.date_modified) {
+            setModifiedLabel("");
+            return;
+        }
+        invoke("format_stored_date", {value: selectedNote.date_modified})
+            .then(setModifiedLabel)
+            .catch(() => setModifiedLabel(selectedNote.date_modified));
+    }, [selectedNote, i18n.language]);
+
+    useEffect(() => {
+        if (!lastSaveTime) {
+            setLastSavedLabel("");
+            return;
+        }
+        invoke("format_stored_date", {value: lastSaveTime.toISOString(), style: "time"})
+            .then(setLastSavedLabel)
+            .catch(() => setLastSavedLabel(lastSaveTime.toLocaleTimeString()));
+    }, [lastSaveTime, i18n.language]);
+
     useEffect(() => {
         const initializeViewMode = async () => {
             try {
@@ -537,7 +560,9 @@ export default function MarkdownEditorComponent({selectedNote}) {
                             </span>
                         </div>
                         <div className="text-xs opacity-75">
-                            {saveStatus === "saved" && lastSaveTime && t('markdownEditor.saveStatus.lastSaved', {time: lastSaveTime.toLocaleTimeString()})}
+                            {saveStatus === "saved" && lastSavedLabel
+                                ? t('markdownEditor.saveStatus.lastSaved', {time: lastSavedLabel})
+                                : modifiedLabel && t('markdownEditor.saveStatus.lastModified', {date: modifiedLabel})}
                         </div>
                     </div>
                 </div>
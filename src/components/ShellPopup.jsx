@@ -1,5 +1,5 @@
 import {useShell} from "../context/ShellContext.jsx";
-import {useEffect, useState} from "react";
+import {useEffect, useRef, useState} from "react";
 import {invoke} from "@tauri-apps/api/core";
 
 export default function ShellPopup() {
@@ -9,19 +9,21 @@ export default function ShellPopup() {
         command,
         setCommand,
         history,
+        prompt,
         handleCommandSubmit
     } = useShell();
-    const [shellName, setShellName] = useState("");
+    const [completions, setCompletions] = useState([]);
+    // Reverse incremental search started with Ctrl-R, null when not searching
+    const [search, setSearch] = useState(null);
+    // Position while walking the persisted history with the arrow keys
+    const [recall, setRecall] = useState(null);
+    const inputRef = useRef(null);
 
     useEffect(() => {
-        if (showShell) {
-            invoke("cli_design").then((result) => {
-                setShellName(result);
-            }).catch(() => {
-                setShellName("user@fenris:~$");
-            });
+        if (search === null) {
+            inputRef.current?.focus();
         }
-    }, [showShell]);
+    }, [search]);
 
     const handleOverlayClick = (e) => {
         if (e.target === e.currentTarget) {
@@ -35,6 +37,107 @@ export default function ShellPopup() {
         }
     };
 
+    const replaceRange = (start, end, text) => {
+        const chars = Array.from(command);
+        const next = [...chars.slice(0, start), ...Array.from(text), ...chars.slice(end)].join("");
+        const cursor = start + Array.from(text).length;
+        setCommand(next);
+        requestAnimationFrame(() => {
+            inputRef.current?.setSelectionRange(cursor, cursor);
+        });
+    };
+
+    const runSearch = async (query, before) => {
+        try {
+            const found = await invoke("search_shell_history", {query, before});
+            setSearch((current) => ({
+                query,
+                match: found || (query === current?.query ? current.match : null),
+                failed: !found
+            }));
+        } catch (error) {
+            setSearch({query, match: null, failed: true});
+        }
+    };
+
+    const recallEntry = async (step) => {
+        try {
+            const {history: entries} = await invoke("get_vec_history");
+            if (entries.length === 0) return;
+            const current = recall === null ? entries.length : recall;
+            const next = Math.min(Math.max(current + step, 0), entries.length);
+            setRecall(next === entries.length ? null : next);
+            setCommand(next === entries.length ? "" : entries[next].command);
+        } catch (error) {
+            setRecall(null);
+        }
+    };
+
+    // Ctrl-R searches older commands, Ctrl-G cancels and Enter copies the match into the input
+    const handleSearchKeyDown = (e) => {
+        if (e.ctrlKey && e.key === 'r') {
+            e.preventDefault();
+            runSearch(search.query, search.match?.number);
+        } else if (e.ctrlKey && e.key === 'g') {
+            e.preventDefault();
+            setSearch(null);
+        } else if (e.key === 'Enter') {
+            e.preventDefault();
+            if (search.match) setCommand(search.match.entry.command);
+            setSearch(null);
+        }
+    };
+
+    // Tab completes a single candidate, otherwise extends the shared prefix and lists the options
+    const handleInputKeyDown = async (e) => {
+        if (e.ctrlKey && e.key === 'r') {
+            e.preventDefault();
+            setCompletions([]);
+            setSearch({query: "", match: null, failed: false});
+            return;
+        }
+        if (e.key === 'ArrowUp' || e.key === 'ArrowDown') {
+            e.preventDefault();
+            await recallEntry(e.key === 'ArrowUp' ? -1 : 1);
+            return;
+        }
+        if (e.key !== 'Tab') {
+            if (completions.length > 0) setCompletions([]);
+            return;
+        }
+        e.preventDefault();
+
+        try {
+            const result = await invoke("complete_shell_input", {
+                line: command,
+                cursor: e.target.selectionStart
+            });
+            if (result.candidates.length === 1) {
+                replaceRange(result.start, result.end, result.candidates[0].value + " ");
+                setCompletions([]);
+            } else {
+                if (result.common_prefix) {
+                    replaceRange(result.start, result.end, result.common_prefix);
+                }
+                setCompletions(result.candidates);
+            }
+        } catch (error) {
+            setCompletions([]);
+        }
+    };
+
+    const handleInputChange = (e) => {
+        setCommand(e.target.value);
+        setCompletions([]);
+        setRecall(null);
+    };
+
+    const handleSubmit = (e) => {
+        setCompletions([]);
+        setRecall(null);
+        handleCommandSubmit(e);
+    };
+
     if (!showShell) return null;
 
     return (
@@ -57,7 +160,7 @@ export default function ShellPopup() {
                         <span className="text-gray-300 text-sm ml-2">Terminal</span>
                     </div>
                     <div className="text-gray-400 text-sm">
-                        {shellName}
+                        {prompt}
                     </div>
                 </div>
 
@@ -69,7 +172,7 @@ export default function ShellPopup() {
                             {/* Command Input Line */}
                             <div className="flex items-start">
                                 <span className="text-green-400 font-bold mr-2 flex-shrink-0">
-                                   {shellName}
+                                   {item.prompt || prompt}
                                 </span>
                                 <span className="text-gray-100">{item.command}</span>
                             </div>
@@ -86,13 +189,15 @@ export default function ShellPopup() {
                     {/* Current Input Line */}
                     <div className="flex items-start">
                         <span className="text-green-400 font-bold mr-2 flex-shrink-0">
-                            {shellName}
+                            {prompt}
                         </span>
-                        <form onSubmit={handleCommandSubmit} className="flex-1">
+                        <form onSubmit={handleSubmit} className={search ? "hidden" : "flex-1"}>
                             <input
+                                ref={inputRef}
                                 type="text"
                                 value={command}
-                                onChange={(e) => setCommand(e.target.value)}
+                                onChange={handleInputChange}
+                                onKeyDown={handleInputKeyDown}
                                 className="w-full bg-transparent text-gray-100 outline-none border-none caret-green-400"
                                 placeholder="Type a command..."
                                 autoFocus
@@ -101,7 +206,39 @@ export default function ShellPopup() {
                                 autoCapitalize="off"
                             />
                         </form>
+                        {search && (
+                            <div className="flex-1 flex text-gray-100">
+                                <span className="text-gray-400 flex-shrink-0">
+                                    {search.failed ? "(failed reverse-i-search)`" : "(reverse-i-search)`"}
+                                </span>
+                                <input
+                                    type="text"
+                                    value={search.query}
+                                    onChange={(e) => runSearch(e.target.value)}
+                                    onKeyDown={handleSearchKeyDown}
+                                    size={Math.max(search.query.length, 1)}
+                                    className="bg-transparent text-gray-100 outline-none border-none caret-green-400"
+                                    autoFocus
+                                    spellCheck="false"
+                                    autoComplete="off"
+                                    autoCapitalize="off"
+                                />
+                                <span className="text-gray-400 mr-2">':</span>
+                                <span className="truncate">{search.match?.entry.command}</span>
+                            </div>
+                        )}
                     </div>
+
+                    {/* Completion Candidates */}
+                    {completions.length > 0 && (
+                        <div className="mt-1 ml-6 flex flex-wrap gap-x-6 gap-y-1 text-gray-400">
+                            {completions.map((candidate) => (
+                                <span key={candidate.kind + candidate.label} title={candidate.description || ""}>
+                                    {candidate.label}
+                                </span>
+                            ))}
+                        </div>
+                    )}
                 </div>
 
                 {/* Status Bar */}
@@ -9,6 +9,7 @@ import {useEffect, useState} from "react";
 import Database from "@tauri-apps/plugin-sql";
 import {invoke} from "@tauri-apps/api/core";
 import {useTranslation} from 'react-i18next';
+import {errorMessage} from "../data/FenrisError.js";
 
 export default function SidePanel({onNoteSelect, selectedNote}) {
     const {t} = useTranslation();
@@ -47,7 +48,7 @@ export default function SidePanel({onNoteSelect, selectedNote}) {
             setShowError(false);
         } catch (e) {
             setShowError(true);
-            setErrorMessage(t('sidePanel.errors.loadFoldersFailed', {error: e.message}));
+            setErrorMessage(t('sidePanel.errors.loadFoldersFailed', {error: errorMessage(e)}));
         }
     }
 
@@ -60,7 +61,7 @@ export default function SidePanel({onNoteSelect, selectedNote}) {
             setShowError(false);
         } catch (e) {
             setShowError(true);
-            setErrorMessage(t('sidePanel.errors.loadNotesFailed', {error: e.message}));
+            setErrorMessage(t('sidePanel.errors.loadNotesFailed', {error: errorMessage(e)}));
         }
     }
 
@@ -5,6 +5,7 @@ import {useState, useEffect} from "react";
 import {invoke} from "@tauri-apps/api/core";
 import {MdOutlineEditNote} from "react-icons/md";
 import {useTranslation} from 'react-i18next';
+import {ErrorKind, errorMessage, isErrorKind} from "../../data/FenrisError.js";
 
 export default function AddNotePopupComponent({isPopupClosed}) {
     const {t} = useTranslation();
@@ -55,7 +56,9 @@ export default function AddNotePopupComponent({isPopupClosed}) {
             setNoteState(prev => ({
                 ...prev,
                 showError: true,
-                errorMessage: t('addNotePopup.errors.noteNameEmpty')
+                errorMessage: isErrorKind(error, ErrorKind.VALIDATION)
+                    ? t('addNotePopup.errors.noteNameEmpty')
+                    : errorMessage(error)
             }));
             return;
         }
@@ -63,7 +66,6 @@ export default function AddNotePopupComponent({isPopupClosed}) {
         setNoteState(prev => ({ ...prev, isLoading: true }));
 
         try {
-            const dateNow = await invoke("cli_date_without_hours");
             const db = await Database.load("sqlite:fenris_app_notes.db");
             const createNewNoteFromRust = await invoke("create_single_note", {
                 noteName: noteState.noteName.trim(),
@@ -78,9 +80,9 @@ export default function AddNotePopupComponent({isPopupClosed}) {
             setNoteState(prev => ({
                 ...prev,
                 showError: true,
-                errorMessage: e.message.includes("UNIQUE")
+                errorMessage: isErrorKind(e, ErrorKind.CONFLICT) || errorMessage(e).includes("UNIQUE")
                     ? t('addNotePopup.errors.noteNameExists')
-                    : t('addNotePopup.errors.createFailed', {error: e.message})
+                    : t('addNotePopup.errors.createFailed', {error: errorMessage(e)})
             }));
         } finally {
             setNoteState(prev => ({ ...prev, isLoading: false }));
@@ -4,6 +4,7 @@ import {FaEllipsisV} from "react-icons/fa";
 import Database from "@tauri-apps/plugin-sql";
 import {invoke} from "@tauri-apps/api/core";
 import {useTranslation} from 'react-i18next';
+import {errorMessage} from "../../data/FenrisError.js";
 
 export default function SingleNoteItemsMenuComponent({
                                                          note,
@@ -122,7 +123,7 @@ export default function SingleNoteItemsMenuComponent({
             setIsUpdateNotePopupOpen(false);
 
         } catch (e) {
-            showToast(t('singleNotes.errors.updateFailed', {error: e.message}), 'error');
+            showToast(t('singleNotes.errors.updateFailed', {error: errorMessage(e)}), 'error');
         } finally {
             setIsLoading(false);
         }
@@ -163,7 +164,7 @@ export default function SingleNoteItemsMenuComponent({
             }
 
         } catch (e) {
-            showToast(t('singleNotes.errors.deleteFailed', { error: e.message }), 'error');
+            showToast(t('singleNotes.errors.deleteFailed', { error: errorMessage(e) }), 'error');
         } finally {
             setIsLoading(false);
             handleMenuToggle();
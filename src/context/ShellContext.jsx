@@ -4,66 +4,32 @@ import {useNavigate} from 'react-router-dom';
 import {useTheme} from "../data/ThemeProvider.jsx";
 import {useTranslation} from "react-i18next";
 import i18next from "i18next";
-import {
-    AddFolderWithCli,
-    addNoteWithCli,
-    deleteFolderWithCli,
-    deleteNoteWithCli, getCommandDescription, getCommandExplanation, getVacuumFromDb,
-    updateFolderWithCli
-} from "./ShellHelpers.jsx";
+import {errorMessage} from "../data/FenrisError.js";
 
 const ShellContext = createContext();
 
 export function ShellProvider({children}) {
     const [showShell, setShowShell] = useState(false);
     const [command, setCommand] = useState("");
+    // Scrollback for this session, the command history itself is persisted by Rust
     const [history, setHistory] = useState([]);
-    const {theme, toggleTheme, changeTheme} = useTheme();
+    // Working folder changed by `cd`, shown in the prompt
+    const [cwd, setCwd] = useState("/");
+    const [prompt, setPrompt] = useState("");
+    const {changeTheme} = useTheme();
     const navigate = useNavigate();
     const {t} = useTranslation();
     const [currentLanguage, setCurrentLanguage] = useState(i18next.language);
 
     const handleLanguageChange = (code) => {
         setCurrentLanguage(code);
-        i18next.changeLanguage(code).then(r => "");
-    };
-
-    const getHistoryFromRust = async () => {
-        try {
-            const rustHistory = await invoke("get_vec_history");
-            const historyArray = rustHistory.history || rustHistory || [];
-            setHistory(historyArray);
-        } catch (error) {
-            setHistory([]);
-        }
-    };
-
-    const updateHistory = async (newHistory) => {
-        try {
-            const historyForRust = newHistory.map(item => {
-                if (typeof item === 'string') {
-                    return item;
-                } else {
-                    return JSON.stringify(item);
-                }
-            });
-
-            const result = await invoke("set_vec_history", {
-                history: historyForRust
-            });
-
-            const updatedHistory = result.history || result || [];
-            setHistory(updatedHistory);
-
-        } catch (error) {
-            setHistory(newHistory);
-        }
+        // Messages and dates shown by Rust follow the interface language
+        Promise.allSettled([
+            invoke("set_locale", {locale: code}),
+            invoke("set_date_locale", {locale: code})
+        ]).finally(() => i18next.changeLanguage(code));
     };
 
-    useEffect(() => {
-        getHistoryFromRust();
-    }, []);
-
     useEffect(() => {
         const handleKeyDown = (event) => {
             if (event.ctrlKey && event.key === 't') {
@@ -91,170 +57,104 @@ export function ShellProvider({children}) {
 
     const handleClearTerminal = (input) => {
         if ((input.ctrlKey && input.key === 'l') || input === "clear") {
-            updateHistory([]);
-        }
-    }
-
-    async function cliHelpCommand(explanation = null) {
-        try {
-            const result = await invoke("cli_help_command", {explanation});
-
-            if (explanation) {
-                return getCommandExplanation(result, t);
-            } else {
-                const commands = result.split(',');
-                return t('shell.availableCommands', {
-                    commands: commands.map(cmd => `  ${cmd.padEnd(20)} ${getCommandDescription(cmd, t)}`).join('\n')
-                });
-            }
-        } catch (error) {
-            return t('shell.error', {error});
-        }
-    }
-
-    async function cliDateNow() {
-        try {
-            return await invoke("cli_date_now");
-        } catch (error) {
-            return t('shell.error', {error});
-        }
-    }
-
-    async function cliCurrentDir() {
-        try {
-            return await invoke("cli_show_dir");
-        } catch (error) {
-            return t('shell.error', {error});
+            setHistory([]);
         }
     }
 
-    const processCommand = async (cmd) => {
-        const trimmedCmd = cmd.trim().toLowerCase();
-
-        if (trimmedCmd.startsWith('help ')) {
-            const specificCmd = trimmedCmd.substring(5).trim();
-            return await cliHelpCommand(specificCmd);
+    const applyShellAction = async (action) => {
+        switch (action.type) {
+            case 'navigate':
+                navigate(action.route);
+                setShowShell(false);
+                break;
+            case 'set_language':
+                handleLanguageChange(action.code);
+                break;
+            case 'reload_theme':
+                // Force theme reload by calling changeTheme with the current stored theme
+                await changeTheme(await invoke("store_and_get_theme"));
+                break;
+            case 'set_visibility':
+                setElementVisibility(action.target, action.visible);
+                break;
+            case 'open_note':
+                navigate('/home', {state: {openNote: {id: action.id, noteType: action.note_type}}});
+                setShowShell(false);
+                break;
+            case 'clear':
+                setHistory([]);
+                break;
+            case 'exit':
+                setShowShell(false);
+                break;
+            default:
+                break;
         }
+    };
 
-        if (trimmedCmd.startsWith('add folder ')) {
-            return await AddFolderWithCli(cmd, t);
+    const applyShellActions = async (actions) => {
+        for (const action of actions) {
+            await applyShellAction(action);
         }
+    };
 
-        if (trimmedCmd.startsWith('delete folder ')) {
-            await deleteFolderWithCli(cmd, t);
+    const setElementVisibility = (target, visible) => {
+        if (target === 'navbar') {
+            const navbar = document.querySelector('nav, aside, main, [class*="navbar"], [class*="sidebar"], [class*="page-margin-markdown"]');
+            if (navbar) {
+                navbar.style.display = visible ? 'block' : 'none';
+            }
+            document.documentElement.style.setProperty('--navbar-margin', visible ? '5rem' : '0px');
+            document.documentElement.style.setProperty('--markdown-margin', visible ? '350px' : '260px');
+        } else if (target === 'json-stats') {
+            const statsSection = document.querySelector('.json-stats');
+            if (statsSection) {
+                statsSection.style.display = visible ? 'block' : 'none';
+            }
         }
+    };
 
-        if (trimmedCmd.startsWith('update folder ')) {
-            await updateFolderWithCli(cmd, t)
-        }
+    useEffect(() => {
+        invoke("get_shell_cwd").then(setCwd).catch(() => {
+        });
+    }, []);
 
-        if (trimmedCmd.startsWith('add note ')) {
-            await addNoteWithCli(cmd, t);
-        }
+    useEffect(() => {
+        invoke("cli_design", {cwd}).then(setPrompt).catch(() => {
+            setPrompt(`user@fenris:${cwd}$ `);
+        });
+    }, [cwd]);
 
-        if (trimmedCmd.startsWith('delete note ')) {
-            await deleteNoteWithCli(cmd, t);
-        }
+    // The startup script runs once, its output shows up when the terminal is first opened.
+    // Rust starts in English, so it gets the interface language before printing anything
+    useEffect(() => {
+        invoke("set_locale", {locale: i18next.language}).catch(() => {
+        }).then(() => invoke("run_startup_script")).then(async (result) => {
+            if (!result) return;
+            await applyShellActions(result.actions);
+            setCwd(result.cwd);
+            setHistory(current => [...current, {
+                prompt,
+                command: result.line,
+                output: result.output,
+                timestamp: new Date().toISOString()
+            }]);
+        }).catch(() => {
+        });
+    }, []);
 
-        switch (trimmedCmd) {
-            case 'help':
-                return await cliHelpCommand();
-            case 'clear':
-                handleClearTerminal();
-                return "";
-            case 'date':
-                return await cliDateNow();
-            case 'exit':
-                setShowShell(false);
-                return "";
-            case 'version':
-                return t('shell.versionInfo');
-            case 'pwd':
-                return await cliCurrentDir();
-            case 'lang fr':
-                handleLanguageChange('fr');
-                return t('shell.success.languageChanged', {language: 'Français'});
-            case 'lang en':
-                handleLanguageChange('en');
-                return t('shell.success.languageChanged', {language: 'English'});
-            case 'lang de':
-                handleLanguageChange('de');
-                return t('shell.success.languageChanged', {language: 'Deutsch'});
-            case 'goto settings':
-                navigate('/settings');
-                setShowShell(false);
-                return t('shell.navigatingToSettings');
-            case 'goto json':
-                navigate('/json');
-                setShowShell(false);
-                return t('shell.navigatingToJson');
-            case 'goto home':
-                navigate('/home');
-                setShowShell(false);
-                return t('shell.navigatingToHome');
-            case 'goto ai':
-                navigate('/ai-chatbot');
-                setShowShell(false);
-                return t('shell.navigatingToAiChatbot');
-            case 'theme dark':
-            case 'theme light':
-            case 'theme nord':
-                const themeArg = trimmedCmd.substring(6).trim();
-                try {
-                    const result = await invoke("handle_shell_theme_command", {themeArg: themeArg});
-                    // Force theme reload by calling changeTheme with the current stored theme
-                    const currentTheme = await invoke("store_and_get_theme");
-                    await changeTheme(currentTheme);
-                    return result;
-                } catch (error) {
-                    return t('shell.error', {error: error.message});
-                }
-            case 'hide navbar':
-                const navbarToHide = document.querySelector('nav, aside, main, [class*="navbar"], [class*="sidebar"], [class*="page-margin-markdown"]');
-                if (navbarToHide) {
-                    navbarToHide.style.display = 'none';
-                }
-                document.documentElement.style.setProperty('--navbar-margin', '0px');
-                document.documentElement.style.setProperty('--markdown-margin', '260px');
-                return t('shell.success.navbarHidden');
-            case 'show navbar':
-                const navbarToShow = document.querySelector('nav, aside, main, [class*="navbar"], [class*="sidebar"], [class*="page-margin-markdown"]');
-                if (navbarToShow) {
-                    navbarToShow.style.display = 'block';
-                }
-                document.documentElement.style.setProperty('--navbar-margin', '5rem');
-                document.documentElement.style.setProperty('--markdown-margin', '350px');
-                return t('shell.success.navbarShown');
-            case 'hide json-stats':
-                const hideStatsSection = document.querySelector('.json-stats');
-                if (hideStatsSection) {
-                    hideStatsSection.style.display = 'none';
-                }
-                return t('shell.success.jsonStatsHidden');
-            case 'show json-stats':
-                const showStatsSection = document.querySelector('.json-stats');
-                if (showStatsSection) {
-                    showStatsSection.style.display = 'block';
-                }
-                return t('shell.success.jsonStatsShown');
-            case "add folder":
-                return t("shell.errors.addFolder.missingName");
-            case "delete folder":
-                return t("shell.errors.deleteFolder.missingName");
-            case "update folder":
-                return t("shell.errors.updateFolder.missingNames");
-            case "add note":
-                return t("shell.errors.updateFolder.missingNames");
-            case "vacuum":
-                await getVacuumFromDb();
-                return "The VACUUM was successfully executed.";
-            case "":
-                return "";
-            default:
-                if (trimmedCmd.startsWith('echo ')) {
-                    return trimmedCmd.substring(5);
-                }
-                return t('shell.commandNotFound', {command: cmd});
+    // Parsing and execution happen in Rust, the frontend only applies UI actions
+    const processCommand = async (cmd) => {
+        try {
+            const result = await invoke("run_shell_command", {line: cmd});
+            await applyShellActions(result.actions);
+            setCwd(result.cwd);
+            if (result.actions.some(action => action.type === 'clear')) {
+                return null;
+            }
+            return {line: result.line || cmd, output: result.output};
+        } catch (error) {
+            return {line: cmd, output: t('shell.error', {error: errorMessage(error)})};
         }
     };
 
@@ -262,16 +162,20 @@ export function ShellProvider({children}) {
         e.preventDefault();
         if (!command.trim()) return;
 
-        const outputText = await processCommand(command);
+        const result = await processCommand(command);
+        if (result === null) {
+            setCommand("");
+            return;
+        }
 
+        // Keeps the prompt the command was typed at, `cd` changes it for the next one
         const newHistoryEntry = {
-            command,
-            output: outputText,
+            prompt,
+            command: result.line,
+            output: result.output,
             timestamp: new Date().toISOString()
         };
-        const newHistory = [...history, newHistoryEntry];
-
-        await updateHistory(newHistory);
+        setHistory([...history, newHistoryEntry]);
 
         setCommand("");
     };
@@ -282,6 +186,7 @@ export function ShellProvider({children}) {
         command,
         setCommand,
         history,
+        prompt,
         handleCommandSubmit,
         processCommand
     };
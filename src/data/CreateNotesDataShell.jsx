@@ -1,5 +1,6 @@
 import {invoke} from "@tauri-apps/api/core";
 import Database from "@tauri-apps/plugin-sql";
+import {errorMessage} from "./FenrisError.js";
 
 export async function saveFolder(folderName) {
     try {
@@ -11,7 +12,7 @@ export async function saveFolder(folderName) {
         return {success: true};
     } catch (e) {
         console.log(e);
-        return {success: false, error: e.message};
+        return {success: false, error: errorMessage(e)};
     }
 }
 
@@ -30,7 +31,7 @@ export async function deleteFolder(folderId) {
         return {success: true};
     } catch (e) {
         console.error('Error deleting folder:', e);
-        return {success: false, error: e.message};
+        return {success: false, error: errorMessage(e)};
     }
 }
 
@@ -55,7 +56,7 @@ export async function deleteFolderByName(folderName) {
 
         return {success: true};
     } catch (e) {
-        return {success: false, error: e.message};
+        return {success: false, error: errorMessage(e)};
     }
 }
 
@@ -69,7 +70,7 @@ export async function updateFolderName(newFolderName, folderId) {
         await db.execute(updateFolderCommand);
         return {success: true};
     } catch (e) {
-        return {success: false, error: e.message};
+        return {success: false, error: errorMessage(e)};
     }
 }
 
@@ -95,7 +96,7 @@ export async function updateFolderByName(currentFolderName, newFolderName) {
         await db.execute(updateFolderCommand);
         return {success: true};
     } catch (e) {
-        return {success: false, error: e.message};
+        return {success: false, error: errorMessage(e)};
     }
 }
 
@@ -108,7 +109,7 @@ export async function saveNoteFromShell(noteName) {
         await db.execute(saveNoteFromRust);
         return {success: true};
     } catch (e) {
-        return {success: false, error: e.message};
+        return {success: false, error: errorMessage(e)};
     }
 }
 
@@ -129,6 +130,6 @@ export async function deleteNoteByName(noteName) {
 
         return {success: true};
     } catch (e) {
-        return {success: false, error: e.message};
+        return {success: false, error: errorMessage(e)};
     }
 }
\ No newline at end of file
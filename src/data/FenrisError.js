@@ -0,0 +1,21 @@
+// Rust commands reject with {kind, code, message, context}, the SQL plugin and JS code still throw strings or Errors
+export const ErrorKind = Object.freeze({
+    NOT_FOUND: "not_found",
+    VALIDATION: "validation",
+    CONFLICT: "conflict",
+    IO: "io",
+    NETWORK: "network",
+    DATABASE: "database",
+    INTERNAL: "internal",
+});
+
+export function errorMessage(error) {
+    if (typeof error === "string") {
+        return error;
+    }
+    return error?.message ?? String(error);
+}
+
+export function isErrorKind(error, kind) {
+    return error?.kind === kind;
+}
@@ -1,4 +1,7 @@
-import {useState} from "react";
+import {useEffect, useState} from "react";
+import {useLocation} from "react-router-dom";
+import {invoke} from "@tauri-apps/api/core";
+import Database from "@tauri-apps/plugin-sql";
 import {useTranslation} from "react-i18next";
 import SidePanel from "../components/SidePanel.jsx";
 import MarkdownEditorComponent from "../components/Markdown/MarkdownEditorComponent.jsx";
@@ -6,6 +9,27 @@ import MarkdownEditorComponent from "../components/Markdown/MarkdownEditorCompon
 export default function HomePage() {
     const {t} = useTranslation();
     const [selectedNote, setSelectedNote] = useState(null);
+    const location = useLocation();
+
+    // Notes opened from the shell with `note open`
+    useEffect(() => {
+        const openNote = location.state?.openNote;
+        if (!openNote) return;
+
+        const loadNote = async () => {
+            try {
+                const db = await Database.load("sqlite:fenris_app_notes.db");
+                const command = openNote.noteType === 'folder' ? "get_note_by_id_sqlite" : "get_single_note_by_id_sqlite";
+                const result = await db.select(await invoke(command, {noteId: openNote.id}));
+                if (result.length > 0) {
+                    setSelectedNote(result[0]);
+                }
+            } catch (e) {
+                setSelectedNote(null);
+            }
+        };
+        loadNote();
+    }, [location.state]);
 
     const handleNoteSelect = (note) => {
         setSelectedNote(note);